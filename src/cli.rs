@@ -0,0 +1,1157 @@
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use serde_json::json;
+use game::{
+    run_many::GamePool,
+    settings::GameSettingsBuilder,
+    shoe::{compare_shoes, print_shoe_comparison, ShoeKind, CSM_RESERVOIR},
+    shuffle::{OrderRetention, ShuffleProcedure, ShuffleStep},
+    script::Script,
+    fast::FastGame,
+    bankroll::{simulate_betting_strats, compare_bankrolls},
+    session::{SessionLength, SessionSettings},
+    presets::{find_preset, presets},
+    config::{ConfigError, RulesConfig, ShoeConfig, SimulationConfig, StrategiesConfig, TableConfig},
+    error::GameError,
+    sweep::{Axis, Sweep},
+    interactive::{play_session, Console, HumanPlayer},
+    trainer::{DealMode, Trainer},
+    count_drill::{CountDrill, DrillSettings},
+    event_log::{read_events, logged_settings, print_rounds, replay},
+    registry::{StrategyError, StrategyKind, StrategyRegistry, StrategySpec, UnknownStrategy},
+    report::{SettingsSummary, SimulationReport},
+    BlackjackPayout, DoubleRule, Game, GameSettings, LimitPolicy, ValidSettings,
+};
+
+#[derive(Parser)]
+#[command(name = "blackjack_sim", version, about = "Blackjack strategy simulator",
+    after_help = "Exit codes: 0 success, 1 runtime failure, 2 bad configuration")]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Simulate one set of strategies & report the player's edge
+    Simulate(SimulateArgs),
+    /// Simulate the settings in a TOML or JSON config file
+    Run(RunArgs),
+    /// Simulate every combination of varied config values
+    Sweep(SweepArgs),
+    /// Play at the table yourself, decisions typed in the terminal
+    Play(PlayArgs),
+    /// Drill strategy decisions, mistakes priced in EV
+    Train(TrainArgs),
+    /// Card counting drill, count asked at checkpoints
+    Count(CountArgs),
+    /// Compare playing strategies under the same rules
+    Compare(CompareArgs),
+    /// Compare betting strategies by bankroll trajectories
+    Bankroll(BankrollArgs),
+    /// Sessions at the table w/ a fixed length & stop-win/loss goals, from the --bankroll brought
+    Session(SessionArgs),
+    /// Simulation speed in hands per second, over shoe sizes w/ & without a cut card
+    Bench(BenchArgs),
+    /// House edge & counter edge dealt from a hand shuffled shoe, a CSM & an infinite deck
+    Shoes(ShoesArgs),
+    /// How much order a shuffle procedure keeps from the previous shoe, step by step
+    Shuffle(ShuffleArgs),
+    /// List registered strategy names
+    Strategies,
+    /// List casino rule presets w/ their published house edge
+    Presets,
+    /// Play rounds & write every event to a JSON lines log
+    Log(LogArgs),
+    /// Show a logged game hand by hand, or check it against this engine
+    Replay(ReplayArgs),
+}
+
+// |-------------------------|
+// |        ARGUMENTS        |
+// |-------------------------|
+
+#[derive(Args, Clone)]
+pub struct RuleArgs {
+    /// Casino rule set, see `presets`. Replaces the shoe & rule flags
+    #[arg(long, conflicts_with_all = ["decks", "no_cut_card", "penetration", "max_splits", "dealer_cutoff",
+        "early_surrender", "late_surrender", "hit_soft_17", "no_double_after_split", "double_on",
+        "blackjack_pays", "dealer_peeks", "split_aces_one_card"])]
+    pub preset: Option<String>,
+    /// Decks in the shoe
+    #[arg(long, default_value_t = 6)]
+    pub decks: u8,
+    /// Deal the whole shoe, no cut card
+    #[arg(long)]
+    pub no_cut_card: bool,
+    /// Share of the shoe dealt before the cut card, random if not given
+    #[arg(long)]
+    pub penetration: Option<f64>,
+    /// How the shoe is shuffled, presets included
+    #[arg(long, value_enum, default_value_t = ShoeType::Shuffled)]
+    pub shoe: ShoeType,
+    /// Cards a continuous shuffler deals before returned discards can come out
+    #[arg(long, default_value_t = CSM_RESERVOIR)]
+    pub reservoir: usize,
+    /// Steps from one shoe to the next, e.g. riffle,strip:5,riffle,plug:3,riffle,cut. Perfect if not given
+    #[arg(long, default_value = "perfect")]
+    pub shuffle: ShuffleProcedure,
+    #[arg(long, default_value_t = 3)]
+    pub max_splits: u8,
+    /// Base bet
+    #[arg(long, default_value_t = 10)]
+    pub bet: u32,
+    #[arg(long, default_value_t = 10_000)]
+    pub bankroll: u32,
+    /// Dealer stands at or above
+    #[arg(long, default_value_t = 17)]
+    pub dealer_cutoff: u8,
+    #[arg(long)]
+    pub early_surrender: bool,
+    #[arg(long)]
+    pub late_surrender: bool,
+    #[arg(long)]
+    pub hit_soft_17: bool,
+    #[arg(long)]
+    pub no_double_after_split: bool,
+    /// Two card totals the player may double on
+    #[arg(long, value_enum, default_value_t = DoubleOn::Any)]
+    pub double_on: DoubleOn,
+    #[arg(long, value_enum, default_value_t = Payout::ThreeToTwo)]
+    pub blackjack_pays: Payout,
+    /// Dealer checks for a natural before the player acts
+    #[arg(long)]
+    pub dealer_peeks: bool,
+    #[arg(long)]
+    pub split_aces_one_card: bool,
+    #[arg(long, default_value_t = 2)]
+    pub table_min: u32,
+    #[arg(long, default_value_t = 500)]
+    pub table_max: u32,
+    #[arg(long, default_value_t = 1)]
+    pub bet_increment: u32,
+    /// What happens to bets outside the table limits
+    #[arg(long, value_enum, default_value_t = Policy::Clamp)]
+    pub limit_policy: Policy,
+    /// Rng seed, random if not given
+    #[arg(long)]
+    pub seed: Option<u64>,
+    /// Print every hand as it's played
+    #[arg(long)]
+    pub echo: bool,
+}
+
+#[derive(Args, Clone)]
+pub struct TableStrategyArgs {
+    #[arg(long, default_value = "dealer-play")]
+    pub dealer: String,
+    #[arg(long, default_value = "hi-lo")]
+    pub counting: String,
+    #[arg(long, default_value = "none")]
+    pub insurance: String,
+}
+
+#[derive(Args, Clone)]
+pub struct OutputArgs {
+    #[arg(long, value_enum, default_value_t = Format::Text)]
+    pub format: Format,
+    /// Show a progress bar on stderr
+    #[arg(long)]
+    pub progress: bool,
+}
+
+#[derive(Args)]
+pub struct SimulateArgs {
+    #[arg(long, default_value_t = 1_000_000)]
+    pub rounds: u64,
+    #[arg(long, default_value = "basic")]
+    pub player: String,
+    #[arg(long, default_value = "constant")]
+    pub betting: String,
+    /// Compact allocation free engine: flat bets only, no echo or progress bar
+    #[arg(long, conflicts_with_all = ["echo", "progress"])]
+    pub fast: bool,
+    #[command(flatten)]
+    pub table: TableStrategyArgs,
+    #[command(flatten)]
+    pub rules: RuleArgs,
+    #[command(flatten)]
+    pub output: OutputArgs,
+}
+
+#[derive(Args)]
+pub struct RunArgs {
+    /// Config file, JSON if it ends in .json, TOML otherwise
+    pub path: PathBuf,
+    /// Overrides the config's rounds
+    #[arg(long)]
+    pub rounds: Option<u64>,
+    /// Overrides the config's seed
+    #[arg(long)]
+    pub seed: Option<u64>,
+    /// Print every hand as it's played
+    #[arg(long)]
+    pub echo: bool,
+    #[command(flatten)]
+    pub output: OutputArgs,
+}
+
+#[derive(Args)]
+pub struct SweepArgs {
+    /// Config file, may list `[[vary]]` keys & values
+    pub path: PathBuf,
+    /// Config key & its values, e.g. shoe.decks=1,2,6. Repeat for more axes
+    #[arg(long, value_name = "KEY=V1,V2")]
+    pub vary: Vec<String>,
+    /// Overrides the config's rounds
+    #[arg(long)]
+    pub rounds: Option<u64>,
+    /// Overrides the config's seed
+    #[arg(long)]
+    pub seed: Option<u64>,
+    /// Grid points simulated at once, 0 for one per core
+    #[arg(long, default_value_t = 1)]
+    pub jobs: usize,
+    /// Also write the results table as CSV
+    #[arg(long)]
+    pub csv: Option<PathBuf>,
+    #[command(flatten)]
+    pub output: OutputArgs,
+}
+
+#[derive(Args)]
+pub struct PlayArgs {
+    /// Show the running & true count at every decision
+    #[arg(long)]
+    pub show_count: bool,
+    /// First cards dealt, e.g. "8s 8h | 6d ?" for a pair of eights against a six, fresh decks after
+    #[arg(long)]
+    pub stack: Option<Script>,
+    /// Suit letters in place of symbols
+    #[arg(long)]
+    pub ascii: bool,
+    #[command(flatten)]
+    pub table: TableStrategyArgs,
+    #[command(flatten)]
+    pub rules: RuleArgs,
+}
+
+#[derive(Args)]
+pub struct TrainArgs {
+    /// Strategy answers are graded against
+    #[arg(long, default_value = "basic")]
+    pub player: String,
+    /// Which decisions get dealt
+    #[arg(long, value_enum, default_value_t = Deal::Random)]
+    pub deal: Deal,
+    /// Stop after this many decisions, asks until quit if not given
+    #[arg(long)]
+    pub rounds: Option<u64>,
+    /// Rounds simulated per play to price a mistake
+    #[arg(long, default_value_t = 20_000)]
+    pub ev_trials: u32,
+    /// Suit letters in place of symbols
+    #[arg(long)]
+    pub ascii: bool,
+    #[command(flatten)]
+    pub table: TableStrategyArgs,
+    #[command(flatten)]
+    pub rules: RuleArgs,
+}
+
+#[derive(Args)]
+pub struct CountArgs {
+    /// Cards flipped together
+    #[arg(long, default_value_t = 1)]
+    pub group: usize,
+    /// Milliseconds each group stays up
+    #[arg(long, default_value_t = 800)]
+    pub pace_ms: u64,
+    /// Cards between count checks
+    #[arg(long, default_value_t = 20)]
+    pub every: usize,
+    /// Cards shown, most of the shoe if not given
+    #[arg(long)]
+    pub cards: Option<usize>,
+    /// Count rounds the engine plays & check against its running count
+    #[arg(long)]
+    pub rounds: bool,
+    /// Playing strategy for `--rounds`
+    #[arg(long, default_value = "basic")]
+    pub player: String,
+    /// Suit letters in place of symbols
+    #[arg(long)]
+    pub ascii: bool,
+    #[command(flatten)]
+    pub table: TableStrategyArgs,
+    #[command(flatten)]
+    pub rules: RuleArgs,
+}
+
+#[derive(Args)]
+pub struct CompareArgs {
+    /// Playing strategies to compare
+    #[arg(required = true, num_args = 1..)]
+    pub players: Vec<String>,
+    #[arg(long, default_value_t = 1_000_000)]
+    pub rounds: u64,
+    #[arg(long, default_value = "constant")]
+    pub betting: String,
+    #[command(flatten)]
+    pub table: TableStrategyArgs,
+    #[command(flatten)]
+    pub rules: RuleArgs,
+    #[command(flatten)]
+    pub output: OutputArgs,
+}
+
+#[derive(Args)]
+pub struct ShoesArgs {
+    /// Rounds simulated per shoe, flat bet & counted each
+    #[arg(long, default_value_t = 1_000_000)]
+    pub rounds: u64,
+    #[arg(long, default_value = "basic")]
+    pub player: String,
+    /// The counter's bets, sized by the count
+    #[arg(long, default_value = "ramp")]
+    pub betting: String,
+    #[command(flatten)]
+    pub table: TableStrategyArgs,
+    #[command(flatten)]
+    pub rules: RuleArgs,
+    #[command(flatten)]
+    pub output: OutputArgs,
+}
+
+#[derive(Args)]
+pub struct ShuffleArgs {
+    /// Steps, e.g. riffle,strip:5,riffle,plug:3,riffle,cut
+    pub procedure: ShuffleProcedure,
+    #[arg(long, default_value_t = 6)]
+    pub decks: u8,
+    /// Shuffles averaged over
+    #[arg(long, default_value_t = 1_000)]
+    pub trials: u32,
+    #[arg(long)]
+    pub seed: Option<u64>,
+    #[arg(long, value_enum, default_value_t = Format::Text)]
+    pub format: Format,
+}
+
+#[derive(Args)]
+pub struct BenchArgs {
+    /// Rounds simulated per shoe
+    #[arg(long, default_value_t = 1_000_000)]
+    pub rounds: u64,
+    /// Shoe sizes benchmarked, in decks
+    #[arg(long, value_delimiter = ',', default_value = "1,6,8")]
+    pub deck_counts: Vec<u8>,
+    /// Time the compact flat bet engine instead
+    #[arg(long)]
+    pub fast: bool,
+    #[arg(long, default_value = "basic")]
+    pub player: String,
+    #[arg(long, default_value = "constant")]
+    pub betting: String,
+    #[command(flatten)]
+    pub table: TableStrategyArgs,
+    #[command(flatten)]
+    pub rules: RuleArgs,
+    #[arg(long, value_enum, default_value_t = Format::Text)]
+    pub format: Format,
+}
+
+#[derive(Args)]
+pub struct BankrollArgs {
+    /// Betting strategies to compare
+    #[arg(required = true, num_args = 1..)]
+    pub betting: Vec<String>,
+    #[arg(long, default_value_t = 1_000)]
+    pub trajectories: u64,
+    /// Rounds per trajectory unless ruined first
+    #[arg(long, default_value_t = 1_000)]
+    pub max_hands: u64,
+    #[arg(long, default_value = "basic")]
+    pub player: String,
+    #[command(flatten)]
+    pub table: TableStrategyArgs,
+    #[command(flatten)]
+    pub rules: RuleArgs,
+    #[command(flatten)]
+    pub output: OutputArgs,
+}
+
+#[derive(Args)]
+pub struct SessionArgs {
+    #[arg(long, default_value_t = 1_000)]
+    pub sessions: u64,
+    /// Rounds per session
+    #[arg(long, default_value_t = 200, conflicts_with = "hours")]
+    pub hands: u64,
+    /// Session length in hours instead of rounds
+    #[arg(long)]
+    pub hours: Option<f64>,
+    #[arg(long, default_value_t = 60)]
+    pub hands_per_hour: u64,
+    /// Leave once up this much
+    #[arg(long)]
+    pub stop_win: Option<i64>,
+    /// Leave once down this much
+    #[arg(long)]
+    pub stop_loss: Option<i64>,
+    #[arg(long, default_value = "basic")]
+    pub player: String,
+    #[arg(long, default_value = "constant")]
+    pub betting: String,
+    #[command(flatten)]
+    pub table: TableStrategyArgs,
+    #[command(flatten)]
+    pub rules: RuleArgs,
+    #[command(flatten)]
+    pub output: OutputArgs,
+}
+
+#[derive(Args)]
+pub struct LogArgs {
+    /// Event log to write
+    pub path: PathBuf,
+    #[arg(long, default_value_t = 100)]
+    pub rounds: u64,
+    #[arg(long, default_value = "basic")]
+    pub player: String,
+    #[arg(long, default_value = "constant")]
+    pub betting: String,
+    #[command(flatten)]
+    pub table: TableStrategyArgs,
+    #[command(flatten)]
+    pub rules: RuleArgs,
+}
+
+#[derive(Args)]
+pub struct ReplayArgs {
+    /// Event log written by `log`
+    pub path: PathBuf,
+    /// Re-run the log through this engine & report the first difference
+    #[arg(long)]
+    pub check: bool,
+}
+
+#[derive(ValueEnum, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Text,
+    Json,
+}
+
+#[derive(ValueEnum, Clone, Copy, PartialEq, Eq)]
+pub enum Policy {
+    Clamp,
+    Reject,
+}
+
+#[derive(ValueEnum, Clone, Copy, PartialEq, Eq)]
+pub enum ShoeType {
+    /// By hand at the cut card
+    Shuffled,
+    /// Continuous shuffling machine, discards back in after every round
+    Csm,
+    /// Drawn w/ replacement
+    Infinite,
+}
+
+impl From<ShoeType> for ShoeKind {
+    fn from(shoe: ShoeType) -> Self {
+        match shoe {
+            ShoeType::Shuffled => ShoeKind::Shuffled,
+            ShoeType::Csm => ShoeKind::Csm,
+            ShoeType::Infinite => ShoeKind::Infinite,
+        }
+    }
+}
+
+#[derive(ValueEnum, Clone, Copy, PartialEq, Eq)]
+pub enum Deal {
+    Random,
+    /// Mostly soft hands, pairs, stiffs & doubles
+    Hard,
+}
+
+impl From<Deal> for DealMode {
+    fn from(deal: Deal) -> Self {
+        match deal {
+            Deal::Random => DealMode::Random,
+            Deal::Hard => DealMode::Hard,
+        }
+    }
+}
+
+#[derive(ValueEnum, Clone, Copy, PartialEq, Eq)]
+pub enum DoubleOn {
+    Any,
+    #[value(name = "9-11")]
+    NineToEleven,
+    #[value(name = "10-11")]
+    TenToEleven,
+}
+
+impl From<DoubleOn> for DoubleRule {
+    fn from(double_on: DoubleOn) -> Self {
+        match double_on {
+            DoubleOn::Any => DoubleRule::Any,
+            DoubleOn::NineToEleven => DoubleRule::NineToEleven,
+            DoubleOn::TenToEleven => DoubleRule::TenToEleven,
+        }
+    }
+}
+
+#[derive(ValueEnum, Clone, Copy, PartialEq, Eq)]
+pub enum Payout {
+    #[value(name = "3:2")]
+    ThreeToTwo,
+    #[value(name = "6:5")]
+    SixToFive,
+    #[value(name = "1:1")]
+    EvenMoney,
+}
+
+impl From<Payout> for BlackjackPayout {
+    fn from(payout: Payout) -> Self {
+        match payout {
+            Payout::ThreeToTwo => BlackjackPayout::ThreeToTwo,
+            Payout::SixToFive => BlackjackPayout::SixToFive,
+            Payout::EvenMoney => BlackjackPayout::EvenMoney,
+        }
+    }
+}
+
+impl From<Policy> for LimitPolicy {
+    fn from(policy: Policy) -> Self {
+        match policy {
+            Policy::Clamp => LimitPolicy::Clamp,
+            Policy::Reject => LimitPolicy::Reject,
+        }
+    }
+}
+
+// |-------------------------|
+// |          ERRORS         |
+// |-------------------------|
+
+#[derive(Debug)]
+pub enum CliError {
+    Config(String),  // Bad flags or names, nothing was run
+    Runtime(String), // Failed while running, e.g. IO
+}
+
+impl CliError {
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            CliError::Runtime(_) => 1,
+            CliError::Config(_) => 2,
+        }
+    }
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CliError::Config(msg) => write!(f, "configuration error: {}", msg),
+            CliError::Runtime(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl From<UnknownStrategy> for CliError {
+    fn from(err: UnknownStrategy) -> Self {
+        CliError::Config(err.to_string())
+    }
+}
+
+impl From<StrategyError> for CliError {
+    fn from(err: StrategyError) -> Self {
+        CliError::Config(err.to_string())
+    }
+}
+
+// Invalid settings are caught before anything runs
+impl From<GameError> for CliError {
+    fn from(err: GameError) -> Self {
+        match err {
+            GameError::InvalidSettings(_) => CliError::Config(err.to_string()),
+            err => CliError::Runtime(err.to_string()),
+        }
+    }
+}
+
+impl From<ConfigError> for CliError {
+    fn from(err: ConfigError) -> Self {
+        match err {
+            ConfigError::Io(msg) => CliError::Runtime(msg),
+            err => CliError::Config(err.to_string()),
+        }
+    }
+}
+
+// IO failure on `path`
+fn io_error(path: &Path) -> impl Fn(std::io::Error) -> CliError + '_ {
+    move |err| CliError::Runtime(format!("{}: {}", path.display(), err))
+}
+
+// |-------------------------|
+// |         SETTINGS        |
+// |-------------------------|
+
+impl RuleArgs {
+    // Same run as a config file
+    fn config(&self, player: &str, betting: &str, table: &TableStrategyArgs) -> Result<SimulationConfig, CliError> {
+        let (preset, rules, shoe) = match self.preset.as_deref() {
+            Some(name) => {
+                let preset = find_preset(name).ok_or_else(|| CliError::Config(format!("Unknown preset '{}', expected one of: {}",
+                    name, presets().iter().map(|preset| preset.key).collect::<Vec<_>>().join(", "))))?;
+                let config = preset.config();
+                (config.preset, config.rules, config.shoe)
+            }
+            None => (None, self.rules(), ShoeConfig {
+                decks: self.decks,
+                cut_card: !self.no_cut_card,
+                penetration: self.penetration,
+                ..Default::default()
+            }),
+        };
+        let shoe = ShoeConfig { kind: self.shoe.into(), reservoir: self.reservoir, shuffle: self.shuffle.clone(), ..shoe };
+
+        Ok(SimulationConfig {
+            preset,
+            seed: self.seed,
+            rules,
+            shoe,
+            table: TableConfig {
+                base_bet: self.bet,
+                bankroll: self.bankroll,
+                min: self.table_min,
+                max: self.table_max,
+                increment: self.bet_increment,
+                limit_policy: self.limit_policy.into(),
+            },
+            strategies: StrategiesConfig {
+                player: player.into(),
+                dealer: table.dealer.as_str().into(),
+                betting: betting.into(),
+                counting: table.counting.as_str().into(),
+                insurance: table.insurance.as_str().into(),
+            },
+            ..Default::default()
+        })
+    }
+
+    fn rules(&self) -> RulesConfig {
+        RulesConfig {
+            dealer_cutoff: self.dealer_cutoff,
+            max_splits: self.max_splits,
+            early_surrender: self.early_surrender,
+            late_surrender: self.late_surrender,
+            hit_soft_17: self.hit_soft_17,
+            double_after_split: !self.no_double_after_split,
+            double_on: self.double_on.into(),
+            blackjack_pays: self.blackjack_pays.into(),
+            dealer_peeks: self.dealer_peeks,
+            split_aces_one_card: self.split_aces_one_card,
+        }
+    }
+
+    // Builder for settings the flags can't express, e.g. a human player
+    fn builder(&self, registry: &StrategyRegistry, player: &str, betting: &str, table: &TableStrategyArgs)
+    -> Result<GameSettingsBuilder<ChaCha8Rng>, CliError> {
+        Ok(self.config(player, betting, table)?.builder_named(registry, SETTING_FLAGS)?.echo(self.echo))
+    }
+
+    // Settings w/ a freshly shuffled & cut shoe
+    fn settings(&self, registry: &StrategyRegistry, player: &str, betting: &str, table: &TableStrategyArgs)
+    -> Result<Arc<ValidSettings<ChaCha8Rng>>, CliError> {
+        Ok(self.builder(registry, player, betting, table)?.build().map_err(|err| err.rename_settings(SETTING_FLAGS))?)
+    }
+}
+
+// Flag of each setting `GameSettings::validate` names
+const SETTING_FLAGS: &[(&str, &str)] = &[
+    ("decks", "--decks"),
+    ("penetration", "--penetration"),
+    ("reservoir", "--reservoir"),
+    ("shuffle", "--shuffle"),
+    ("max_splits", "--max-splits"),
+    ("dealer_cutoff", "--dealer-cutoff"),
+    ("init_bet", "--bet"),
+    ("bankroll", "--bankroll"),
+    ("table_min", "--table-min"),
+    ("table_max", "--table-max"),
+    ("bet_increment", "--bet-increment"),
+];
+
+// Settings a log or report was made with, strategies rebuilt from their recorded spec
+// Older logs only have display names, looked up w/ default params
+// Shoes come from the log's shuffles, the seed only matters to reports
+fn settings_from_summary(registry: &StrategyRegistry, summary: &SettingsSummary) -> Result<Arc<ValidSettings<ChaCha8Rng>>, CliError> {
+    let strat = |kind: StrategyKind, spec: &Option<StrategySpec>, name: &str| match spec {
+        Some(spec) => registry.build(kind, spec),
+        None => Ok(registry.get(kind, name)?),
+    };
+    let mut builder = GameSettings::builder()
+        .decks(summary.deck_count)
+        .cut_card(summary.contains_blank)
+        .shoe(summary.shoe)
+        .reservoir(summary.reservoir)
+        .shuffle(summary.shuffle.clone())
+        .max_splits(summary.max_splits)
+        .init_bet(summary.init_bet)
+        .bankroll(summary.bankroll)
+        .dealer_cutoff(summary.dealer_cutoff)
+        .dealer_strat(strat(StrategyKind::Dealer, &summary.dealer_spec, &summary.dealer_strat)?)
+        .player_strat(strat(StrategyKind::Playing, &summary.player_spec, &summary.player_strat)?)
+        .betting_strat(strat(StrategyKind::Betting, &summary.betting_spec, &summary.betting_strat)?)
+        .counting_strat(strat(StrategyKind::Counting, &summary.counting_spec, &summary.counting_strat)?)
+        .insurance_strat(strat(StrategyKind::Insurance, &summary.insurance_spec, &summary.insurance_strat)?)
+        .early_surrender(summary.allow_early_surrender)
+        .late_surrender(summary.allow_late_surrender)
+        .rules(summary.rules)
+        .table_limits(summary.table_min, summary.table_max)
+        .bet_increment(summary.bet_increment)
+        .limit_policy(summary.limit_policy)
+        .seed(summary.seed.unwrap_or(0));
+    if let Some(penetration) = summary.penetration {
+        builder = builder.penetration(penetration);
+    }
+    Ok(builder.build()?)
+}
+
+fn positive(flag: &str, value: u64) -> Result<(), CliError> {
+    match value {
+        0 => Err(CliError::Config(format!("--{} must be positive", flag))),
+        _ => Ok(()),
+    }
+}
+
+fn print_json(value: &impl serde::Serialize) -> Result<(), CliError> {
+    let json = serde_json::to_string_pretty(value).map_err(|err| CliError::Runtime(err.to_string()))?;
+    println!("{}", json);
+    Ok(())
+}
+
+// |-------------------------|
+// |         COMMANDS        |
+// |-------------------------|
+
+pub fn run(cli: Cli) -> Result<(), CliError> {
+    let registry = StrategyRegistry::default();
+    match cli.command {
+        Command::Simulate(args) => simulate(&registry, args),
+        Command::Run(args) => run_config(&registry, args),
+        Command::Sweep(args) => sweep(&registry, args),
+        Command::Play(args) => play(&registry, args),
+        Command::Train(args) => train(&registry, args),
+        Command::Count(args) => count(&registry, args),
+        Command::Compare(args) => compare(&registry, args),
+        Command::Bankroll(args) => bankroll(&registry, args),
+        Command::Session(args) => session(&registry, args),
+        Command::Bench(args) => bench(&registry, args),
+        Command::Shoes(args) => shoes(&registry, args),
+        Command::Shuffle(args) => shuffle(args),
+        Command::Strategies => {
+            strategies(&registry);
+            Ok(())
+        }
+        Command::Presets => {
+            list_presets();
+            Ok(())
+        }
+        Command::Log(args) => log(&registry, args),
+        Command::Replay(args) => replay_log(&registry, args),
+    }
+}
+
+fn simulate(registry: &StrategyRegistry, args: SimulateArgs) -> Result<(), CliError> {
+    positive("rounds", args.rounds)?;
+    let settings = args.rules.settings(registry, &args.player, &args.betting, &args.table)?;
+    if args.fast {
+        let mut game = FastGame::new(settings)?;
+        game.run(args.rounds)?;
+        match args.output.format {
+            Format::Text => print_report(&game.report()),
+            Format::Json => print_json(&game.report())?,
+        }
+        return Ok(());
+    }
+
+    let mut pool = GamePool::new(settings);
+    pool.run(args.rounds, args.output.progress)?;
+    match args.output.format {
+        Format::Text => pool.sum_results(),
+        Format::Json => print_json(&pool.report())?,
+    }
+    Ok(())
+}
+
+fn print_report(report: &SimulationReport) {
+    let perc = |count: u64| 100_f64 * count as f64 / report.hands.max(1) as f64;
+    let outcomes = &report.outcomes;
+    println!("\n -- Simulation Results --\n");
+    println!("Player Strat: {}", report.settings.player_strat);
+    println!("Betting Strat: {}", report.settings.betting_strat);
+    println!("n = {}", report.hands);
+    println!("Player Wins: {} | {}%", outcomes.player_wins, perc(outcomes.player_wins));
+    println!("Dealer Wins: {} | {}%", outcomes.dealer_wins, perc(outcomes.dealer_wins));
+    println!("Ties: {} | {}%", outcomes.ties, perc(outcomes.ties));
+    println!("Player Payoff: ${}", report.payoff);
+    println!("Player Payoff/Game: ${}", report.ev_per_hand);
+    println!("Player Edge: {:.3}% +/- {:.3}%", report.ev_percent, report.ev_percent_ci95);
+    println!("Hands/sec: {:.0}", report.hands_per_sec);
+}
+
+fn run_config(registry: &StrategyRegistry, args: RunArgs) -> Result<(), CliError> {
+    let mut config = SimulationConfig::load(&args.path)?;
+    config.rounds = args.rounds.unwrap_or(config.rounds);
+    config.seed = args.seed.or(config.seed);
+    let settings = config.builder::<ChaCha8Rng>(registry)?.echo(args.echo).build()?;
+
+    let mut pool = GamePool::new(settings);
+    pool.run(config.rounds, args.output.progress)?;
+    match args.output.format {
+        Format::Text => pool.sum_results(),
+        Format::Json => print_json(&pool.report())?,
+    }
+    Ok(())
+}
+
+fn sweep(registry: &StrategyRegistry, args: SweepArgs) -> Result<(), CliError> {
+    let mut sweep = Sweep::load(&args.path)?;
+    for spec in args.vary.iter() {
+        sweep.vary(Axis::parse(spec).map_err(|err| CliError::Config(format!("--vary: {}", err)))?);
+    }
+    if let Some(rounds) = args.rounds {
+        sweep.set("rounds", json!(rounds))?;
+    }
+    if let Some(seed) = args.seed {
+        sweep.set("seed", json!(seed))?;
+    }
+    let jobs = match args.jobs {
+        0 => std::thread::available_parallelism().map_or(1, |cores| cores.get()),
+        jobs => jobs,
+    };
+
+    let rows = sweep.run::<ChaCha8Rng>(registry, jobs, args.output.progress)?;
+    match args.output.format {
+        Format::Text => sweep.print_table(&rows),
+        Format::Json => print_json(&rows)?,
+    }
+    if let Some(path) = args.csv.as_ref() {
+        sweep.write_csv(&rows, path).map_err(io_error(path))?;
+    }
+    Ok(())
+}
+
+fn play(registry: &StrategyRegistry, args: PlayArgs) -> Result<(), CliError> {
+    let console = Console::stdio().with_count(args.show_count).with_ascii(args.ascii).shared();
+    let settings = args.rules.builder(registry, "basic", "constant", &args.table)?
+        .player_strat(Arc::new(Box::new(HumanPlayer::new(console.clone()))))
+        .build()
+        .map_err(|err| err.rename_settings(SETTING_FLAGS))?;
+
+    let mut game = Game::from_settings(settings.clone());
+    if let Some(stack) = args.stack.as_ref() {
+        game.set_shoe(Box::new(stack.shoe().with_fallback(settings.deck.deck_count)));
+    }
+    play_session(&mut game, &console)?;
+    Ok(())
+}
+
+fn train(registry: &StrategyRegistry, args: TrainArgs) -> Result<(), CliError> {
+    positive("ev-trials", args.ev_trials as u64)?;
+    let settings = args.rules.settings(registry, &args.player, "constant", &args.table)?;
+    let mut trainer = Trainer::new(settings, args.deal.into(), args.ev_trials)?;
+    trainer.drill(&Console::stdio().with_ascii(args.ascii).shared(), args.rounds)?;
+    Ok(())
+}
+
+fn count(registry: &StrategyRegistry, args: CountArgs) -> Result<(), CliError> {
+    positive("group", args.group as u64)?;
+    positive("every", args.every as u64)?;
+    let settings = args.rules.settings(registry, &args.player, "constant", &args.table)?;
+    let drill = DrillSettings {
+        group_size: args.group,
+        pace: std::time::Duration::from_millis(args.pace_ms),
+        checkpoint_every: args.every,
+        cards: args.cards,
+    };
+
+    let mut drill = CountDrill::new(settings, drill);
+    let console = Console::stdio().with_ascii(args.ascii).shared();
+    match args.rounds {
+        true => drill.table_drill(&console)?,
+        false => drill.flip_drill(&console)?,
+    };
+    Ok(())
+}
+
+fn compare(registry: &StrategyRegistry, args: CompareArgs) -> Result<(), CliError> {
+    positive("rounds", args.rounds)?;
+    // Every strategy checked before anything runs
+    let all_settings = args.players.iter()
+        .map(|player| args.rules.settings(registry, player, &args.betting, &args.table))
+        .collect::<Result<Vec<_>, CliError>>()?;
+
+    let reports = all_settings.into_iter().map(|settings| {
+        let mut pool = GamePool::new(settings);
+        pool.run(args.rounds, args.output.progress)?;
+        Ok(pool.report())
+    }).collect::<Result<Vec<SimulationReport>, GameError>>()?;
+
+    match args.output.format {
+        Format::Text => print_comparison(&reports),
+        Format::Json => print_json(&reports)?,
+    }
+    Ok(())
+}
+
+fn print_comparison(reports: &[SimulationReport]) {
+    println!("\n -- Playing Strat Comparison --\n");
+    println!("{:<20} {:>12} {:>10} {:>10} {:>10} {:>12}", "Strat", "Hands", "Win %", "EV/Hand", "Edge %", "95% CI +/-");
+    for report in reports {
+        let win_perc = 100_f64 * report.outcomes.player_wins as f64 / report.hands.max(1) as f64;
+        println!("{:<20} {:>12} {:>10.2} {:>10.4} {:>10.3} {:>12.3}",
+            report.settings.player_strat,
+            report.hands,
+            win_perc,
+            report.ev_per_hand,
+            report.ev_percent,
+            report.ev_percent_ci95,
+        );
+    }
+}
+
+fn bankroll(registry: &StrategyRegistry, args: BankrollArgs) -> Result<(), CliError> {
+    positive("trajectories", args.trajectories)?;
+    positive("max-hands", args.max_hands)?;
+    let betting_strats = args.betting.iter()
+        .map(|name| registry.get(StrategyKind::Betting, name))
+        .collect::<Result<Vec<_>, UnknownStrategy>>()?;
+    let settings = args.rules.settings(registry, &args.player, "constant", &args.table)?;
+
+    let reports = simulate_betting_strats(&settings, betting_strats, args.trajectories, args.max_hands, args.output.progress)?;
+    match args.output.format {
+        Format::Text => compare_bankrolls(&reports),
+        Format::Json => {
+            let rows = reports.iter().map(|(name, report)| json!({
+                "betting_strat": name,
+                "start_bankroll": report.start_bankroll,
+                "trajectories": report.trajectories,
+                "max_hands": report.max_hands,
+                "risk_of_ruin": report.risk_of_ruin(),
+                "doubling_chance": report.doubling_chance(),
+                "median_hands_to_ruin": report.median_hands_to_ruin(),
+                "mean_final_bankroll": report.mean_final_bankroll(),
+                "final_bankroll_percentiles": {
+                    "p5": report.percentile(5.0),
+                    "p25": report.percentile(25.0),
+                    "p50": report.percentile(50.0),
+                    "p75": report.percentile(75.0),
+                    "p95": report.percentile(95.0),
+                },
+                "limit_stats": report.limit_stats,
+            })).collect::<Vec<_>>();
+            print_json(&rows)?;
+        }
+    }
+    Ok(())
+}
+
+fn session(registry: &StrategyRegistry, args: SessionArgs) -> Result<(), CliError> {
+    positive("sessions", args.sessions)?;
+    let length = match args.hours {
+        Some(hours) if !(hours > 0.0 && hours.is_finite()) => {
+            return Err(CliError::Config(format!("--hours must be positive, found {}", hours)));
+        }
+        Some(hours) => {
+            positive("hands-per-hour", args.hands_per_hour)?;
+            SessionLength::Hours { hours, hands_per_hour: args.hands_per_hour }
+        }
+        None => SessionLength::Hands(args.hands),
+    };
+    positive("hands", length.hands())?;
+    for (flag, goal) in [("stop-win", args.stop_win), ("stop-loss", args.stop_loss)] {
+        if goal.is_some_and(|goal| goal <= 0) {
+            return Err(CliError::Config(format!("--{} must be positive", flag)));
+        }
+    }
+    let settings = args.rules.settings(registry, &args.player, &args.betting, &args.table)?;
+    let session = SessionSettings {
+        length,
+        bankroll: settings.bankroll,
+        stop_win: args.stop_win,
+        stop_loss: args.stop_loss,
+    };
+
+    let mut pool = GamePool::new(settings);
+    let report = pool.simulate_sessions(args.sessions, &session, args.output.progress)?;
+    match args.output.format {
+        Format::Text => report.sum_results(),
+        Format::Json => print_json(&json!({
+            "sessions": report.sessions,
+            "session_hands": length.hands(),
+            "bankroll": session.bankroll,
+            "stop_win": session.stop_win,
+            "stop_loss": session.stop_loss,
+            "winning_sessions": report.winning_sessions(),
+            "mean_result": report.mean_result(),
+            "mean_hands": report.mean_hands(),
+            "result_percentiles": {
+                "p5": report.percentile(5.0),
+                "p25": report.percentile(25.0),
+                "p50": report.percentile(50.0),
+                "p75": report.percentile(75.0),
+                "p95": report.percentile(95.0),
+            },
+            "stop_wins": report.stop_wins,
+            "stop_losses": report.stop_losses,
+            "ruined": report.ruined,
+        }))?,
+    }
+    Ok(())
+}
+
+fn bench(registry: &StrategyRegistry, args: BenchArgs) -> Result<(), CliError> {
+    positive("rounds", args.rounds)?;
+    if args.rules.preset.is_some() {
+        return Err(CliError::Config("--preset fixes the shoe, bench varies it w/ --deck-counts".to_string()));
+    }
+    // Every shoe checked before anything runs
+    let all_settings = args.deck_counts.iter()
+        .flat_map(|decks| [false, true].map(|no_cut_card| (*decks, no_cut_card)))
+        .map(|(decks, no_cut_card)| {
+            let rules = RuleArgs { decks, no_cut_card, ..args.rules.clone() };
+            rules.settings(registry, &args.player, &args.betting, &args.table)
+        })
+        .collect::<Result<Vec<_>, CliError>>()?;
+
+    let reports = all_settings.into_iter().map(|settings| match args.fast {
+        true => {
+            let mut game = FastGame::new(settings)?;
+            game.run(args.rounds)?;
+            Ok(game.report())
+        }
+        false => {
+            let mut pool = GamePool::new(settings);
+            pool.run(args.rounds, false)?;
+            Ok(pool.report())
+        }
+    }).collect::<Result<Vec<SimulationReport>, GameError>>()?;
+
+    match args.format {
+        Format::Text => {
+            println!("\n -- Simulation Speed --\n");
+            println!("{:<6} {:>9} {:>12} {:>10} {:>14} {:>12}", "Decks", "Cut Card", "Hands", "Secs", "Hands/sec", "Payoff");
+            for report in reports.iter() {
+                println!("{:<6} {:>9} {:>12} {:>10.2} {:>14.0} {:>12}",
+                    report.settings.deck_count,
+                    if report.settings.contains_blank { "yes" } else { "no" },
+                    report.hands,
+                    report.elapsed_secs,
+                    report.hands_per_sec,
+                    report.payoff,
+                );
+            }
+        }
+        Format::Json => print_json(&reports)?,
+    }
+    Ok(())
+}
+
+fn shoes(registry: &StrategyRegistry, args: ShoesArgs) -> Result<(), CliError> {
+    positive("rounds", args.rounds)?;
+    let settings = args.rules.settings(registry, &args.player, &args.betting, &args.table)?;
+
+    let comparisons = compare_shoes(&settings, args.rounds, args.output.progress)?;
+    match args.output.format {
+        Format::Text => print_shoe_comparison(&comparisons),
+        Format::Json => print_json(&comparisons)?,
+    }
+    Ok(())
+}
+
+// Retention after each step, a wash for the baseline
+fn shuffle(args: ShuffleArgs) -> Result<(), CliError> {
+    positive("decks", args.decks as u64)?;
+    positive("trials", args.trials as u64)?;
+    let cards = 52 * args.decks as usize;
+    let mut rng = ChaCha8Rng::seed_from_u64(args.seed.unwrap_or_else(rand::random));
+    let mut rows = (1..=args.procedure.0.len())
+        .map(|steps| ShuffleProcedure(args.procedure.0[..steps].to_vec()))
+        .map(|procedure| (procedure.to_string(), OrderRetention::measure(&procedure, cards, args.trials, &mut rng)))
+        .collect::<Vec<_>>();
+    let wash = ShuffleProcedure(vec![ShuffleStep::Wash]);
+    rows.push(("wash (random)".to_string(), OrderRetention::measure(&wash, cards, args.trials, &mut rng)));
+
+    match args.format {
+        Format::Text => {
+            println!("\n -- Order Retention --\n");
+            println!("{} decks, {} shuffles\n", args.decks, args.trials);
+            let width = rows.iter().map(|(steps, _)| steps.len()).max().unwrap_or(0);
+            println!("{:<width$} {:>12} {:>14} {:>13}", "Steps", "Rising Seqs", "Pairs Kept %", "Displacement");
+            for (steps, retention) in rows.iter() {
+                println!("{:<width$} {:>12.1} {:>14.2} {:>13.3}", steps, retention.rising_sequences, 100.0 * retention.pairs_kept, retention.displacement);
+            }
+        }
+        Format::Json => print_json(&rows.iter().map(|(steps, retention)| json!({ "steps": steps, "retention": retention })).collect::<Vec<_>>())?,
+    }
+    Ok(())
+}
+
+fn strategies(registry: &StrategyRegistry) {
+    for kind in [StrategyKind::Playing, StrategyKind::Dealer, StrategyKind::Betting, StrategyKind::Counting, StrategyKind::Insurance] {
+        println!("{}: {}", kind, registry.names(kind).join(", "));
+    }
+}
+
+fn list_presets() {
+    println!("{:<18} {:<24} {:>7}  Rules", "Preset", "Name", "Edge %");
+    for preset in presets() {
+        println!("{:<18} {:<24} {:>7.2}  {}", preset.key, preset.name, preset.house_edge, preset.description);
+    }
+}
+
+fn log(registry: &StrategyRegistry, args: LogArgs) -> Result<(), CliError> {
+    positive("rounds", args.rounds)?;
+    let settings = args.rules.settings(registry, &args.player, &args.betting, &args.table)?;
+
+    let mut pool = GamePool::new(settings);
+    pool.log_events(&args.path).map_err(io_error(&args.path))?;
+    let played = pool.run(args.rounds, false);
+    pool.close_event_log().map_err(io_error(&args.path))?;
+    played?;
+    Ok(())
+}
+
+fn replay_log(registry: &StrategyRegistry, args: ReplayArgs) -> Result<(), CliError> {
+    let events = read_events(&args.path).map_err(io_error(&args.path))?;
+    if !args.check {
+        print_rounds(&events);
+        return Ok(());
+    }
+
+    let summary = logged_settings(&events).ok_or(CliError::Config("Log has no settings header".to_string()))?;
+    let settings = settings_from_summary(registry, summary)?;
+    let replay = replay(events, settings)?;
+    match replay.first_mismatch() {
+        None => {
+            println!("Replay matches: {} events", replay.replayed.len());
+            Ok(())
+        }
+        Some(mismatch) => {
+            let show = |event: Option<game::event_log::GameEvent>| event.map_or("<none>".to_string(), |event| event.to_string());
+            Err(CliError::Runtime(format!("Replay differs at event {}\n  logged:   {}\n  replayed: {}",
+                mismatch.index, show(mismatch.logged), show(mismatch.replayed))))
+        }
+    }
+}
@@ -1,317 +1,244 @@
 use rand::Rng;
-use crate::{Game, Hand, HandState, deck::{Card, MultiDeck}, playing_strategy::StratReturn, GameState};
-use std::{time::Instant, ops::Div};
+use crate::{Game, Hand, HandState, deck::{Card, Rank}, error::GameError, script::Script, Winner, LimitPolicy};
+use std::ops::Div;
 
 
 impl <R: Rng + Clone> Game <R> {
 
-    pub fn deal(&mut self, init_bet: u32) {
+    pub fn deal(&mut self, init_bet: u32) -> Result<(), GameError> {
         // Check if player hands vec empty
         if self.player.hands.is_empty() {
             self.player.hands.push(Hand::new(init_bet))
         }
 
-        // Check if player 
-
         // Deal Player Hands
-        let mut temp_hands = std::mem::take(&mut self.player.hands);
-        for hand in temp_hands.iter_mut() {
-            hand.set_state(HandState::Playing);
-            while hand.cards.len() < 2 {
-                hand.cards.push(self.draw());
+        for index in 0..self.player.hands.len() {
+            self.player.hands[index].set_state(HandState::Playing);
+            while self.player.hands[index].cards.len() < 2 {
+                let draw = self.draw()?;
+                self.player.hands[index].add_card(draw);
             }
         }
-        // Assign player hand
-        self.player.hands = temp_hands;
 
         // Deal Dealer
-        if self.dealer.hand.is_none() {
-            self.dealer.hand = Some(Hand::new(init_bet))
-        }
-        else {
-            self.dealer.hand.as_mut().expect("None handled").init_bet = init_bet;
-        }
-        self.dealer.hand.as_mut().expect("").set_state(HandState::Playing);
-        while self.dealer.hand.as_ref().expect("").cards.len() < 2 {
-            let draw = self.draw();
-            self.dealer.hand.as_mut().expect("").cards.push(draw);
+        let mut dealer_hand = self.dealer.hand.take().unwrap_or_else(|| Hand::new(init_bet));
+        dealer_hand.init_bet = init_bet;
+        dealer_hand.set_state(HandState::Playing);
+        while dealer_hand.cards.len() < 2 {
+            dealer_hand.add_card(self.draw()?);
         }
-        
-        
-    }
+        self.dealer.hand = Some(dealer_hand);
 
-    pub fn debug_deal(&mut self, init_bet: u32) {
-        let start = Instant::now();
-    
-        // Check if player hands vec empty
-        if self.player.hands.is_empty() {
-            self.player.hands.push(Hand::new(init_bet))
-        }
-    
-        let check_hands_time = start.elapsed();
-        let deal_start = Instant::now();
-        println!("Time to check and update player hands: {:?}", check_hands_time);
-    
-        // Deal Player Hands
-        let mut temp_hands = std::mem::take(&mut self.player.hands);
-        
-        let loop_start = Instant::now();
-        for hand in temp_hands.iter_mut() {
-            hand.set_state(HandState::Playing);
-            while hand.cards.len() < 2 {
-                let draw_start = Instant::now();
-                hand.cards.push(self.draw());
-                let draw_time = draw_start.elapsed();
-                println!("Time to draw: {:?}", draw_time);
+        self.notify(|observer, game| {
+            if let (Some(player), Some(dealer)) = (game.player.hands.first(), game.dealer.hand.as_ref()) {
+                observer.on_deal(player, dealer);
             }
+        });
+        Ok(())
+    }
+
+    // Round over, shoe reshuffled if the cut card came out
+    // Discards a continuous shuffler takes back leave nothing to count
+    pub fn reset_hands(&mut self) {
+        self.player.hands = vec![];
+        self.dealer.hand = None;
+        if self.cut_card_reached {
+            self.reshuffle();
+        } else if self.shoe.discard(&self.played_cards, &mut self.rng) {
+            self.running_count = 0;
+            self.true_count = 0.0;
+            self.played_cards.clear();
         }
-        let loop_time = loop_start.elapsed();
-        println!("Time Loop: {:?}", loop_time);
+    }
 
-    
-        let player_deal_time = deal_start.elapsed();
-        let dealer_deal_start = Instant::now();
-    
-        // Deal Dealer
-        if self.dealer.hand.is_none() {
-            self.dealer.hand = Some(Hand::new(init_bet))
+    // Start a fresh bankroll trajectory, progression history cleared
+    pub fn reset_bankroll(&mut self, bankroll: i64) {
+        self.bankroll = bankroll;
+        self.last_bet = 0;
+        self.last_winner = Winner::None;
+        self.bet_history.clear();
+    }
+
+    // Table limits applied to the player's chosen bet
+    // None if rejected, player sits the round out
+    pub fn place_bet(&mut self, bet: u32) -> Option<u32> {
+        let legal = legal_bet(bet, self.table_min, self.table_max, self.bet_increment);
+
+        if legal == bet {
+            self.notify(|observer, _| observer.on_bet(bet, true));
+            return Some(bet);
         }
-        self.dealer.hand.as_mut().expect("").set_state(HandState::Playing);
-        while self.dealer.hand.as_ref().expect("").cards.len() < 2 {
-            let draw = self.draw();
-            self.dealer.hand.as_mut().expect("").cards.push(draw);
+
+        match self.limit_policy {
+            LimitPolicy::Clamp => {
+                if bet > self.table_max { self.limit_stats.clamped_max += 1 }
+                else if bet < self.table_min { self.limit_stats.clamped_min += 1 }
+                else { self.limit_stats.rounded += 1 }
+                self.notify(|observer, _| observer.on_bet(legal, true));
+                Some(legal)
+            }
+            LimitPolicy::Reject => {
+                // Progression broken, start over from the base bet
+                self.limit_stats.rejected += 1;
+                self.last_bet = 0;
+                self.last_winner = Winner::None;
+                self.bet_history.clear();
+                self.notify(|observer, _| observer.on_bet(bet, false));
+                None
+            }
         }
-    
-        let dealer_deal_time = dealer_deal_start.elapsed();
-        let total_time = start.elapsed();
-    
-        
-        println!("Time to deal to player: {:?}", player_deal_time);
-        println!("Time to deal to dealer: {:?}", dealer_deal_time);
-        println!("Total deal function time: {:?}", total_time);
-    
-        // Restoring the player hands
-        self.player.hands = temp_hands;
+    }
 
+    // Ruin: Player can't put up the next bet
+    pub fn can_cover(&self, bet: u32) -> bool {
+        bet as i64 <= self.bankroll
     }
 
-    pub fn reset_hands(&mut self) {
-        self.player.hands = vec![];
-        self.dealer.hand = None
+    // Double or split bet on top of every bet already on the table
+    pub fn can_raise(&self, extra: u32) -> bool {
+        if !self.bankroll_limited {
+            return true;
+        }
+        let at_risk = self.player.hands.iter()
+            .map(|hand| hand.init_bet as i64 * if hand.doubled { 2 } else { 1 })
+            .sum::<i64>();
+        at_risk + extra as i64 <= self.bankroll
     }
 
     pub fn new_deck(&mut self) {
-        // Empty Played Cards
-        self.played_cards.clear();
-
-        // New deck using existint settings
-        let new_deck = MultiDeck::new(self.deck.deck_count, self.deck.contains_blank);
-
-        // Assign new deck and shuffle
-        self.deck = new_deck;
-        self.deck.shuffle(&mut self.rng);
-
-        // Insert blank if present
-        if self.deck.contains_blank {
-            self.deck.insert_blank(&mut self.rng);
+        // Replays reuse the logged shoe, already shuffled & cut
+        // Played cards go back in as the discard tray
+        match self.scripted_shoes.pop_front() {
+            Some(shoe) => self.shoe.restack(shoe),
+            None => self.shoe.reshuffle(&self.played_cards, &mut self.rng),
         }
+        self.played_cards.clear();
+        self.notify(|observer, game| observer.on_shuffle(game.shoe.cards()));
     }
     
-    pub fn draw(&mut self) -> Card { 
-        // If cards in deck
-        let new_card = self.deck.draw();
-
-        // Debug Statement
-        if self.echo {
-            if new_card.is_some() {
-                println!("Card Draw: {}", &new_card.unwrap());
-            } else {
-                println!("Card Draw: {:?}", &new_card)
-            }
+    // Finished shoe replaced, counts start over
+    pub fn reshuffle(&mut self) {
+        self.notify(|observer, game| observer.on_shoe_end(&game.played_cards));
+        self.new_deck();
+        self.cut_card_reached = false;
+
+        // Reset Counts && Played Cards
+        self.running_count = 0;
+        self.true_count = 0.0;
+        self.played_cards = vec![];
+    }
+
+    // Empty shoe replaced, cut card marks the round to reshuffle after
+    pub fn draw(&mut self) -> Result<Card, GameError> {
+        let mut card = self.shoe.draw(&mut self.rng);
+        // Cut card: finish the round, reshuffle after
+        if card.is_some_and(|card| card.is_blank()) {
+            self.cut_card_reached = true;
+            card = self.shoe.draw(&mut self.rng);
         }
-        
-        // Give card back if exists, or refresh deck and redraw
-        match new_card {
-            Some(card) => {
-                if !card.is_blank() {
-                    self.played_cards.push(card);
-                    self.update_count(self.get_state(None));
-                    
-                    
-                    return card;
-                }
-            },
-            None => {
-                // Else new deck
-                let expected_deck_size = match self.deck.contains_blank {
-                    true => {(52*self.deck.deck_count as usize) + 1}, // Add card for blank
-                    false => {52*self.deck.deck_count as usize}
-                };
-                
-                self.new_deck();
-                assert_eq!(expected_deck_size, self.deck.decks.cards.len());
-
-                // Reset Counts && Played Cards
-                self.running_count = 0;
-                self.true_count = 0.0;
-                self.played_cards = vec![];
-
-                // Debug Statement
-                if self.echo {
-                    println!("Creating New Deck");
-                    println!("Expected: {}", 52*self.deck.deck_count as usize);
-                    println!("New Deck size: {}", self.deck.decks.cards.len());
-                }
-            }
+        // Out of cards, new shoe
+        if card.is_none() {
+            self.reshuffle();
+            card = self.shoe.draw(&mut self.rng);
         }
-        
-        // Draw card from new deck
-        let new_card = self.deck.draw();
-        assert!(new_card.is_some()); 
-        let new_card = new_card.unwrap();
-
-        // Update Played Cards
-        self.played_cards.push(new_card);
-        // Update Count
-        self.update_count(self.get_state(None));
-        
-        new_card
-        
+        let card = card.filter(|card| !card.is_blank()).ok_or(GameError::EmptyShoe)?;
+        self.notify(|observer, _| observer.on_draw(&card));
+
+        // Update Played Cards & Count
+        self.played_cards.push(card);
+        self.update_count()?;
+        Ok(card)
     }
 
-    pub fn update_count(&mut self, state: GameState) {
-        let delta = match self.player.counting_strat.get_decision(state) {
-            StratReturn::Count(d) => d,
-            _ => unreachable!("Always Count")
-        };
+    // Counts the card just drawn, the last of `played_cards`
+    pub fn update_count(&mut self) -> Result<(), GameError> {
+        let delta = self.player.decide_count(&self.view(None))?;
         self.running_count += delta as i32;
-        self.true_count = (self.running_count as f64).div(self.deck.deck_count as f64)
+        self.true_count = (self.running_count as f64).div(self.shoe.deck_count() as f64);
+        Ok(())
     }
 
-    pub fn hit_dealer(&mut self) {
-        assert!(self.dealer.hand.is_some()); // Dealer must have hand
-        let new_card = self.draw(); // Draw Card
-
-        // Get Dealer Hand Mut Ref
-        let dealer_hand = self.dealer.hand.as_mut().expect("");
-        dealer_hand.cards.push(new_card); // Add card to dealer's hand
-        
-        // Soft Ace Check
-        // If Soft Ace && over 21 w/ the new draw => Deflate Ace
-        if dealer_hand.value() > 21 && dealer_hand.contains_soft_ace() {
-            // Debug Statement
-            if self.echo {
-                println!("Deflate Ace");
-            }
-            dealer_hand.deflate_ace();
+    pub fn hit_dealer(&mut self) -> Result<(), GameError> {
+        if self.dealer.hand.is_none() {
+            return Err(GameError::NotDealt);
         }
+        let new_card = self.draw()?;
+        if let Some(dealer_hand) = self.dealer.hand.as_mut() {
+            dealer_hand.add_card(new_card); // Soft aces deflated if over 21
+        }
+        Ok(())
     }
 
-    pub fn hit_player(&mut self, target_hand: &Hand) {
-        // Draw card
-        let draw = self.draw();
-        // Find target hand
-        for hand in self.player.hands.iter_mut() {
-            if target_hand == hand {
-                // Add card to hand
-                if self.echo {
-                    println!("Draw: {}", &draw);
-                }
-                
-                hand.cards.push(draw);
-
-                // Soft Ace Check
-                if hand.value() > 21 {
-                    if hand.contains_soft_ace() {
-                        if self.echo {
-                            println!("Deflate");
-                        }
-                        hand.deflate_ace();
-                    }
-                    else {
-                        break;
-                    }
-                }
-                break;
-            }
+    // Player hands are addressed by index, split hands can be identical
+    pub fn player_hand_mut(&mut self, index: usize) -> Result<&mut Hand, GameError> {
+        self.player.hands.get_mut(index).ok_or(GameError::NoSuchHand(index))
+    }
+
+    pub fn hit_player(&mut self, index: usize) -> Result<(), GameError> {
+        self.player_hand_mut(index)?;
+        let draw = self.draw()?;
+        let hand = self.player_hand_mut(index)?;
+        // Add card to hand, soft aces deflated if over 21
+        hand.add_card(draw);
+        // Busted hand done whatever the strategy says next
+        if hand.value() > 21 {
+            hand.set_state(HandState::Finished);
         }
+        Ok(())
     }
 
-    pub fn split_hand(&mut self, target_hand: &Hand) {
-        let x_card;
-        let mut x_hand;
-        let y_card;
-        let mut y_hand;
-
-        // Find target hand
-        for (i, hand) in self.player.hands.iter_mut().enumerate() {
-            if target_hand == hand {
-                // Clone target (2 card hand)
-                let mut target = hand.clone();
-
-                // Pop Card from hand and add to new hand
-                // Draw second card for hand
-                x_card = target.cards.pop().unwrap();
-                x_hand = Hand::from_cards(vec![x_card], target.init_bet, false, false, true);
-                x_hand.cards.push(self.draw());
-                
-                // Pop second card and add to new hand
-                // Draw second card for hand
-                y_card = target.cards.pop().unwrap();
-                y_hand = Hand::from_cards(vec![y_card], target.init_bet, false, false, true);
-                y_hand.cards.push(self.draw());
-
-                // Add hands to player
-                self.player.hands.push(x_hand);
-                self.player.hands.push(y_hand);
-
-                // Remove original hand from player
-                self.player.hands.remove(i);
-
-                break;
-            }
+    // Split hands stay in place, left hand dealt its second card now
+    // Right hand waits for its second card until its turn, see `handle_player_hand`
+    pub fn split_hand(&mut self, index: usize) -> Result<(), GameError> {
+        let target = self.player_hand_mut(index)?;
+        if !target.contains_pair() {
+            return Err(GameError::NotAPair(index));
+        }
+        // Aces count 11 again once apart
+        let init_bet = target.init_bet;
+        let [mut left_card, mut right_card] = [target.cards[0], target.cards[1]];
+        left_card.inflate();
+        right_card.inflate();
+
+        let mut left_hand = Hand::from_cards(vec![left_card], init_bet, false, false, true);
+        left_hand.add_card(self.draw()?);
+        let mut right_hand = Hand::from_cards(vec![right_card], init_bet, false, false, true);
+
+        // Split aces get one card each, no decisions to wait for
+        if self.rules.split_aces_one_card && left_card.rank == Rank::Ace {
+            right_hand.add_card(self.draw()?);
+            left_hand.set_state(HandState::Finished);
+            right_hand.set_state(HandState::Finished);
         }
-        
-        
 
+        self.player.hands[index] = left_hand;
+        self.player.hands.insert(index + 1, right_hand);
+        Ok(())
     }
 
-    pub fn double_hand(&mut self, target_hand: &Hand) {
-        let single_draw = self.draw();
-        for hand in self.player.hands.iter_mut() {
-            if target_hand == hand {
-                hand.doubled = true;
-                hand.cards.push(single_draw);
-                hand.state = HandState::Finished;
-                break;
-            }
-        }
+    pub fn double_hand(&mut self, index: usize) -> Result<(), GameError> {
+        self.player_hand_mut(index)?;
+        let single_draw = self.draw()?;
+        let hand = self.player_hand_mut(index)?;
+        hand.doubled = true;
+        hand.add_card(single_draw);
+        hand.state = HandState::Finished;
+        Ok(())
     }
 
-    pub fn player_natural(&mut self, target_hand: &Hand) {
-        for hand in self.player.hands.iter_mut() {
-            if target_hand == hand {
-                hand.natural = true;
-                break;
-            }
-        }
+    pub fn player_natural(&mut self, index: usize) -> Result<(), GameError> {
+        self.player_hand_mut(index)?.natural = true;
+        Ok(())
     }
 
-    pub fn stand_player(&mut self, target_hand: &Hand) {
-        for hand in self.player.hands.iter_mut() {
-            if target_hand == hand {
-                hand.set_state(HandState::Finished);
-            }
-        }
+    pub fn stand_player(&mut self, index: usize) -> Result<(), GameError> {
+        self.player_hand_mut(index)?.set_state(HandState::Finished);
+        Ok(())
     }
 
-    pub fn surrender(&mut self, target_hand: &Hand, state: HandState) {
-        for hand in self.player.hands.iter_mut() {
-            if target_hand == hand {
-                hand.set_state(state);
-                break;
-            }
-        }
+    pub fn surrender(&mut self, index: usize, state: HandState) -> Result<(), GameError> {
+        self.player_hand_mut(index)?.set_state(state);
+        Ok(())
     }
 
     // Test Functions
@@ -323,4 +250,40 @@ impl <R: Rng + Clone> Game <R> {
     pub fn set_dealer_hand(&mut self, new_hand: Hand) {
         self.dealer.hand = Some(new_hand);
     }
-}
\ No newline at end of file
+
+    // Last group the dealer's, every other a player hand, `?` drawn from the shoe
+    pub fn set_table(&mut self, script: &Script) -> Result<(), GameError> {
+        let mut hands = vec![];
+        for group in script.groups.iter() {
+            let mut hand = Hand::new(self.init_bet);
+            hand.set_state(HandState::Playing);
+            for card in group.iter() {
+                let card = match card {
+                    Some(card) => *card,
+                    None => self.draw()?,
+                };
+                hand.add_card(card);
+            }
+            hands.push(hand);
+        }
+        self.dealer.hand = hands.pop();
+        self.player.hands = hands;
+        Ok(())
+    }
+}
+
+// Nearest bet the table takes, never above table_max
+// Limits w/o a multiple of the increment between them are refused by `GameSettings::validate`
+pub(crate) fn legal_bet(bet: u32, table_min: u32, table_max: u32, bet_increment: u32) -> u32 {
+    let mut legal = bet.max(table_min).min(table_max);
+
+    // Round down to bet increment, back up if that breaks the minimum
+    let increment = bet_increment.max(1);
+    if !legal.is_multiple_of(increment) {
+        legal -= legal % increment;
+        if legal < table_min {
+            legal = legal.saturating_add(increment);
+        }
+    }
+    legal.min(table_max)
+}
@@ -0,0 +1,188 @@
+use std::ops::Div;
+use std::sync::Arc;
+use rand::Rng;
+use num_format::{Locale, ToFormattedString};
+use crate::run_many::GamePool;
+use crate::{GameSettings, LimitStats, Round};
+use crate::error::GameError;
+use crate::playing_strategy::StrategyFunc;
+
+// Value at percentile p [0-100], nearest rank
+pub fn percentile(values: &[i64], p: f64) -> i64 {
+    if values.is_empty() {
+        return 0;
+    }
+    let mut sorted = values.to_vec();
+    sorted.sort_unstable();
+    let rank = ((p / 100_f64) * (sorted.len() - 1) as f64).round() as usize;
+    sorted[rank.min(sorted.len() - 1)]
+}
+
+// Outcome of many independent bankroll trajectories
+#[derive(Clone, Debug, Default)]
+pub struct BankrollReport {
+    pub start_bankroll: i64,
+    pub max_hands: u64,
+    pub trajectories: u64,
+    pub ruined: u64,
+    pub doubled: u64,              // Reached 2x starting bankroll before ruin
+    pub hands_to_ruin: Vec<u64>,   // One entry per ruined trajectory
+    pub final_bankrolls: Vec<i64>, // One entry per trajectory
+    pub limit_stats: LimitStats,   // Bets changed by table limits, all trajectories
+}
+
+impl BankrollReport {
+    pub fn new(start_bankroll: i64, max_hands: u64) -> Self {
+        BankrollReport { start_bankroll, max_hands, ..Default::default() }
+    }
+
+    pub fn record(&mut self, hands: u64, ruined: bool, doubled: bool, final_bankroll: i64) {
+        self.trajectories += 1;
+        if ruined {
+            self.ruined += 1;
+            self.hands_to_ruin.push(hands);
+        }
+        if doubled {
+            self.doubled += 1;
+        }
+        self.final_bankrolls.push(final_bankroll);
+    }
+
+    pub fn risk_of_ruin(&self) -> f64 {
+        (self.ruined as f64).div(self.trajectories as f64)
+    }
+
+    pub fn doubling_chance(&self) -> f64 {
+        (self.doubled as f64).div(self.trajectories as f64)
+    }
+
+    // None if no trajectory was ruined
+    pub fn median_hands_to_ruin(&self) -> Option<u64> {
+        if self.hands_to_ruin.is_empty() {
+            return None;
+        }
+        let mut hands = self.hands_to_ruin.clone();
+        hands.sort_unstable();
+        Some(hands[hands.len() / 2])
+    }
+
+    // Final bankroll at percentile p [0-100]
+    pub fn percentile(&self, p: f64) -> i64 {
+        percentile(&self.final_bankrolls, p)
+    }
+
+    pub fn mean_final_bankroll(&self) -> f64 {
+        (self.final_bankrolls.iter().sum::<i64>() as f64).div(self.trajectories as f64)
+    }
+
+    pub fn sum_results(&self) {
+        let median_str = match self.median_hands_to_ruin() {
+            Some(hands) => hands.to_formatted_string(&Locale::en),
+            None => "Never Ruined".to_string(),
+        };
+
+        println!("\n -- Bankroll Results --\n");
+        println!("Starting Bankroll: ${}", self.start_bankroll.to_formatted_string(&Locale::en));
+        println!("Trajectories: {} | Max Hands: {}",
+            self.trajectories.to_formatted_string(&Locale::en),
+            self.max_hands.to_formatted_string(&Locale::en));
+        println!("Risk of Ruin: {}%", 100_f64 * self.risk_of_ruin());
+        println!("Median Hands to Ruin: {}", median_str);
+        println!("Chance of Doubling: {}%", 100_f64 * self.doubling_chance());
+        println!("Mean Final Bankroll: ${}", self.mean_final_bankroll());
+        println!("Progressions Broken by Table Limits: {}", self.limit_stats.clamped_max + self.limit_stats.rejected);
+    }
+}
+
+impl <R: Rng + Clone> GamePool <R> {
+    // Plays `trajectories` independent runs starting from the settings bankroll, each on a fresh shoe.
+    // A run ends when the bankroll can't cover the next bet (ruin) or after `max_hands` played.
+    // Rounds sat out under table limits aren't hands, doubles & splits the bankroll can't cover are hit.
+    pub fn simulate_bankroll(&mut self, trajectories: u64, max_hands: u64, progress_bar: bool) -> Result<BankrollReport, GameError> {
+        let start_bankroll = self.settings.bankroll as i64;
+        let mut report = BankrollReport::new(start_bankroll, max_hands);
+
+        // Single game keeps the same rng, trajectories stay independent
+        // Progress in rounds, a trajectory ends early on ruin
+        let mut bj = self.new_game(progress_bar.then_some(trajectories * max_hands))?;
+        bj.bankroll_limited = true;
+
+        for _ in 0..trajectories {
+            bj.reshuffle();
+            bj.reset_bankroll(start_bankroll);
+            let mut hands: u64 = 0;
+            let mut ruined = false;
+            let mut doubled = false;
+            let mut sat_out = false;
+
+            while hands < max_hands {
+                match bj.play_round()? {
+                    Round::Played(_) => self.simulated_games += 1,
+                    // Round sat out, progression back at the base bet
+                    // Nothing changes before the next round, so a second refusal would repeat forever
+                    Round::Rejected if sat_out => break,
+                    Round::Rejected => {
+                        sat_out = true;
+                        continue;
+                    }
+                    Round::Ruined => {
+                        ruined = true;
+                        break;
+                    }
+                }
+                hands += 1;
+                sat_out = false;
+
+                if bj.bankroll >= 2 * start_bankroll {
+                    doubled = true;
+                }
+            }
+
+            report.record(hands, ruined, doubled, bj.bankroll);
+        }
+        report.limit_stats = bj.limit_stats.clone();
+        self.finish_game(&mut bj, progress_bar);
+
+        Ok(report)
+    }
+}
+
+// Run the same bankroll trajectories for each betting strat
+// Returns (strat name, report) in the given order
+pub fn simulate_betting_strats<R: Rng + Clone>(
+    settings: &GameSettings<R>,
+    betting_strats: Vec<Arc<Box<dyn StrategyFunc>>>,
+    trajectories: u64,
+    max_hands: u64,
+    progress_bar: bool,
+) -> Result<Vec<(String, BankrollReport)>, GameError> {
+    betting_strats.into_iter().map(|betting_strat| {
+        let name = betting_strat.to_string();
+        let mut strat_settings = settings.clone();
+        strat_settings.betting_strat = betting_strat;
+
+        let mut pool = GamePool::new(Arc::new(strat_settings.validate()?));
+        Ok((name, pool.simulate_bankroll(trajectories, max_hands, progress_bar)?))
+    }).collect()
+}
+
+// Side by side final bankroll distributions
+pub fn compare_bankrolls(reports: &[(String, BankrollReport)]) {
+    println!("\n -- Betting Strat Comparison --\n");
+    println!("{:<20} {:>8} {:>8} {:>12} {:>10} {:>10} {:>10} {:>10} {:>10} {:>10}",
+        "Strat", "Ruin %", "2x %", "Mean", "P5", "P25", "Median", "P75", "P95", "Breaks");
+    for (name, report) in reports {
+        println!("{:<20} {:>8.2} {:>8.2} {:>12.2} {:>10} {:>10} {:>10} {:>10} {:>10} {:>10}",
+            name,
+            100_f64 * report.risk_of_ruin(),
+            100_f64 * report.doubling_chance(),
+            report.mean_final_bankroll(),
+            report.percentile(5.0),
+            report.percentile(25.0),
+            report.percentile(50.0),
+            report.percentile(75.0),
+            report.percentile(95.0),
+            report.limit_stats.clamped_max + report.limit_stats.rejected,
+        );
+    }
+}
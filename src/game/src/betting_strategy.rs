@@ -1,40 +1,396 @@
-use crate::{GameState, Winner, StrategyFunc, playing_strategy::StratReturn};
+use serde::Deserialize;
+use crate::{BetRecord, GameView, Winner, StrategyFunc, playing_strategy::StratReturn, actions::legal_bet, error::GameError};
 
 pub struct ConstantBet;
 impl StrategyFunc for ConstantBet {
-    fn get_decision(&self, state: GameState) -> StratReturn {
-        StratReturn::Bet(state.init_bet)
+    fn get_decision(&self, state: &GameView) -> Result<StratReturn, GameError> {
+        Ok(StratReturn::Bet(state.init_bet))
     }
 
     fn to_string(&self) -> String {
         "Constant Bet".to_string()
     }
+
+    fn bets_flat(&self) -> bool {
+        true
+    }
 }
 
 pub struct Martingale;
 impl StrategyFunc for Martingale {
-    fn get_decision(&self, state: GameState) -> StratReturn {
+    fn get_decision(&self, state: &GameView) -> Result<StratReturn, GameError> {
         // Goal: Win payout should cover all previous losses 
         // If lost last hand, expontially increase bet
         // If Win: Reset bet to init
         // If Tied: Bet same amount
         
         let next_bet = match state.last_winner {
-            Winner::Dealer => { state.last_bet * 2 }, // Increase bet
+            Winner::Dealer => { state.last_bet.saturating_mul(2) }, // Increase bet
             Winner::Player => {state.init_bet }, // Reset bet
             Winner::Tie => { state.last_bet}, // Rebet same amount
             Winner::None => { state.init_bet}
         };
 
-        StratReturn::Bet(next_bet)
+        Ok(StratReturn::Bet(next_bet))
+    }
+
+    fn to_string(&self) -> String {
+        "Martingale".to_string()
+    }
+}
+
+
+// |-------------------------|
+// |   PROGRESSION SYSTEMS   |
+// |-------------------------|
+
+// All bets in units of the init bet
+// Ties (pushes) never move a progression
+
+// Consecutive wins at the end of the history, ties skipped
+fn win_streak(history: &[BetRecord]) -> usize {
+    history.iter().rev()
+        .filter(|record| record.winner != Winner::Tie)
+        .take_while(|record| record.winner == Winner::Player)
+        .count()
+}
+
+// Rounds since the last loss, streak strats need nothing older
+fn rounds_since_loss(history: &[BetRecord]) -> usize {
+    history.iter().rev().take_while(|record| record.winner != Winner::Dealer).count()
+}
+
+// Positive progression: Double after each win, bank profit after 3 wins
+// Loss resets to one unit
+pub struct Paroli;
+impl StrategyFunc for Paroli {
+    fn get_decision(&self, state: &GameView) -> Result<StratReturn, GameError> {
+        let step = win_streak(state.bet_history) % 3;
+        Ok(StratReturn::Bet(state.init_bet.saturating_mul(1 << step)))
+    }
+
+    fn to_string(&self) -> String {
+        "Paroli".to_string()
+    }
+
+    fn history_needed(&self, _init_bet: u32, history: &[BetRecord]) -> usize { rounds_since_loss(history) }
+}
+
+// 1-3-2-6: Bet 1, 3, 2, then 6 units on consecutive wins
+// Loss or completed cycle resets to one unit
+pub struct OneThreeTwoSix;
+impl StrategyFunc for OneThreeTwoSix {
+    fn get_decision(&self, state: &GameView) -> Result<StratReturn, GameError> {
+        let units = [1, 3, 2, 6];
+        let step = win_streak(state.bet_history) % units.len();
+        Ok(StratReturn::Bet(state.init_bet.saturating_mul(units[step])))
+    }
+
+    fn to_string(&self) -> String {
+        "1-3-2-6".to_string()
+    }
+
+    fn history_needed(&self, _init_bet: u32, history: &[BetRecord]) -> usize { rounds_since_loss(history) }
+}
+
+// D'Alembert: One unit up after a loss, one unit down after a win
+// Never below one unit
+pub struct DAlembert;
+impl StrategyFunc for DAlembert {
+    fn get_decision(&self, state: &GameView) -> Result<StratReturn, GameError> {
+        let unit = state.init_bet;
+        let next_bet = match state.last_winner {
+            Winner::Dealer => { state.last_bet.saturating_add(unit) },
+            Winner::Player => { state.last_bet.saturating_sub(unit).max(unit) },
+            Winner::Tie => { state.last_bet },
+            Winner::None => { unit },
+        };
+
+        Ok(StratReturn::Bet(next_bet))
+    }
+
+    fn to_string(&self) -> String {
+        "D'Alembert".to_string()
+    }
+}
+
+// Fibonacci: One step along 1, 1, 2, 3, 5, 8... after a loss, two steps back after a win
+// Cycle over once back at the first step
+pub struct Fibonacci;
+impl Fibonacci {
+    // Replay steps from the start of the cycle
+    // Returns (current step, rounds since cycle start)
+    fn replay(history: &[BetRecord]) -> (usize, usize) {
+        let mut step: usize = 0;
+        let mut cycle_len = 0;
+        for record in history {
+            match record.winner {
+                Winner::Dealer => step += 1,
+                Winner::Player => step = step.saturating_sub(2),
+                Winner::Tie | Winner::None => {}
+            }
+            cycle_len = if step == 0 { 0 } else { cycle_len + 1 };
+        }
+        (step, cycle_len)
+    }
+
+    pub fn units(step: usize) -> u32 {
+        let (mut a, mut b) = (1_u32, 1_u32);
+        for _ in 0..step {
+            (a, b) = (b, a.saturating_add(b));
+        }
+        a
+    }
+}
+
+impl StrategyFunc for Fibonacci {
+    fn get_decision(&self, state: &GameView) -> Result<StratReturn, GameError> {
+        let (step, _) = Fibonacci::replay(state.bet_history);
+        Ok(StratReturn::Bet(state.init_bet.saturating_mul(Fibonacci::units(step))))
     }
 
     fn to_string(&self) -> String {
-        todo!()
+        "Fibonacci".to_string()
+    }
+
+    fn history_needed(&self, _init_bet: u32, history: &[BetRecord]) -> usize { Fibonacci::replay(history).1 }
+}
+
+// Labouchere (Cancellation): Bet the sum of the first and last numbers in the line
+// Win crosses both off, loss adds the lost bet to the end
+// Cleared line wins the line's total, start a new one
+#[derive(Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Labouchere {
+    pub line: Vec<u32>, // Starting line in units
+}
+
+impl Default for Labouchere {
+    fn default() -> Self {
+        Labouchere { line: vec![1, 2, 3] }
     }
 }
 
+impl Labouchere {
+    fn next_bet(line: &[u32]) -> u32 {
+        match line.len() {
+            0 => 0,
+            1 => line[0],
+            n => line[0].saturating_add(line[n - 1]),
+        }
+    }
+
+    // Replay the line from the start of the cycle
+    // Returns (current line, rounds since line started)
+    pub fn replay(&self, history: &[BetRecord]) -> (Vec<u32>, usize) {
+        let mut line = self.line.clone();
+        let mut cycle_len = 0;
+        for record in history {
+            match record.winner {
+                Winner::Player => {
+                    line.remove(0);
+                    line.pop();
+                }
+                Winner::Dealer => line.push(Labouchere::next_bet(&line)),
+                Winner::Tie | Winner::None => {}
+            }
+            cycle_len += 1;
 
-// percent
-pub fn kelly_criterion(state: GameState) -> u32 { state.init_bet }
+            if line.is_empty() {
+                line = self.line.clone();
+                cycle_len = 0;
+            }
+        }
+        (line, cycle_len)
+    }
+}
 
+impl StrategyFunc for Labouchere {
+    fn get_decision(&self, state: &GameView) -> Result<StratReturn, GameError> {
+        let (line, _) = self.replay(state.bet_history);
+        Ok(StratReturn::Bet(state.init_bet.saturating_mul(Labouchere::next_bet(&line))))
+    }
+
+    fn to_string(&self) -> String {
+        format!("Labouchere {:?}", self.line)
+    }
+
+    fn problems(&self) -> Vec<String> {
+        match self.line.is_empty() || self.line.contains(&0) {
+            true => vec![format!("line must be non-empty w/o zero entries, found {:?}", self.line)],
+            false => vec![],
+        }
+    }
+
+    fn history_needed(&self, _init_bet: u32, history: &[BetRecord]) -> usize { self.replay(history).1 }
+}
+
+// Oscar's Grind: Grind out one unit of profit per series
+// Raise bet one unit after a win, never betting more than needed to finish the series
+// Same bet after a loss
+pub struct OscarsGrind;
+impl OscarsGrind {
+    // Replay the series from its start
+    // Returns (next bet, rounds since series start)
+    fn replay(unit: u32, history: &[BetRecord]) -> (u32, usize) {
+        let unit = unit as i64;
+        let mut bet = unit;
+        let mut profit: i64 = 0;
+        let mut series_len = 0;
+        for record in history {
+            profit += record.payoff;
+            series_len += 1;
+
+            if profit >= unit {
+                // Series won, start over
+                bet = unit;
+                profit = 0;
+                series_len = 0;
+            } else if record.winner == Winner::Player {
+                bet = (bet + unit).min((unit - profit).max(unit));
+            }
+        }
+        (bet as u32, series_len)
+    }
+}
+
+impl StrategyFunc for OscarsGrind {
+    fn get_decision(&self, state: &GameView) -> Result<StratReturn, GameError> {
+        Ok(StratReturn::Bet(OscarsGrind::replay(state.init_bet, state.bet_history).0))
+    }
+
+    fn to_string(&self) -> String {
+        "Oscar's Grind".to_string()
+    }
+
+    fn history_needed(&self, init_bet: u32, history: &[BetRecord]) -> usize {
+        OscarsGrind::replay(init_bet, history).1
+    }
+}
+
+// Kelly Criterion
+// Bet the fraction of bankroll that maximizes long run growth: edge / variance
+// Edge estimated linearly from the true count, off the top edge at TC 0
+// Fractional Kelly scales the bet down to cut variance at the cost of growth
+#[derive(Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct KellyCriterion {
+    pub fraction: f64,    // 1.0 = Full Kelly, 0.5 = Half Kelly
+    pub base_edge: f64,   // Player edge at true count 0
+    pub edge_per_tc: f64, // Edge gained per true count
+    pub variance: f64,    // Variance of a single hand
+    pub min_bet: u32,     // Bet when no advantage
+    pub max_bet: u32,     // Top of the spread, table max still applies
+    pub unit: u32,        // Bets rounded down to multiple of unit
+}
+
+impl Default for KellyCriterion {
+    // Hi-Lo w/ 6 decks, S17: ~-0.5% off the top, +0.5% per TC, variance ~1.33
+    fn default() -> Self {
+        KellyCriterion {
+            fraction: 1.0,
+            base_edge: -0.005,
+            edge_per_tc: 0.005,
+            variance: 1.33,
+            min_bet: 10,
+            max_bet: 500,
+            unit: 5,
+        }
+    }
+}
+
+impl KellyCriterion {
+    pub fn new(fraction: f64, min_bet: u32, max_bet: u32, unit: u32) -> Self {
+        KellyCriterion { fraction, min_bet, max_bet, unit, ..Default::default() }
+    }
+
+    pub fn advantage(&self, true_count: f64) -> f64 {
+        self.base_edge + self.edge_per_tc * true_count
+    }
+
+    pub fn bet_size(&self, bankroll: i64, true_count: f64) -> u32 {
+        let edge = self.advantage(true_count);
+        // No advantage (or no money): Minimum bet
+        if edge <= 0.0 || bankroll <= 0 {
+            return self.min_bet;
+        }
+
+        let optimal = bankroll as f64 * self.fraction * edge / self.variance;
+        let unit = self.unit.max(1) as f64;
+        let rounded = (optimal / unit).floor() * unit;
+
+        (rounded as u32).clamp(self.min_bet, self.max_bet)
+    }
+}
+
+impl StrategyFunc for KellyCriterion {
+    fn get_decision(&self, state: &GameView) -> Result<StratReturn, GameError> {
+        // Clamped to table limits, same as the table would
+        let bet = self.bet_size(state.bankroll, state.true_count);
+        Ok(StratReturn::Bet(legal_bet(bet, state.table_min, state.table_max, state.bet_increment)))
+    }
+
+    fn to_string(&self) -> String {
+        if self.fraction == 1.0 {
+            "Kelly Criterion".to_string()
+        } else {
+            format!("Fractional Kelly ({}x)", self.fraction)
+        }
+    }
+
+    fn problems(&self) -> Vec<String> {
+        let mut problems = vec![];
+        if self.min_bet > self.max_bet {
+            problems.push(format!("min_bet ({}) is above max_bet ({})", self.min_bet, self.max_bet));
+        }
+        if self.unit == 0 {
+            problems.push("unit must be positive".to_string());
+        }
+        if self.variance.is_nan() || self.variance <= 0.0 {
+            problems.push(format!("variance must be positive, found {}", self.variance));
+        }
+        if !(self.fraction > 0.0 && self.fraction <= 1.0) {
+            problems.push(format!("fraction must be in (0, 1], found {}", self.fraction));
+        }
+        problems
+    }
+
+    fn bet_unit(&self) -> Option<u32> { Some(self.unit) }
+
+    fn bet_limits(&self) -> Option<(u32, u32)> { Some((self.min_bet, self.max_bet)) }
+}
+
+// Bet Ramp: Units bet by true count, count based counterpart to a progression
+// Uses the highest step at or below the floored true count, one unit below the first step
+#[derive(Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BetRamp {
+    pub ramp: Vec<(i32, u32)>, // (true count, units)
+}
+
+impl Default for BetRamp {
+    // 1-8 spread, common for 6 deck Hi-Lo
+    fn default() -> Self {
+        BetRamp { ramp: vec![(1, 1), (2, 2), (3, 4), (4, 6), (5, 8)] }
+    }
+}
+
+impl BetRamp {
+    pub fn units(&self, true_count: f64) -> u32 {
+        let true_count = true_count.floor() as i32;
+        self.ramp.iter()
+            .filter(|(count, _)| *count <= true_count)
+            .max_by_key(|(count, _)| *count)
+            .map_or(1, |(_, units)| *units)
+    }
+}
+
+impl StrategyFunc for BetRamp {
+    fn get_decision(&self, state: &GameView) -> Result<StratReturn, GameError> {
+        Ok(StratReturn::Bet(state.init_bet.saturating_mul(self.units(state.true_count))))
+    }
+
+    fn to_string(&self) -> String {
+        format!("Bet Ramp {:?}", self.ramp)
+    }
+}
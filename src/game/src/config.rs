@@ -0,0 +1,389 @@
+//! Experiment definitions as TOML or JSON files, turned into `GameSettings` through the registry.
+//!
+//! ```toml
+//! preset = "vegas-strip" # optional, keys below override it
+//! rounds = 1_000_000
+//! seed = 7
+//!
+//! [shoe]
+//! decks = 6
+//! penetration = 0.75
+//!
+//! [strategies]
+//! player = "basic"
+//! betting = { name = "ramp", ramp = [[1, 1], [2, 2], [3, 4], [4, 8]] }
+//! ```
+
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use crate::error::GameError;
+use crate::presets::{find_preset, presets};
+use crate::registry::{StrategyKind, StrategyRegistry, StrategySpec};
+use crate::settings::GameSettingsBuilder;
+use crate::shoe::{ShoeKind, CSM_RESERVOIR};
+use crate::shuffle::ShuffleProcedure;
+use crate::{BlackjackPayout, DoubleRule, GameSettings, LimitPolicy, TableRules, ValidSettings};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SimulationConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preset: Option<String>, // Base rules & shoe, see `presets`
+    pub rounds: u64,
+    pub seed: Option<u64>, // Random if missing
+    pub rules: RulesConfig,
+    pub shoe: ShoeConfig,
+    pub table: TableConfig,
+    pub strategies: StrategiesConfig,
+}
+
+impl Default for SimulationConfig {
+    fn default() -> Self {
+        SimulationConfig {
+            preset: None,
+            rounds: 1_000_000,
+            seed: None,
+            rules: RulesConfig::default(),
+            shoe: ShoeConfig::default(),
+            table: TableConfig::default(),
+            strategies: StrategiesConfig::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RulesConfig {
+    pub dealer_cutoff: u8,
+    pub max_splits: u8,
+    pub early_surrender: bool,
+    pub late_surrender: bool,
+    pub hit_soft_17: bool,
+    pub double_after_split: bool,
+    pub double_on: DoubleRule,
+    pub blackjack_pays: BlackjackPayout,
+    pub dealer_peeks: bool,
+    pub split_aces_one_card: bool,
+}
+
+impl Default for RulesConfig {
+    fn default() -> Self {
+        RulesConfig::from_table_rules(TableRules::default())
+    }
+}
+
+impl RulesConfig {
+    // Cutoff, splits & surrender at their defaults
+    pub fn from_table_rules(rules: TableRules) -> Self {
+        RulesConfig {
+            dealer_cutoff: 17,
+            max_splits: 3,
+            early_surrender: false,
+            late_surrender: false,
+            hit_soft_17: rules.hit_soft_17,
+            double_after_split: rules.double_after_split,
+            double_on: rules.double_on,
+            blackjack_pays: rules.blackjack_pays,
+            dealer_peeks: rules.dealer_peeks,
+            split_aces_one_card: rules.split_aces_one_card,
+        }
+    }
+
+    pub fn table_rules(&self) -> TableRules {
+        TableRules {
+            hit_soft_17: self.hit_soft_17,
+            double_after_split: self.double_after_split,
+            double_on: self.double_on,
+            blackjack_pays: self.blackjack_pays,
+            dealer_peeks: self.dealer_peeks,
+            split_aces_one_card: self.split_aces_one_card,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ShoeConfig {
+    pub decks: u8,
+    pub cut_card: bool,
+    pub penetration: Option<f64>, // Share dealt before the cut card, (0, 1]
+    pub kind: ShoeKind,           // Cut card & penetration only used when "shuffled"
+    pub reservoir: usize,         // "csm" only, cards dealt before returned discards can come out
+    pub shuffle: ShuffleProcedure, // "shuffled" only, steps from the previous shoe to the next
+}
+
+impl Default for ShoeConfig {
+    fn default() -> Self {
+        ShoeConfig { decks: 6, cut_card: true, penetration: None, kind: ShoeKind::Shuffled, reservoir: CSM_RESERVOIR, shuffle: ShuffleProcedure::default() }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TableConfig {
+    pub base_bet: u32,
+    pub bankroll: u32,
+    pub min: u32,
+    pub max: u32,
+    pub increment: u32,
+    pub limit_policy: LimitPolicy,
+}
+
+impl Default for TableConfig {
+    fn default() -> Self {
+        TableConfig { base_bet: 10, bankroll: 10_000, min: 2, max: 500, increment: 1, limit_policy: LimitPolicy::Clamp }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StrategiesConfig {
+    pub player: StrategySpec,
+    pub dealer: StrategySpec,
+    pub betting: StrategySpec,
+    pub counting: StrategySpec,
+    pub insurance: StrategySpec,
+}
+
+impl Default for StrategiesConfig {
+    fn default() -> Self {
+        StrategiesConfig {
+            player: "basic".into(),
+            dealer: "dealer-play".into(),
+            betting: "constant".into(),
+            counting: "hi-lo".into(),
+            insurance: "none".into(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Io(String),          // Couldn't read or write the file
+    Parse(String),       // Not valid TOML/JSON or doesn't fit the schema
+    Invalid(Vec<String>), // Parsed, but values or strategies are unusable
+    Game(GameError),      // Settings built, but the game couldn't be played
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io(msg) => write!(f, "{}", msg),
+            ConfigError::Game(err) => write!(f, "{}", err),
+            ConfigError::Parse(msg) => write!(f, "{}", msg),
+            ConfigError::Invalid(problems) => {
+                write!(f, "{} problem{}:", problems.len(), if problems.len() == 1 { "" } else { "s" })?;
+                for problem in problems {
+                    write!(f, "\n - {}", problem)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Error for ConfigError {}
+
+impl From<GameError> for ConfigError {
+    fn from(err: GameError) -> Self {
+        match err {
+            GameError::InvalidSettings(problems) => ConfigError::Invalid(problems),
+            err => ConfigError::Game(err),
+        }
+    }
+}
+
+fn is_json(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("json"))
+}
+
+impl SimulationConfig {
+    // JSON if the extension is .json, TOML otherwise
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path).map_err(|err| ConfigError::Io(format!("{}: {}", path.display(), err)))?;
+        let parsed = match is_json(path) {
+            true => SimulationConfig::from_json(&text),
+            false => SimulationConfig::from_toml(&text),
+        };
+        parsed.map_err(|err| match err {
+            ConfigError::Parse(msg) => ConfigError::Parse(format!("{}: {}", path.display(), msg)),
+            other => other,
+        })
+    }
+
+    pub fn from_toml(text: &str) -> Result<Self, ConfigError> {
+        let parse_error = |err: toml::de::Error| ConfigError::Parse(err.to_string());
+        let table: toml::Table = toml::from_str(text).map_err(parse_error)?;
+        match table.contains_key("preset") {
+            true => SimulationConfig::over_preset(serde_json::to_value(table).map_err(|err| ConfigError::Parse(err.to_string()))?),
+            false => toml::from_str(text).map_err(parse_error), // Keeps line numbers in errors
+        }
+    }
+
+    pub fn from_json(text: &str) -> Result<Self, ConfigError> {
+        let value: Value = serde_json::from_str(text).map_err(|err| ConfigError::Parse(err.to_string()))?;
+        SimulationConfig::from_value(value)
+    }
+
+    // Config as parsed JSON or TOML, preset applied if named
+    pub fn from_value(value: Value) -> Result<Self, ConfigError> {
+        match value.get("preset").is_some() {
+            true => SimulationConfig::over_preset(value),
+            false => serde_json::from_value(value).map_err(|err| ConfigError::Parse(err.to_string())),
+        }
+    }
+
+    // Preset's config w/ every key in `value` replacing the preset's, section by section
+    fn over_preset(value: Value) -> Result<Self, ConfigError> {
+        let name = value["preset"].as_str().ok_or(ConfigError::Parse("preset must be a name".to_string()))?;
+        let preset = find_preset(name).ok_or_else(|| ConfigError::Invalid(vec![unknown_preset(name)]))?;
+        let mut merged = serde_json::to_value(preset.config()).expect("Config serializable");
+        if let (Value::Object(merged), Value::Object(value)) = (&mut merged, value) {
+            for (key, value) in value {
+                match (merged.get_mut(&key), value) {
+                    (Some(Value::Object(section)), Value::Object(keys)) => section.extend(keys),
+                    (_, value) => { merged.insert(key, value); }
+                }
+            }
+        }
+        serde_json::from_value(merged).map_err(|err| ConfigError::Parse(err.to_string()))
+    }
+
+    pub fn to_toml(&self) -> String {
+        toml::to_string(self).expect("Config serializable")
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), ConfigError> {
+        let path = path.as_ref();
+        let text = match is_json(path) {
+            true => serde_json::to_string_pretty(self).expect("Config serializable"),
+            false => self.to_toml(),
+        };
+        fs::write(path, text).map_err(|err| ConfigError::Io(format!("{}: {}", path.display(), err)))
+    }
+
+    // Problems only a config can have, settings are checked by `GameSettings::validate`
+    pub fn problems(&self) -> Vec<String> {
+        let mut problems = vec![];
+        if let Some(name) = self.preset.as_deref() {
+            if find_preset(name).is_none() {
+                problems.push(unknown_preset(name));
+            }
+        }
+        if self.rounds == 0 {
+            problems.push("rounds must be positive".to_string());
+        }
+        problems
+    }
+
+    // Builder w/ the config's values & strategies, for settings the config can't express
+    // All problems reported together, settings named by their config key
+    pub fn builder<R: Rng + SeedableRng>(&self, registry: &StrategyRegistry) -> Result<GameSettingsBuilder<R>, ConfigError> {
+        self.builder_named(registry, SETTING_KEYS)
+    }
+
+    // Same, settings in problems named by `names` e.g. command line flags
+    pub fn builder_named<R: Rng + SeedableRng>(&self, registry: &StrategyRegistry, names: &[(&str, &str)])
+    -> Result<GameSettingsBuilder<R>, ConfigError> {
+        let mut problems = self.problems();
+        let mut builder = GameSettings::builder()
+            .decks(self.shoe.decks)
+            .cut_card(self.shoe.cut_card)
+            .shoe(self.shoe.kind)
+            .reservoir(self.shoe.reservoir)
+            .shuffle(self.shoe.shuffle.clone())
+            .max_splits(self.rules.max_splits)
+            .init_bet(self.table.base_bet)
+            .bankroll(self.table.bankroll)
+            .dealer_cutoff(self.rules.dealer_cutoff)
+            .early_surrender(self.rules.early_surrender)
+            .late_surrender(self.rules.late_surrender)
+            .rules(self.rules.table_rules())
+            .table_limits(self.table.min, self.table.max)
+            .bet_increment(self.table.increment)
+            .limit_policy(self.table.limit_policy);
+        if let Some(penetration) = self.shoe.penetration {
+            builder = builder.penetration(penetration);
+        }
+        if let Some(seed) = self.seed {
+            builder = builder.seed(seed);
+        }
+
+        // Strategies that don't build keep the builder's default
+        let strategies = &self.strategies;
+        let mut build = |kind: StrategyKind, spec: &StrategySpec| match registry.build(kind, spec) {
+            Ok(strat) => Some(strat),
+            Err(err) => {
+                problems.push(format!("strategies.{}: {}", kind_key(kind), err));
+                None
+            }
+        };
+        if let Some(strat) = build(StrategyKind::Playing, &strategies.player) {
+            builder = builder.player_strat(strat);
+        }
+        if let Some(strat) = build(StrategyKind::Dealer, &strategies.dealer) {
+            builder = builder.dealer_strat(strat);
+        }
+        if let Some(strat) = build(StrategyKind::Betting, &strategies.betting) {
+            builder = builder.betting_strat(strat);
+        }
+        if let Some(strat) = build(StrategyKind::Counting, &strategies.counting) {
+            builder = builder.counting_strat(strat);
+        }
+        if let Some(strat) = build(StrategyKind::Insurance, &strategies.insurance) {
+            builder = builder.insurance_strat(strat);
+        }
+
+        // Settings problems joined w/ the rest rather than found on build
+        if !problems.is_empty() {
+            if let Err(GameError::InvalidSettings(found)) = builder.build().map_err(|err| err.rename_settings(names)) {
+                problems.extend(found);
+            }
+            return Err(ConfigError::Invalid(problems));
+        }
+        Ok(builder)
+    }
+
+    // Shuffled & cut shoe, strategies built by the registry
+    pub fn settings<R: Rng + SeedableRng>(&self, registry: &StrategyRegistry) -> Result<Arc<ValidSettings<R>>, ConfigError> {
+        Ok(self.builder(registry)?.build().map_err(|err| err.rename_settings(SETTING_KEYS))?)
+    }
+}
+
+// Config key of each setting `GameSettings::validate` names
+pub const SETTING_KEYS: &[(&str, &str)] = &[
+    ("decks", "shoe.decks"),
+    ("penetration", "shoe.penetration"),
+    ("reservoir", "shoe.reservoir"),
+    ("shuffle", "shoe.shuffle"),
+    ("max_splits", "rules.max_splits"),
+    ("dealer_cutoff", "rules.dealer_cutoff"),
+    ("init_bet", "table.base_bet"),
+    ("bankroll", "table.bankroll"),
+    ("table_min", "table.min"),
+    ("table_max", "table.max"),
+    ("bet_increment", "table.increment"),
+];
+
+fn unknown_preset(name: &str) -> String {
+    let known = presets().iter().map(|preset| preset.key).collect::<Vec<_>>();
+    format!("preset: Unknown preset '{}', expected one of: {}", name, known.join(", "))
+}
+
+// Field name in `StrategiesConfig`
+fn kind_key(kind: StrategyKind) -> &'static str {
+    match kind {
+        StrategyKind::Playing => "player",
+        StrategyKind::Dealer => "dealer",
+        StrategyKind::Betting => "betting",
+        StrategyKind::Counting => "counting",
+        StrategyKind::Insurance => "insurance",
+    }
+}
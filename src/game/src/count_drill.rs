@@ -0,0 +1,236 @@
+//! Card counting speed drill. Cards are flipped at a set pace & the running and true count
+//! asked for at checkpoints, counted w/ any counting strategy. Cards come straight off a shoe
+//! or from rounds the engine plays, checked against its own running count.
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use rand::Rng;
+use crate::deck::{Card, MultiDeck};
+use crate::interactive::{Console, SharedConsole};
+use crate::playing_strategy::{StratReturn, StrategyFunc};
+use crate::error::GameError;
+use crate::{Game, GameView, ValidSettings, Winner};
+
+const TRUE_COUNT_TOLERANCE: f64 = 0.5; // Deck estimates aren't exact
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DrillSettings {
+    pub group_size: usize,       // Cards flipped together
+    pub pace: Duration,          // How long each group stays up
+    pub checkpoint_every: usize, // Cards between count checks
+    pub cards: Option<usize>,    // Cards shown, the shoe less half a deck if None
+}
+
+impl Default for DrillSettings {
+    fn default() -> Self {
+        DrillSettings { group_size: 1, pace: Duration::from_millis(800), checkpoint_every: 20, cards: None }
+    }
+}
+
+// Count the player should have after `cards_seen` cards
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Checkpoint {
+    pub cards_seen: usize,
+    pub running_count: i32,
+    pub true_count: f64,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct CountScore {
+    pub checkpoints: u32,
+    pub running_correct: u32,
+    pub true_correct: u32,
+    pub cards: u64,
+    pub flip_time: Duration,   // Cards on screen
+    pub answer_time: Duration, // Summed over checkpoints
+}
+
+impl CountScore {
+    pub fn running_accuracy(&self) -> f64 {
+        100.0 * self.running_correct as f64 / self.checkpoints.max(1) as f64
+    }
+
+    pub fn true_accuracy(&self) -> f64 {
+        100.0 * self.true_correct as f64 / self.checkpoints.max(1) as f64
+    }
+
+    pub fn mean_answer_secs(&self) -> f64 {
+        self.answer_time.as_secs_f64() / self.checkpoints.max(1) as f64
+    }
+
+    // Answering included, what a table would have to wait for
+    pub fn cards_per_minute(&self) -> f64 {
+        let minutes = (self.flip_time + self.answer_time).as_secs_f64() / 60.0;
+        if minutes > 0.0 { self.cards as f64 / minutes } else { 0.0 }
+    }
+
+    // Running count exact, true count within tolerance
+    pub fn record(&mut self, expected: &Checkpoint, running_count: i32, true_count: f64, answer_time: Duration) -> (bool, bool) {
+        let running_ok = running_count == expected.running_count;
+        let true_ok = (true_count - expected.true_count).abs() <= TRUE_COUNT_TOLERANCE;
+        self.checkpoints += 1;
+        self.running_correct += running_ok as u32;
+        self.true_correct += true_ok as u32;
+        self.answer_time += answer_time;
+        (running_ok, true_ok)
+    }
+}
+
+// Tag the counting system gives one card
+pub fn card_count(system: &dyn StrategyFunc, card: Card) -> Result<i8, GameError> {
+    let cards = [card];
+    let state = GameView::new(0, 0, &cards, None, None, None, None, 17, false, Winner::None, 0, 0.0, false, false);
+    match system.get_decision(&state)? {
+        StratReturn::Count(delta) => Ok(delta),
+        _ => Err(GameError::wrong_decision(system, "Count")),
+    }
+}
+
+// Running count per deck left to deal, as counted at the table
+pub fn true_count(running_count: i32, cards_left: usize) -> f64 {
+    running_count as f64 * 52.0 / cards_left.max(1) as f64
+}
+
+pub struct CountDrill<R: Rng + Clone> {
+    settings: Arc<ValidSettings<R>>, // Decks, counting system & the strategies playing table rounds
+    rng: R,
+    pub drill: DrillSettings,
+    pub score: CountScore,
+}
+
+impl<R: Rng + Clone> CountDrill<R> {
+    pub fn new(settings: Arc<ValidSettings<R>>, drill: DrillSettings) -> Self {
+        let rng = settings.rng.clone();
+        CountDrill { settings, rng, drill, score: CountScore::default() }
+    }
+
+    pub fn system_name(&self) -> String {
+        self.settings.counting_strat.to_string()
+    }
+
+    // Cards straight off a freshly shuffled shoe
+    pub fn flip_drill(&mut self, console: &SharedConsole) -> Result<CountScore, GameError> {
+        let mut console = console.lock().expect("Console poisoned");
+        let mut shoe = MultiDeck::new(self.settings.deck.deck_count, false);
+        shoe.shuffle(&mut self.rng);
+        let shoe_size = shoe.decks.cards.len();
+        let cards = self.drill.cards.unwrap_or(shoe_size.saturating_sub(26)).min(shoe_size);
+        self.intro(&mut console, shoe_size);
+
+        let (mut seen, mut since_check, mut running_count) = (0, 0, 0);
+        while seen < cards {
+            let group = (0..self.drill.group_size.max(1).min(cards - seen))
+                .map(|_| shoe.draw().expect("Cards left"))
+                .collect::<Vec<_>>();
+            for card in group.iter() {
+                running_count += card_count(self.settings.counting_strat.as_ref().as_ref(), *card)? as i32;
+            }
+            self.flip(&mut console, &group);
+            seen += group.len();
+            since_check += group.len();
+
+            if since_check >= self.drill.checkpoint_every || seen == cards {
+                since_check = 0;
+                let expected = Checkpoint { cards_seen: seen, running_count, true_count: true_count(running_count, shoe_size - seen) };
+                if !self.checkpoint(&mut console, &expected) {
+                    break;
+                }
+            }
+        }
+        self.summary(&mut console);
+        Ok(self.score)
+    }
+
+    // Rounds played out by the settings' strategies, checked against the engine's running count
+    // Runs to the cut card or `cards`, whichever comes first
+    pub fn table_drill(&mut self, console: &SharedConsole) -> Result<CountScore, GameError> {
+        let mut console = console.lock().expect("Console poisoned");
+        let mut game = Game::from_settings(self.settings.clone());
+        let shoe_size = 52 * self.settings.deck.deck_count as usize;
+        let cards = self.drill.cards.unwrap_or(shoe_size.saturating_sub(26));
+        self.intro(&mut console, shoe_size);
+
+        let (mut seen, mut since_check) = (0, 0);
+        loop {
+            let before = game.played_cards.len();
+            game.deal(game.init_bet)?;
+            game.play_hand()?;
+            // Shoe ran out mid round, count started over
+            let round = game.played_cards[before.min(game.played_cards.len())..].to_vec();
+            for group in round.chunks(self.drill.group_size.max(1)) {
+                self.flip(&mut console, group);
+            }
+            seen += round.len();
+            since_check += round.len();
+
+            let shoe_done = game.cut_card_reached || seen >= cards || shoe_size - game.played_cards.len() < 26;
+            if since_check >= self.drill.checkpoint_every || shoe_done {
+                since_check = 0;
+                let cards_left = shoe_size - game.played_cards.len();
+                let expected = Checkpoint { cards_seen: seen, running_count: game.running_count, true_count: true_count(game.running_count, cards_left) };
+                if !self.checkpoint(&mut console, &expected) {
+                    break;
+                }
+            }
+            game.reset_hands();
+            if shoe_done {
+                break;
+            }
+        }
+        self.summary(&mut console);
+        Ok(self.score)
+    }
+
+    fn intro(&self, console: &mut Console, shoe_size: usize) {
+        console.say(&format!(
+            "{} over {} decks, {} card(s) at a time. Count asked every {} cards, q to quit",
+            self.system_name(), shoe_size / 52, self.drill.group_size.max(1), self.drill.checkpoint_every
+        ));
+    }
+
+    fn flip(&mut self, console: &mut Console, group: &[Card]) {
+        let start = Instant::now();
+        let text = group.iter().map(|card| console.cards(card)).collect::<Vec<_>>().join(" ");
+        console.flash(&text, self.drill.pace);
+        self.score.cards += group.len() as u64;
+        self.score.flip_time += start.elapsed();
+    }
+
+    // False once the player quits or input ends
+    fn checkpoint(&mut self, console: &mut Console, expected: &Checkpoint) -> bool {
+        console.say(&format!("\n{} cards seen", expected.cards_seen));
+        let start = Instant::now();
+        let Some(running_count) = ask_number::<i32>(console, "Running count") else { return false };
+        let Some(true_count) = ask_number::<f64>(console, "True count") else { return false };
+        let (running_ok, true_ok) = self.score.record(expected, running_count, true_count, start.elapsed());
+
+        let verdict = |ok: bool| if ok { "right" } else { "wrong" };
+        console.say(&format!(
+            "Running count {:+} ({}), true count {:+.1} ({})",
+            expected.running_count, verdict(running_ok), expected.true_count, verdict(true_ok)
+        ));
+        true
+    }
+
+    fn summary(&self, console: &mut Console) {
+        let score = &self.score;
+        console.say(&format!(
+            "\n{} checkpoints: running count {:.0}% right, true count {:.0}% right\n{:.1}s per answer, {:.0} cards a minute",
+            score.checkpoints, score.running_accuracy(), score.true_accuracy(), score.mean_answer_secs(), score.cards_per_minute()
+        ));
+    }
+}
+
+// None if the player quits
+fn ask_number<T: std::str::FromStr>(console: &mut Console, prompt: &str) -> Option<T> {
+    loop {
+        let answer = console.ask(prompt)?;
+        if answer == "q" || answer == "quit" {
+            return None;
+        }
+        match answer.parse() {
+            Ok(number) => return Some(number),
+            Err(_) => console.say(&format!("'{}' isn't a number", answer)),
+        }
+    }
+}
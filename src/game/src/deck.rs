@@ -2,10 +2,14 @@
 //! Provides a [`Deck`] implementation for simulating black jack games.
 
 use crate::playing_strategy::DealerUpcardStrength;
+use crate::shoe::{ShoeKind, CSM_RESERVOIR};
+use crate::shuffle::ShuffleProcedure;
 use core::slice::Iter;
 use rand::seq::SliceRandom; // Required for shuffling the deck
 use rand::Rng;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 use std::fmt;
+use std::str::FromStr;
 
 /// Card Suit representation
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
@@ -24,6 +28,39 @@ impl Suit {
     }
 }
 
+impl Suit {
+    pub fn symbol(&self) -> char {
+        match self {
+            Suit::Hearts => '\u{2665}',
+            Suit::Diamonds => '\u{2666}',
+            Suit::Clubs => '\u{2663}',
+            Suit::Spades => '\u{2660}',
+        }
+    }
+
+    // For terminals w/o the symbols
+    pub fn letter(&self) -> char {
+        match self {
+            Suit::Hearts => 'h',
+            Suit::Diamonds => 'd',
+            Suit::Clubs => 'c',
+            Suit::Spades => 's',
+        }
+    }
+}
+
+// Letter either case or symbol
+impl FromStr for Suit {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        Suit::iterator()
+            .find(|suit| text.eq_ignore_ascii_case(&suit.letter().to_string()) || text == suit.symbol().to_string())
+            .copied()
+            .ok_or_else(|| format!("Unknown suit '{}', expected h, d, c or s", text))
+    }
+}
+
 impl fmt::Display for Suit {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -95,6 +132,43 @@ impl Rank {
     }
 }
 
+impl Rank {
+    // As printed on the card, 10 for ten
+    pub fn label(&self) -> &'static str {
+        match self {
+            Rank::Ace => "A",
+            Rank::Two => "2",
+            Rank::Three => "3",
+            Rank::Four => "4",
+            Rank::Five => "5",
+            Rank::Six => "6",
+            Rank::Seven => "7",
+            Rank::Eight => "8",
+            Rank::Nine => "9",
+            Rank::Ten => "10",
+            Rank::Jack => "J",
+            Rank::Queen => "Q",
+            Rank::King => "K",
+            Rank::Blank => "X",
+        }
+    }
+}
+
+// Label either case, T for ten too
+impl FromStr for Rank {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        if text.eq_ignore_ascii_case("T") {
+            return Ok(Rank::Ten);
+        }
+        Rank::iterator()
+            .find(|rank| text.eq_ignore_ascii_case(rank.label()))
+            .copied()
+            .ok_or_else(|| format!("Unknown rank '{}', expected A, 2-10, J, Q or K", text))
+    }
+}
+
 impl fmt::Display for Rank {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -165,16 +239,60 @@ impl Card {
     }
 }
 
+impl Card {
+    // Compact code: rank then suit initial, e.g. "AS", "10H", "KD"
+    pub fn code(&self) -> String {
+        match self.rank {
+            Rank::Blank => Rank::Blank.label().to_string(),
+            rank => format!("{}{}", rank.label(), self.suit.letter().to_ascii_uppercase()),
+        }
+    }
+
+    // Code as written by `code`, X for the blank cut card
+    pub fn from_code(code: &str) -> Option<Card> {
+        match code {
+            "X" => Some(Card { rank: Rank::Blank, suit: Suit::Hearts, soft: true }),
+            code => code.parse().ok(),
+        }
+    }
+}
+
+// Rank then suit, either case: "Kh", "10s", "AS", "T♦"
+impl FromStr for Card {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let text = text.trim();
+        let Some((split, _)) = text.char_indices().last().filter(|(split, _)| *split > 0) else {
+            return Err(format!("Card '{}' needs a rank & a suit, e.g. Kh or 10s", text));
+        };
+        let (rank, suit) = text.split_at(split);
+        Ok(Card { rank: rank.parse()?, suit: suit.parse()?, soft: true })
+    }
+}
+
+// Cards serialized by code
+impl Serialize for Card {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.code())
+    }
+}
+
+impl<'de> Deserialize<'de> for Card {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let code = String::deserialize(deserializer)?;
+        Card::from_code(&code).ok_or_else(|| de::Error::custom(format!("invalid card code: {}", code)))
+    }
+}
+
+// Rank & suit symbol, `{:#}` for a suit letter instead: [K ♥] or [K h]
 impl fmt::Display for Card {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let suit_code = match self.suit {
-            Suit::Hearts => '\u{2665}',
-            Suit::Diamonds => '\u{2666}',
-            Suit::Clubs => '\u{2663}',
-            Suit::Spades => '\u{2660}', //127184
+        let suit = match f.alternate() {
+            true => self.suit.letter(),
+            false => self.suit.symbol(),
         };
-
-        write!(f, "[{} {}]", self.value(), suit_code)
+        write!(f, "[{} {}]", self.rank.label(), suit)
     }
 }
 
@@ -220,6 +338,10 @@ pub struct MultiDeck {
     pub decks: Deck,
     pub deck_count: u8,
     pub contains_blank: bool,
+    pub penetration: Option<f64>, // Share of the shoe dealt before the cut card, None = 59-69 cards left
+    pub kind: ShoeKind,           // Shoe the game deals from, see `MultiDeck::build_shoe`
+    pub reservoir: usize,         // Continuous shuffler only
+    pub shuffle: ShuffleProcedure, // How the previous shoe is shuffled into the next, hand shuffled only
 }
 
 impl MultiDeck {
@@ -235,18 +357,44 @@ impl MultiDeck {
 
         let decks = Deck { cards: flattened_decks };
 
-        Self { decks, contains_blank, deck_count: size }
+        Self { decks, contains_blank, deck_count: size, penetration: None, kind: ShoeKind::Shuffled, reservoir: CSM_RESERVOIR, shuffle: ShuffleProcedure::default() }
+    }
+
+    pub fn with_penetration(mut self, penetration: f64) -> Self {
+        self.penetration = Some(penetration);
+        self
+    }
+
+    pub fn with_kind(mut self, kind: ShoeKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    pub fn with_reservoir(mut self, reservoir: usize) -> Self {
+        self.reservoir = reservoir;
+        self
+    }
+
+    pub fn with_shuffle(mut self, shuffle: ShuffleProcedure) -> Self {
+        self.shuffle = shuffle;
+        self
     }
 
     pub fn insert_blank(&mut self, rng: &mut impl Rng) {
-        // Last 60-70 cards randomly
         let blank_card = Card {
             rank: Rank::Blank,
             suit: Suit::Hearts,
             soft: true,
         };
-        let offset = rng.gen_range(59..70); // Blank card put randomly 
-        // let index = self.decks.cards.len() - offset;
+        // Cards drawn from the back, offset = cards left behind the blank
+        let offset = match self.penetration {
+            Some(penetration) => {
+                let len = self.decks.cards.len();
+                (((1.0 - penetration) * len as f64).round() as usize).min(len)
+            }
+            // Last 60-70 cards randomly, at most half of a small shoe
+            None => rng.gen_range(59..70).min(self.decks.cards.len() / 2),
+        };
         self.decks.cards.insert(offset, blank_card);
     }
 
@@ -262,7 +410,7 @@ impl MultiDeck {
     }
 }
 
-#[derive(Clone, PartialEq, Eq, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum HandState {
     Init,        // Not dealt cards yet
     Playing,     // Active player
@@ -294,12 +442,30 @@ impl Default for Hand {
     }
 }
 
+// Cards run together, `{:#}` passed on to each
 impl fmt::Display for Hand {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         for card in self.cards.iter() {
-            let _ = write!(f, "{}", card);
+            match f.alternate() {
+                true => write!(f, "{:#}", card)?,
+                false => write!(f, "{}", card)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+// Cards by code, space separated: "As 7d". Playing, default bet
+impl FromStr for Hand {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut hand = Hand::default();
+        hand.set_state(HandState::Playing);
+        for card in text.split_whitespace() {
+            hand.add_card(card.parse()?);
         }
-        write!(f, "")
+        Ok(hand)
     }
 }
 
@@ -330,13 +496,14 @@ impl Hand {
         }
     }
 
+    // Two cards of a rank, a hit pair is just a total
     pub fn contains_pair(&self) -> bool {
-        self.cards.get(0).expect("No cards").rank == self.cards.get(1).expect("No 2nd card").rank
+        self.cards.len() == 2 && self.cards[0].rank == self.cards[1].rank
     }
 
     pub fn contains_pair_of(&self, target_card: Card) -> bool {
         if self.contains_pair() {
-            self.cards.get(0).expect("No cards").rank == target_card.rank
+            self.cards.first().expect("No cards").rank == target_card.rank
         } else {
             false
         }
@@ -352,15 +519,18 @@ impl Hand {
         })
     }
 
+    // One soft ace counted as 1
     pub fn deflate_ace(&mut self) {
-        for card in self.cards.iter_mut() {
-            // Find Ace
-            if card.value() == 11 {
-                // Check for soft ace
-                if card.is_inflated() {
-                    card.deflate()
-                }
-            }
+        if let Some(card) = self.cards.iter_mut().find(|card| card.value() == 11 && card.is_inflated()) {
+            card.deflate()
+        }
+    }
+
+    // Card added w/ soft aces counted as 1 until the hand is 21 or under
+    pub fn add_card(&mut self, card: Card) {
+        self.cards.push(card);
+        while self.value() > 21 && self.contains_soft_ace() {
+            self.deflate_ace();
         }
     }
 
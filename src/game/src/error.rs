@@ -0,0 +1,77 @@
+//! What can go wrong running a game, reported instead of panicking.
+
+use std::error::Error;
+use std::fmt;
+use crate::playing_strategy::StrategyFunc;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GameError {
+    InvalidSettings(Vec<String>), // Found by `GameSettings::validate`, all problems together
+    EmptyShoe,                    // No card to draw, even after a reshuffle
+    NotDealt,                     // Round played before the deal
+    NoSuchHand(usize),            // Player hand index out of range
+    NotAPair(usize),              // Split asked of a hand that isn't a pair
+    NoSuchRank(u8),               // Rank index outside Ace = 1 to King = 13
+    Busted(u8),                   // Decision asked for a hand already over 21
+    WrongDecision { strategy: String, expected: &'static str }, // Strategy answered w/ the wrong `StratReturn`
+}
+
+impl GameError {
+    pub(crate) fn wrong_decision(strategy: &dyn StrategyFunc, expected: &'static str) -> Self {
+        GameError::WrongDecision { strategy: strategy.to_string(), expected }
+    }
+
+    // Setting names in problems swapped for what the caller calls them, e.g. flags or config keys
+    // `names` pairs a `GameSettings` field w/ its name at the edge
+    pub fn rename_settings(self, names: &[(&str, &str)]) -> Self {
+        match self {
+            GameError::InvalidSettings(problems) => {
+                GameError::InvalidSettings(problems.iter().map(|problem| rename_words(problem, names)).collect())
+            }
+            err => err,
+        }
+    }
+}
+
+// Whole words only, so `table_min` isn't touched by a rename of `min`
+fn rename_words(text: &str, names: &[(&str, &str)]) -> String {
+    let mut renamed = String::with_capacity(text.len());
+    let mut word = String::new();
+    for c in text.chars().chain(std::iter::once(' ')) {
+        if c.is_ascii_alphanumeric() || c == '_' {
+            word.push(c);
+            continue;
+        }
+        match names.iter().find(|(name, _)| *name == word) {
+            Some((_, edge)) => renamed.push_str(edge),
+            None => renamed.push_str(&word),
+        }
+        word.clear();
+        renamed.push(c);
+    }
+    renamed.pop();
+    renamed
+}
+
+impl fmt::Display for GameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GameError::InvalidSettings(problems) => {
+                write!(f, "Invalid settings, {} problem{}:", problems.len(), if problems.len() == 1 { "" } else { "s" })?;
+                for problem in problems {
+                    write!(f, "\n - {}", problem)?;
+                }
+                Ok(())
+            }
+            GameError::EmptyShoe => write!(f, "Shoe has no cards to draw, even after a reshuffle"),
+            GameError::NotDealt => write!(f, "Round played before the cards were dealt"),
+            GameError::NoSuchHand(index) => write!(f, "Player has no hand {}", index),
+            GameError::NotAPair(index) => write!(f, "Player hand {} isn't a pair to split", index),
+            GameError::NoSuchRank(idx) => write!(f, "No rank has index {}, Ace is 1 & King 13", idx),
+            GameError::Busted(value) => write!(f, "Decision asked for a hand of {}, already over 21", value),
+            GameError::WrongDecision { strategy, expected } => write!(f, "Strategy '{}' didn't return a `{}` decision", strategy, expected),
+        }
+    }
+}
+
+impl Error for GameError {}
@@ -0,0 +1,316 @@
+//! Per-hand event stream, written as JSON lines & replayed against the engine.
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::sync::Arc;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use crate::deck::{Card, Hand};
+use crate::observer::{GameObserver, Seat};
+use crate::playing_strategy::PlayerDecision;
+use crate::report::SettingsSummary;
+use crate::{Game, ValidSettings, Winner};
+use crate::error::GameError;
+use crate::shoe::ShoeKind;
+
+// Game state at a player decision
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StateSummary {
+    pub dealer_upcard: Option<Card>,
+    pub running_count: i32,
+    pub true_count: f64,
+    pub cards_played: usize,
+    pub bankroll: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SettledHand {
+    pub cards: Vec<Card>,
+    pub value: u8,
+    pub winner: Winner,
+    pub payoff: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event")]
+pub enum GameEvent {
+    Start { settings: Box<SettingsSummary> }, // Log header, not replayed
+    Shuffle { shoe: Vec<Card> },          // Shoe order, drawn from the back
+    CutCard { position: usize },          // Index of the blank in the shoe
+    Bet { amount: u32 },                  // Bet after table limits
+    BetRejected { amount: u32 },
+    Deal { player: Vec<Card>, dealer: Vec<Card> },
+    Decision { cards: Vec<Card>, value: u8, decision: PlayerDecision, state: StateSummary },
+    DealerDecision { cards: Vec<Card>, value: u8, decision: PlayerDecision },
+    Draw { card: Card },
+    Settle { hands: Vec<SettledHand>, dealer: Vec<Card>, bankroll: i64 },
+}
+
+impl GameEvent {
+    // Starts a new betting round
+    pub fn is_bet(&self) -> bool {
+        matches!(self, GameEvent::Bet { .. } | GameEvent::BetRejected { .. })
+    }
+}
+
+fn codes(cards: &[Card]) -> String {
+    cards.iter().map(|card| card.code()).collect::<Vec<String>>().join(" ")
+}
+
+impl fmt::Display for GameEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GameEvent::Start { settings } => write!(f, "Start: {} vs {}, {} decks", settings.player_strat, settings.dealer_strat, settings.deck_count),
+            GameEvent::Shuffle { shoe } => write!(f, "Shuffle: {} cards", shoe.len()),
+            GameEvent::CutCard { position } => write!(f, "Cut Card: {} cards from the back", position),
+            GameEvent::Bet { amount } => write!(f, "Bet: ${}", amount),
+            GameEvent::BetRejected { amount } => write!(f, "Bet Rejected: ${}", amount),
+            GameEvent::Deal { player, dealer } => write!(f, "Deal: Player [{}] | Dealer [{}]", codes(player), codes(dealer)),
+            GameEvent::Decision { cards, value, decision, state } => write!(f,
+                "Player [{}] ({}) -> {:?} | RC {} TC {:.2} Bankroll ${}",
+                codes(cards), value, decision, state.running_count, state.true_count, state.bankroll),
+            GameEvent::DealerDecision { cards, value, decision } => write!(f, "Dealer [{}] ({}) -> {:?}", codes(cards), value, decision),
+            GameEvent::Draw { card } => write!(f, "Draw: {}", card.code()),
+            GameEvent::Settle { hands, dealer, bankroll } => {
+                write!(f, "Settle: Dealer [{}]", codes(dealer))?;
+                for hand in hands {
+                    write!(f, " | [{}] ({}) {:?} {:+}", codes(&hand.cards), hand.value, hand.winner, hand.payoff)?;
+                }
+                write!(f, " | Bankroll ${}", bankroll)
+            }
+        }
+    }
+}
+
+enum EventSink {
+    File(BufWriter<File>),
+    Memory(Vec<GameEvent>),
+}
+
+pub struct EventLog {
+    sink: EventSink,
+    error: Option<io::Error>,    // First write error, reported on flush
+    settled: Vec<SettledHand>, // Round's hands until it ends
+    dealer: Vec<Card>,
+}
+
+impl EventLog {
+    // One compact JSON event per line
+    pub fn create(path: impl AsRef<Path>) -> io::Result<Self> {
+        let file = File::create(path)?;
+        Ok(EventLog::with_sink(EventSink::File(BufWriter::new(file))))
+    }
+
+    // Keeps events in memory, used for replays
+    pub fn memory() -> Self {
+        EventLog::with_sink(EventSink::Memory(vec![]))
+    }
+
+    fn with_sink(sink: EventSink) -> Self {
+        EventLog { sink, error: None, settled: vec![], dealer: vec![] }
+    }
+
+    pub fn record(&mut self, event: GameEvent) {
+        match &mut self.sink {
+            EventSink::File(writer) => {
+                if self.error.is_some() {
+                    return;
+                }
+                let written = serde_json::to_string(&event)
+                    .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+                    .and_then(|line| writeln!(writer, "{}", line));
+                if let Err(err) = written {
+                    self.error = Some(err);
+                }
+            }
+            EventSink::Memory(events) => events.push(event),
+        }
+    }
+
+    // Recorded events, empty for file logs
+    pub fn events(&self) -> &[GameEvent] {
+        match &self.sink {
+            EventSink::File(_) => &[],
+            EventSink::Memory(events) => events,
+        }
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        if let Some(err) = self.error.take() {
+            return Err(err);
+        }
+        match &mut self.sink {
+            EventSink::File(writer) => writer.flush(),
+            EventSink::Memory(_) => Ok(()),
+        }
+    }
+}
+
+impl GameObserver for EventLog {
+    fn on_shuffle(&mut self, shoe: &[Card]) {
+        self.record(GameEvent::Shuffle { shoe: shoe.to_vec() });
+        if let Some(position) = shoe.iter().position(|card| card.is_blank()) {
+            self.record(GameEvent::CutCard { position });
+        }
+    }
+
+    fn on_bet(&mut self, bet: u32, accepted: bool) {
+        match accepted {
+            true => self.record(GameEvent::Bet { amount: bet }),
+            false => self.record(GameEvent::BetRejected { amount: bet }),
+        }
+    }
+
+    fn on_deal(&mut self, player: &Hand, dealer: &Hand) {
+        self.record(GameEvent::Deal { player: player.cards.clone(), dealer: dealer.cards.clone() });
+    }
+
+    fn on_decision(&mut self, seat: Seat, hand: &Hand, decision: &PlayerDecision, state: &StateSummary) {
+        let (cards, value, decision) = (hand.cards.clone(), hand.value(), decision.clone());
+        match seat {
+            Seat::Player => self.record(GameEvent::Decision { cards, value, decision, state: state.clone() }),
+            Seat::Dealer => self.record(GameEvent::DealerDecision { cards, value, decision }),
+        }
+    }
+
+    fn on_draw(&mut self, card: &Card) {
+        self.record(GameEvent::Draw { card: *card });
+    }
+
+    fn on_hand_settled(&mut self, hand: &Hand, dealer: &Hand, winner: &Winner, payoff: i64) {
+        self.settled.push(SettledHand { cards: hand.cards.clone(), value: hand.value(), winner: winner.clone(), payoff });
+        self.dealer = dealer.cards.clone();
+    }
+
+    fn on_round_end(&mut self, bankroll: i64) {
+        let hands = std::mem::take(&mut self.settled);
+        let dealer = std::mem::take(&mut self.dealer);
+        self.record(GameEvent::Settle { hands, dealer, bankroll });
+    }
+}
+
+pub fn read_events(path: impl AsRef<Path>) -> io::Result<Vec<GameEvent>> {
+    let text = fs::read_to_string(path)?;
+    text.lines()
+        .filter(|line| !line.trim().is_empty())
+        .enumerate()
+        .map(|(i, line)| serde_json::from_str(line)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, format!("line {}: {}", i + 1, err))))
+        .collect()
+}
+
+// Header settings of a log, if written by a `GamePool`
+pub fn logged_settings(events: &[GameEvent]) -> Option<&SettingsSummary> {
+    events.iter().find_map(|event| match event {
+        GameEvent::Start { settings } => Some(settings.as_ref()),
+        _ => None,
+    })
+}
+
+// Events grouped by betting round, anything before the first bet is dropped
+pub fn rounds(events: &[GameEvent]) -> Vec<&[GameEvent]> {
+    let starts = events.iter().enumerate()
+        .filter(|(_, event)| event.is_bet())
+        .map(|(i, _)| i)
+        .collect::<Vec<usize>>();
+    starts.iter().enumerate().map(|(n, start)| {
+        let end = starts.get(n + 1).copied().unwrap_or(events.len());
+        &events[*start..end]
+    }).collect()
+}
+
+pub fn print_rounds(events: &[GameEvent]) {
+    if let Some(settings) = logged_settings(events) {
+        println!("{}", GameEvent::Start { settings: Box::new(settings.clone()) });
+    }
+    for (n, round) in rounds(events).iter().enumerate() {
+        println!("\n -- Round {} --", n + 1);
+        for event in round.iter() {
+            // Draws already shown in deals, decisions & settlements
+            if !matches!(event, GameEvent::Draw { .. }) {
+                println!("{}", event);
+            }
+        }
+    }
+}
+
+// |-------------------------|
+// |          REPLAY         |
+// |-------------------------|
+
+// First event where the replayed engine differs from the log
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReplayMismatch {
+    pub index: usize,
+    pub logged: Option<GameEvent>,
+    pub replayed: Option<GameEvent>,
+}
+
+pub struct Replay {
+    pub logged: Vec<GameEvent>,
+    pub replayed: Vec<GameEvent>,
+}
+
+impl Replay {
+    pub fn first_mismatch(&self) -> Option<ReplayMismatch> {
+        let logged = self.logged.iter().filter(|event| !matches!(event, GameEvent::Start { .. })).collect::<Vec<&GameEvent>>();
+        let len = logged.len().max(self.replayed.len());
+        (0..len).find_map(|index| {
+            let (logged, replayed) = (logged.get(index).copied(), self.replayed.get(index));
+            (logged != replayed).then(|| ReplayMismatch { index, logged: logged.cloned(), replayed: replayed.cloned() })
+        })
+    }
+}
+
+// Re-runs a logged `simulate` through the engine on the same shoes & round count.
+// Strategies come from `settings`, a matching replay means the same bets & decisions.
+pub fn replay<R: Rng + Clone>(logged: Vec<GameEvent>, settings: Arc<ValidSettings<R>>) -> Result<Replay, GameError> {
+    let mut shoes = logged.iter().filter_map(|event| match event {
+        GameEvent::Shuffle { shoe } => Some(shoe.clone()),
+        _ => None,
+    }).collect::<VecDeque<Vec<Card>>>();
+    let round_count = logged.iter().filter(|event| event.is_bet()).count();
+    // Only shuffles are logged, not where a machine put the discards
+    if settings.deck.kind != ShoeKind::Shuffled {
+        return Err(GameError::InvalidSettings(vec![format!("replays need a hand shuffled shoe, found {}", settings.deck.kind)]));
+    }
+
+    let mut bj = Game::from_settings(settings);
+    if let Some(shoe) = shoes.pop_front() {
+        bj.shoe.restack(shoe);
+    }
+    bj.scripted_shoes = shoes;
+    bj.attach_event_log(EventLog::memory());
+
+    for _ in 0..round_count {
+        bj.play_round()?;
+    }
+
+    // Same round trip as a logged event, card codes don't keep deflated aces
+    let replayed = bj.event_log.take().map(|log| log.events().iter()
+        .map(|event| serde_json::to_string(event).and_then(|line| serde_json::from_str(&line)).expect("Events serializable"))
+        .collect()
+    ).unwrap_or_default();
+    Ok(Replay { logged, replayed })
+}
+
+impl <R: Rng + Clone> Game <R> {
+    // Starts logging, the shoe in play is recorded first so the log can be replayed
+    pub fn attach_event_log(&mut self, mut log: EventLog) {
+        log.on_shuffle(self.shoe.cards());
+        self.event_log = Some(log);
+    }
+
+    pub(crate) fn state_summary(&self) -> StateSummary {
+        StateSummary {
+            dealer_upcard: self.get_dealer_upcard(),
+            running_count: self.running_count,
+            true_count: self.true_count,
+            cards_played: self.played_cards.len(),
+            bankroll: self.bankroll,
+        }
+    }
+}
@@ -0,0 +1,289 @@
+//! Terminal play for a human at the table. `HumanPlayer` answers the engine's decisions
+//! from typed input, `play_session` takes the bets & shows how every round settles.
+
+use std::fmt;
+use std::io::{self, BufRead, Write};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use rand::Rng;
+use crate::deck::Hand;
+use crate::playing_strategy::{PlayerDecision, StratReturn, StrategyFunc};
+use crate::error::GameError;
+use crate::{EndState, Game, GameView, Winner};
+
+// Where the player reads prompts & types answers
+pub struct Console {
+    input: Box<dyn BufRead + Send>,
+    output: Box<dyn Write + Send>,
+    pub show_count: bool, // Running & true count next to every prompt
+    pub ascii: bool,      // Suit letters in place of symbols
+    closed: bool,         // Input ended, remaining decisions stand
+}
+
+pub type SharedConsole = Arc<Mutex<Console>>;
+
+impl Console {
+    pub fn new(input: impl BufRead + Send + 'static, output: impl Write + Send + 'static) -> Self {
+        Console { input: Box::new(input), output: Box::new(output), show_count: false, ascii: false, closed: false }
+    }
+
+    pub fn stdio() -> Self {
+        Console::new(io::BufReader::new(io::stdin()), io::stdout())
+    }
+
+    pub fn with_count(mut self, show_count: bool) -> Self {
+        self.show_count = show_count;
+        self
+    }
+
+    pub fn with_ascii(mut self, ascii: bool) -> Self {
+        self.ascii = ascii;
+        self
+    }
+
+    // Card, hand or anything else shown w/ the console's suits
+    pub(crate) fn cards(&self, cards: &impl fmt::Display) -> String {
+        match self.ascii {
+            true => format!("{:#}", cards),
+            false => cards.to_string(),
+        }
+    }
+
+    pub fn shared(self) -> SharedConsole {
+        Arc::new(Mutex::new(self))
+    }
+
+    // Terminal gone, nothing worth reporting mid game
+    pub(crate) fn say(&mut self, text: &str) {
+        let _ = writeln!(self.output, "{}", text);
+    }
+
+    // Shown for `pace` then erased, nothing held back when there's no pause
+    pub(crate) fn flash(&mut self, text: &str, pace: Duration) {
+        if pace.is_zero() {
+            self.say(text);
+            return;
+        }
+        let _ = write!(self.output, "\r\x1b[2K{}", text);
+        let _ = self.output.flush();
+        thread::sleep(pace);
+        let _ = write!(self.output, "\r\x1b[2K");
+        let _ = self.output.flush();
+    }
+
+    // Trimmed & lowercased answer, None once input ends
+    pub(crate) fn ask(&mut self, prompt: &str) -> Option<String> {
+        if self.closed {
+            return None;
+        }
+        let _ = write!(self.output, "{} > ", prompt);
+        let _ = self.output.flush();
+        let mut line = String::new();
+        match self.input.read_line(&mut line) {
+            Ok(0) | Err(_) => {
+                self.closed = true;
+                let _ = writeln!(self.output);
+                None
+            }
+            Ok(_) => Some(line.trim().to_lowercase()),
+        }
+    }
+}
+
+// Value w/ what the player needs to know about it
+pub(crate) fn describe(hand: &Hand) -> String {
+    let value = hand.value();
+    if hand.natural || (hand.cards.len() == 2 && !hand.split_child && value == 21) {
+        return "blackjack".to_string();
+    }
+    match value {
+        22.. => format!("{} bust", value),
+        _ if hand.contains_soft_ace() => format!("soft {}", value),
+        _ => value.to_string(),
+    }
+}
+
+// |-------------------------|
+// |      HUMAN DECISIONS    |
+// |-------------------------|
+
+pub(crate) struct Choice {
+    pub key: &'static str,
+    pub word: &'static str,
+    pub label: &'static str,
+    pub decision: PlayerDecision,
+}
+
+const HIT: Choice = Choice { key: "h", word: "hit", label: "[h]it", decision: PlayerDecision::Hit };
+const STAND: Choice = Choice { key: "s", word: "stand", label: "[s]tand", decision: PlayerDecision::Stand };
+const DOUBLE: Choice = Choice { key: "d", word: "double", label: "[d]ouble", decision: PlayerDecision::Double };
+const SPLIT: Choice = Choice { key: "p", word: "split", label: "s[p]lit", decision: PlayerDecision::Split };
+const EARLY_SURRENDER: Choice = Choice { key: "r", word: "surrender", label: "su[r]render", decision: PlayerDecision::EarlySurrender };
+const LATE_SURRENDER: Choice = Choice { key: "r", word: "surrender", label: "su[r]render", decision: PlayerDecision::LateSurrender };
+
+// Only what the table allows is offered
+pub(crate) fn choices(state: &GameView, hand: &Hand) -> Vec<Choice> {
+    let mut choices = vec![HIT, STAND];
+    if state.can_double(hand) {
+        choices.push(DOUBLE);
+    }
+    if state.can_split(hand) {
+        choices.push(SPLIT);
+    }
+    if state.can_surrender(hand, true) {
+        choices.push(EARLY_SURRENDER);
+    } else if state.can_surrender(hand, false) {
+        choices.push(LATE_SURRENDER);
+    }
+    choices
+}
+
+// Asks the player for every playing decision
+pub struct HumanPlayer {
+    console: SharedConsole,
+}
+
+impl HumanPlayer {
+    pub fn new(console: SharedConsole) -> Self {
+        HumanPlayer { console }
+    }
+}
+
+impl StrategyFunc for HumanPlayer {
+    fn get_decision(&self, state: &GameView) -> Result<StratReturn, GameError> {
+        let hand = state.hand()?;
+        let mut console = self.console.lock().expect("Console poisoned");
+
+        let upcard = state.dealer_upcard.map_or("?".to_string(), |card| console.cards(&card));
+        let count = match console.show_count {
+            true => format!("   Running count {:+}, true count {:+.1}", state.running_count, state.true_count),
+            false => String::new(),
+        };
+        console.say(&format!("\nDealer shows {}{}", upcard, count));
+        let split = if hand.split_child { ", split hand" } else { "" };
+        let shown = console.cards(hand);
+        console.say(&format!("Your hand {} ({}{})   Bet {}", shown, describe(hand), split, hand.init_bet));
+
+        // Nothing to decide on 21
+        if hand.value() == 21 {
+            console.say("Standing on 21");
+            return Ok(StratReturn::Play(PlayerDecision::Stand));
+        }
+
+        let choices = choices(state, hand);
+        let prompt = choices.iter().map(|choice| choice.label).collect::<Vec<_>>().join(", ");
+        loop {
+            // Input gone, play out the round safely
+            let Some(answer) = console.ask(&prompt) else {
+                return Ok(StratReturn::Play(PlayerDecision::Stand));
+            };
+            match choices.iter().find(|choice| answer == choice.key || answer == choice.word) {
+                Some(choice) => return Ok(StratReturn::Play(choice.decision.clone())),
+                None => console.say(&format!("'{}' isn't allowed here, choose {}", answer, prompt)),
+            }
+        }
+    }
+
+    fn to_string(&self) -> String {
+        "Human Player".to_string()
+    }
+
+    // Offered whenever the table allows it
+    fn surrenders_early(&self) -> bool {
+        true
+    }
+
+    fn surrenders_late(&self) -> bool {
+        true
+    }
+}
+
+// |-------------------------|
+// |         SESSION         |
+// |-------------------------|
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SessionResult {
+    pub rounds: u64,
+    pub start_bankroll: i64,
+    pub bankroll: i64,
+}
+
+impl SessionResult {
+    pub fn net(&self) -> i64 {
+        self.bankroll - self.start_bankroll
+    }
+}
+
+// Bet, deal, play & settle rounds until the player quits, input ends or the bankroll can't cover the table minimum
+// The game's player strategy should be a `HumanPlayer` on the same console
+pub fn play_session<R: Rng + Clone>(game: &mut Game<R>, console: &SharedConsole) -> Result<SessionResult, GameError> {
+    let mut result = SessionResult { rounds: 0, start_bankroll: game.bankroll, bankroll: game.bankroll };
+    loop {
+        let bet = {
+            let mut console = console.lock().expect("Console poisoned");
+            if !game.can_cover(game.table_min) {
+                console.say(&format!("\nBankroll {} can't cover the {} table minimum", game.bankroll, game.table_min));
+                break;
+            }
+            let last = if game.last_bet > 0 { game.last_bet } else { game.init_bet };
+            console.say(&format!("\nBankroll {}   Table {}-{}", game.bankroll, game.table_min, game.table_max));
+            let Some(answer) = console.ask(&format!("Bet, enter for {} or q to quit", last)) else { break };
+            match answer.as_str() {
+                "q" | "quit" => break,
+                "" => last,
+                text => match text.parse::<u32>() {
+                    Ok(bet) if bet > 0 => bet,
+                    _ => {
+                        console.say(&format!("'{}' isn't a bet", text));
+                        continue;
+                    }
+                },
+            }
+        };
+
+        // Table limits first, then the bankroll
+        let Some(placed) = game.place_bet(bet) else {
+            console.lock().expect("Console poisoned").say(&format!("Bet {} refused by the table limits", bet));
+            continue;
+        };
+        if !game.can_cover(placed) {
+            console.lock().expect("Console poisoned").say(&format!("Bankroll {} can't cover a {} bet", game.bankroll, placed));
+            continue;
+        }
+        if placed != bet {
+            console.lock().expect("Console poisoned").say(&format!("Bet changed to {} by the table limits", placed));
+        }
+
+        // Player decisions lock the console themselves
+        game.deal(placed)?;
+        let hand_results = game.play_hand()?;
+        show_round(game, &hand_results, &mut console.lock().expect("Console poisoned"));
+        game.reset_hands();
+        result.rounds += 1;
+        result.bankroll = game.bankroll;
+    }
+
+    console.lock().expect("Console poisoned").say(&format!(
+        "\nPlayed {} rounds, bankroll {} ({:+})", result.rounds, result.bankroll, result.net()
+    ));
+    Ok(result)
+}
+
+fn show_round<R: Rng + Clone>(game: &Game<R>, hand_results: &[(Winner, EndState)], console: &mut Console) {
+    let Some(dealer) = game.dealer.hand.as_ref() else { return };
+    let shown = console.cards(dealer);
+    console.say(&format!("\nDealer has {} ({})", shown, describe(dealer)));
+    for (hand, (winner, state)) in game.player.hands.iter().zip(hand_results.iter()) {
+        let outcome = match winner {
+            _ if state.p_surrender_early || state.p_surrender_late => "surrendered",
+            Winner::Player if state.p_natural => "blackjack",
+            Winner::Player => "win",
+            Winner::Dealer => "lose",
+            _ => "push",
+        };
+        let shown = console.cards(hand);
+        console.say(&format!("Your hand {} ({}): {} {:+}", shown, describe(hand), outcome, state.payoff(winner)));
+    }
+}
@@ -1,7 +1,11 @@
 #![allow(dead_code)]
+use std::collections::VecDeque;
+use std::fmt;
+use std::ops::Deref;
 use std::sync::Arc;
 
 use rand::Rng;
+use serde::{Deserialize, Serialize};
 
 pub mod playing_strategy;
 pub mod particpants;
@@ -10,11 +14,33 @@ pub mod run_hand;
 pub mod actions;
 pub mod run_many;
 pub mod betting_strategy;
+pub mod bankroll;
+pub mod session;
+pub mod report;
+pub mod event_log;
+pub mod observer;
+pub mod registry;
+pub mod config;
+pub mod presets;
+pub mod sweep;
+pub mod interactive;
+pub mod trainer;
+pub mod count_drill;
+pub mod fast;
+pub mod error;
+pub mod settings;
+pub mod shoe;
+pub mod shuffle;
+pub mod script;
 
 use crate::{
     particpants::{Player, Dealer},
-    deck::{MultiDeck, Card, Hand, HandState},
-    playing_strategy::{StrategyFunc, DealerUpcardStrength}
+    deck::{MultiDeck, Card, Hand, HandState, Rank},
+    playing_strategy::{StrategyFunc, DealerUpcardStrength},
+    shoe::{Shoe, ShoeKind},
+    error::GameError,
+    event_log::EventLog,
+    observer::{GameObserver, EchoObserver},
 };
 
 #[derive(Clone, Debug)]
@@ -29,6 +55,7 @@ pub struct EndState {
     pub p_surrender_late: bool,
     pub d_natural: bool,
     pub d_bust: bool,
+    pub natural_pays: BlackjackPayout,
 }
 
 impl Default for EndState {
@@ -36,12 +63,149 @@ impl Default for EndState {
         EndState { 
             hand_bet: u32::MAX, magnitude_bet_inc: 0,
             p_natural: false, p_insurance: false, p_doubled: false, p_bust: false, p_surrender_early: false, p_surrender_late: false,
-            d_natural: false, d_bust: false }
+            d_natural: false, d_bust: false, natural_pays: BlackjackPayout::ThreeToTwo }
     }
 }
 
+impl EndState {
+    // Net change to player bankroll for a settled hand
+    // Natural pays per the table, doubles win/lose twice the bet, surrenders lose half
+    pub fn payoff(&self, winner: &Winner) -> i64 {
+        let bet = self.hand_bet as i64;
+        match winner {
+            Winner::Player => {
+                if self.p_natural && !self.d_natural { self.natural_pays.payoff(bet) }
+                else if self.p_doubled { bet * 2 }
+                else { bet }
+            }
+            Winner::Dealer => {
+                if self.p_surrender_early || self.p_surrender_late { -(bet / 2) }
+                else if self.p_doubled { -(bet * 2) }
+                else { -bet }
+            }
+            Winner::Tie | Winner::None => 0,
+        }
+    }
+}
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+
+// What the table does with a bet outside its limits
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LimitPolicy {
+    #[serde(alias = "clamp")]
+    Clamp,  // Bet moved to nearest legal amount
+    #[serde(alias = "reject")]
+    Reject, // Round sat out & betting progression reset
+}
+
+// |-------------------------|
+// |       TABLE RULES       |
+// |-------------------------|
+
+// Payout on a player natural
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BlackjackPayout {
+    #[serde(rename = "3:2")]
+    ThreeToTwo,
+    #[serde(rename = "6:5")]
+    SixToFive,
+    #[serde(rename = "1:1")]
+    EvenMoney,
+}
+
+impl BlackjackPayout {
+    pub fn payoff(&self, bet: i64) -> i64 {
+        match self {
+            BlackjackPayout::ThreeToTwo => (bet * 3) / 2,
+            BlackjackPayout::SixToFive => (bet * 6) / 5,
+            BlackjackPayout::EvenMoney => bet,
+        }
+    }
+}
+
+impl fmt::Display for BlackjackPayout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BlackjackPayout::ThreeToTwo => write!(f, "3:2"),
+            BlackjackPayout::SixToFive => write!(f, "6:5"),
+            BlackjackPayout::EvenMoney => write!(f, "1:1"),
+        }
+    }
+}
+
+// Two card totals the player may double on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DoubleRule {
+    #[serde(rename = "any")]
+    Any,
+    #[serde(rename = "9-11")]
+    NineToEleven,
+    #[serde(rename = "10-11")]
+    TenToEleven,
+}
+
+impl DoubleRule {
+    // Hard or soft total
+    pub fn allows(&self, total: u8) -> bool {
+        match self {
+            DoubleRule::Any => true,
+            DoubleRule::NineToEleven => (9..=11).contains(&total),
+            DoubleRule::TenToEleven => (10..=11).contains(&total),
+        }
+    }
+}
+
+// House rules beyond the dealer cutoff, splits & surrender
+// Default is the game as first simulated: S17, DAS, no peek & split aces played out
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TableRules {
+    pub hit_soft_17: bool,
+    pub double_after_split: bool,
+    pub double_on: DoubleRule,
+    pub blackjack_pays: BlackjackPayout,
+    // Hole card checked for a natural before the player acts
+    // Without it (ENHC) a dealer natural takes doubles & splits too
+    pub dealer_peeks: bool,
+    pub split_aces_one_card: bool, // One card each & no resplitting
+}
+
+impl Default for TableRules {
+    fn default() -> Self {
+        TableRules {
+            hit_soft_17: false,
+            double_after_split: true,
+            double_on: DoubleRule::Any,
+            blackjack_pays: BlackjackPayout::ThreeToTwo,
+            dealer_peeks: false,
+            split_aces_one_card: false,
+        }
+    }
+}
+
+// Count of bets changed or refused by table limits
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LimitStats {
+    pub clamped_min: u64,
+    pub clamped_max: u64,
+    pub rounded: u64, // Not a multiple of the bet increment
+    pub rejected: u64,
+}
+
+impl LimitStats {
+    pub fn clamped(&self) -> u64 {
+        self.clamped_min + self.clamped_max + self.rounded
+    }
+
+    pub fn merge(&mut self, other: &LimitStats) {
+        self.clamped_min += other.clamped_min;
+        self.clamped_max += other.clamped_max;
+        self.rounded += other.rounded;
+        self.rejected += other.rejected;
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Winner {
     Player,
     Dealer,
@@ -49,33 +213,67 @@ pub enum Winner {
     None
 }
 
+// Outcome of one betting round
+#[derive(Debug, Clone)]
+pub enum Round {
+    Played(Vec<(Winner, EndState)>),
+    Rejected, // Bet refused by table limits
+    Ruined,   // Bankroll can't cover the bet
+}
+
+// One settled round, used by betting progressions
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BetRecord {
+    pub bet: u32,
+    pub winner: Winner,
+    pub payoff: i64, // Net over all split hands
+}
+
 // #[derive(Clone, Debug)]
 pub struct Game<R: Rng> {
-    deck: MultiDeck,
+    shoe: Box<dyn Shoe>,
     pub max_splits: u8,
     pub player: Player,
     pub dealer: Dealer,
     pub init_bet: u32,
     pub last_bet: u32,
+    pub bankroll: i64,
+    pub bankroll_limited: bool, // Bankroll & session runs: ruin ends play, doubles & splits it can't cover are hit
     pub played_cards: Vec<Card>,
+    pub cut_card_reached: bool, // Reshuffle once the round ends
     pub last_winner: Winner,
+    pub bet_history: Vec<BetRecord>,
     pub rng: R,
-    pub echo: bool,
 
     // Surrender Rules
     pub allow_early_surrender: bool,
     pub allow_late_surrender: bool,
+    pub rules: TableRules,
+
+    // Table Limits
+    pub table_min: u32,
+    pub table_max: u32,
+    pub bet_increment: u32,
+    pub limit_policy: LimitPolicy,
+    pub limit_stats: LimitStats,
 
     // Counting
     pub running_count: i32,
     pub true_count: f64,
+
+    // Observers, event log kept apart so it can be handed back
+    pub observers: Vec<Box<dyn GameObserver>>,
+    pub event_log: Option<EventLog>,
+    pub scripted_shoes: VecDeque<Vec<Card>>, // Used in place of the next shuffles, replays only
 } 
 
 impl <R: Rng + Clone> Game <R> {
+    #[allow(clippy::too_many_arguments)]
     pub fn new (
         deck: MultiDeck,
         max_splits: u8, 
         init_bet: u32,
+        bankroll: i64,
         dealer: Dealer,
         player: Player,
         rng: R,
@@ -85,26 +283,44 @@ impl <R: Rng + Clone> Game <R> {
         
         
     ) -> Self {
+        // Printing is just another observer
+        let mut observers: Vec<Box<dyn GameObserver>> = vec![];
+        if echo {
+            observers.push(Box::new(EchoObserver));
+        }
+
         Game { 
-            deck: deck.clone(),
+            shoe: deck.build_shoe(),
             max_splits, 
             init_bet,
             last_bet: 0,
+            bankroll,
+            bankroll_limited: false,
             player,
             dealer,
             played_cards: vec![],
+            cut_card_reached: false,
             last_winner: Winner::None,
+            bet_history: vec![],
             rng,
-            echo,
             allow_early_surrender,
             allow_late_surrender,
+            rules: TableRules::default(),
+            table_min: 0,
+            table_max: u32::MAX,
+            bet_increment: 1,
+            limit_policy: LimitPolicy::Clamp,
+            limit_stats: LimitStats::default(),
             running_count: 0,
             true_count: 0.0,
-            
+            observers,
+            event_log: None,
+            scripted_shoes: VecDeque::new(),
         }
     }
 
-    pub fn from_settings(value: Arc<GameSettings<R>>) -> Self {
+    // Settings already validated, see `GameSettings::validate`
+    pub fn from_settings(value: Arc<ValidSettings<R>>) -> Self {
         let player = Player {
             playing_strat: value.player_strat.clone(),
             betting_strat: value.betting_strat.clone(),
@@ -118,7 +334,23 @@ impl <R: Rng + Clone> Game <R> {
             cutoff: value.dealer_cutoff,
         };
 
-        Game::new(value.deck.clone(), value.max_splits, value.init_bet, dealer, player, value.rng.clone(),value.allow_early_surrender, value.allow_late_surrender, value.echo)
+        let mut game = Game::new(value.deck.clone(), value.max_splits, value.init_bet, value.bankroll as i64, dealer, player, value.rng.clone(),value.allow_early_surrender, value.allow_late_surrender, value.echo);
+        game.table_min = value.table_min;
+        game.table_max = value.table_max;
+        game.bet_increment = value.bet_increment;
+        game.limit_policy = value.limit_policy;
+        game.rules = value.rules;
+        game
+    }
+
+    pub fn shoe(&self) -> &dyn Shoe {
+        self.shoe.as_ref()
+    }
+
+    // Any `Shoe` dealt from instead of the settings', fresh & shuffled
+    pub fn set_shoe(&mut self, shoe: Box<dyn Shoe>) {
+        self.shoe = shoe;
+        self.reshuffle();
     }
     
     // Assumes dealer has been dealth
@@ -139,20 +371,29 @@ impl <R: Rng + Clone> Game <R> {
         
     }
 
-    pub fn get_state(&self, player_hand: Option<Hand>) -> GameState {
-        GameState { 
+    // What strategies decide from, borrowed so nothing is copied per decision or draw
+    pub fn view<'a>(&'a self, player_hand: Option<&'a Hand>) -> GameView<'a> {
+        GameView { 
             init_bet: self.init_bet,
             last_bet: self.last_bet,
-            played_cards: self.played_cards.clone(),
+            bankroll: self.bankroll,
+            played_cards: &self.played_cards,
             dealer_upcard: self.get_dealer_upcard(),
             dealer_upcard_str: self.get_dealer_upcard_str(),
             player_hand,
-            dealer_hand: self.dealer.hand.clone(),
+            dealer_hand: self.dealer.hand.as_ref(),
             dealer_cutoff: self.dealer.cutoff,
-            contains_blank: self.deck.contains_blank,
+            contains_blank: self.shoe.contains_blank(),
             last_winner: self.last_winner.clone(),
+            bet_history: &self.bet_history,
             allow_early_surrender: self.allow_early_surrender,
             allow_late_surrender: self.allow_late_surrender, 
+            rules: self.rules,
+            max_splits: self.max_splits,
+            player_hand_count: self.player.hands.len(),
+            table_min: self.table_min,
+            table_max: self.table_max,
+            bet_increment: self.bet_increment,
             running_count: self.running_count,
             true_count: self.true_count,
         }
@@ -162,20 +403,29 @@ impl <R: Rng + Clone> Game <R> {
 
 
 #[derive(Clone, Debug)]
-pub struct GameState {
+pub struct GameView<'a> {
     init_bet: u32,
     last_bet: u32, // Martingale Strat
-    played_cards: Vec<Card>, 
+    bankroll: i64, // Kelly Strat
+    played_cards: &'a [Card], // Since the shuffle, last one just drawn
     dealer_upcard: Option<Card>,
     dealer_upcard_str: Option<DealerUpcardStrength>,
-    player_hand: Option<Hand>,
-    dealer_hand: Option<Hand>,
+    player_hand: Option<&'a Hand>,
+    dealer_hand: Option<&'a Hand>,
     dealer_cutoff: u8,
     contains_blank: bool,
     last_winner: Winner,
+    bet_history: &'a [BetRecord], // Progressions w/ longer memory
     // Surrenders Allowed
     allow_early_surrender: bool,
     allow_late_surrender: bool,
+    rules: TableRules,
+    max_splits: u8,
+    player_hand_count: usize, // Grows w/ splits
+    // Table limits, bets are made legal against them
+    table_min: u32,
+    table_max: u32,
+    bet_increment: u32,
     
 
     // Card Counting
@@ -185,15 +435,17 @@ pub struct GameState {
     
 }
 
-impl GameState {
-    pub fn new(init_bet: u32, played_cards: Vec<Card>, dealer_upcard: Option<Card>, dealer_upcard_str: Option<DealerUpcardStrength>, 
-        player_hand: Option<Hand>, dealer_hand: Option<Hand>, dealer_cutoff: u8, 
+impl<'a> GameView<'a> {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(init_bet: u32, bankroll: i64, played_cards: &'a [Card], dealer_upcard: Option<Card>, dealer_upcard_str: Option<DealerUpcardStrength>, 
+        player_hand: Option<&'a Hand>, dealer_hand: Option<&'a Hand>, dealer_cutoff: u8, 
         contains_blank: bool, last_winner: Winner, running_count: i32, true_count: f64, allow_early_surrender:bool,
         allow_late_surrender: bool
     ) -> Self {
-        GameState {
+        GameView {
             init_bet,
             last_bet: 0,
+            bankroll,
             played_cards,
             dealer_upcard,
             dealer_upcard_str,
@@ -202,20 +454,81 @@ impl GameState {
             dealer_cutoff,
             contains_blank,
             last_winner,
+            bet_history: &[],
             allow_early_surrender,
             allow_late_surrender,
+            rules: TableRules::default(),
+            max_splits: 3,
+            player_hand_count: 1,
+            table_min: 0,
+            table_max: u32::MAX,
+            bet_increment: 1,
             running_count,
             true_count,
 
         }
     }
+
+    pub fn with_rules(mut self, rules: TableRules) -> Self {
+        self.rules = rules;
+        self
+    }
+
+    pub fn with_limits(mut self, table_min: u32, table_max: u32, bet_increment: u32) -> Self {
+        self.table_min = table_min;
+        self.table_max = table_max;
+        self.bet_increment = bet_increment;
+        self
+    }
+
+    // Hand being played, dealer hand & upcard, & last card drawn, missing before the deal
+    pub fn hand(&self) -> Result<&'a Hand, GameError> {
+        self.player_hand.ok_or(GameError::NotDealt)
+    }
+
+    pub fn dealer(&self) -> Result<&'a Hand, GameError> {
+        self.dealer_hand.ok_or(GameError::NotDealt)
+    }
+
+    pub fn upcard(&self) -> Result<(Card, DealerUpcardStrength), GameError> {
+        self.dealer_upcard.zip(self.dealer_upcard_str).ok_or(GameError::NotDealt)
+    }
+
+    pub fn last_card(&self) -> Result<&'a Card, GameError> {
+        self.played_cards.last().ok_or(GameError::NotDealt)
+    }
+
+    // Doubling only on the first two cards, within the table's rules
+    pub fn can_double(&self, hand: &Hand) -> bool {
+        hand.cards.len() == 2
+            && !hand.doubled
+            && (!hand.split_child || self.rules.double_after_split)
+            && self.rules.double_on.allows(hand.value())
+    }
+
+    // Initial two card hand, never after a split, of the kind the table allows
+    pub fn can_surrender(&self, hand: &Hand, early: bool) -> bool {
+        hand.cards.len() == 2
+            && !hand.split_child
+            && if early { self.allow_early_surrender } else { self.allow_late_surrender }
+    }
+
+    // Pair w/ splits left, split aces only resplit if played out
+    pub fn can_split(&self, hand: &Hand) -> bool {
+        hand.cards.len() == 2
+            && hand.contains_pair()
+            && self.player_hand_count <= self.max_splits as usize
+            && !(hand.split_child && self.rules.split_aces_one_card && hand.cards[0].rank == Rank::Ace)
+    }
 }
 
+#[derive(Clone)]
 pub struct  GameSettings <R: Rng> {
     pub deck: MultiDeck,
     pub contains_blank: bool,
     pub max_splits: u8,
     pub init_bet: u32,
+    pub bankroll: u32,
     pub dealer_cutoff: u8,
     pub dealer_strat: Arc<Box<dyn StrategyFunc>>,
     pub player_strat: Arc<Box<dyn StrategyFunc>>,
@@ -224,8 +537,99 @@ pub struct  GameSettings <R: Rng> {
     pub insurance_strat: Arc<Box<dyn StrategyFunc>>,
     pub allow_early_surrender: bool,
     pub allow_late_surrender: bool,
+    pub rules: TableRules,
+    pub table_min: u32,
+    pub table_max: u32,
+    pub bet_increment: u32,
+    pub limit_policy: LimitPolicy,
     pub rng: R,
+    pub seed: Option<u64>, // Seed `rng` was built from, recorded in reports
     pub echo: bool,
 }
 
+impl<R: Rng> GameSettings<R> {
+    // Checked once, games & pools only take the `ValidSettings` this returns
+    pub fn validate(self) -> Result<ValidSettings<R>, GameError> {
+        let problems = self.problems();
+        match problems.is_empty() {
+            true => Ok(ValidSettings(self)),
+            false => Err(GameError::InvalidSettings(problems)),
+        }
+    }
+
+    // Every problem no game could be played under, reported together
+    pub fn problems(&self) -> Vec<String> {
+        let mut problems = vec![];
+        if self.deck.deck_count == 0 || self.deck.decks.cards.iter().all(|card| card.is_blank()) {
+            problems.push("decks must hold at least one deck of cards".to_string());
+        }
+        if let Some(penetration) = self.deck.penetration {
+            if !(penetration > 0.0 && penetration <= 1.0) {
+                problems.push(format!("penetration must be in (0, 1], found {}", penetration));
+            }
+        }
+        let shoe_size = 52 * self.deck.deck_count as usize;
+        if self.deck.kind == ShoeKind::Csm && self.deck.reservoir >= shoe_size {
+            problems.push(format!("reservoir ({}) must leave room for discards in a {} card shoe", self.deck.reservoir, shoe_size));
+        }
+        problems.extend(self.deck.shuffle.problems());
+        if self.contains_blank != self.deck.contains_blank {
+            problems.push(format!("contains_blank ({}) disagrees w/ the deck's ({})", self.contains_blank, self.deck.contains_blank));
+        }
+        if self.dealer_cutoff > 21 {
+            problems.push(format!("dealer_cutoff must be at most 21, found {}", self.dealer_cutoff));
+        }
+        if self.init_bet == 0 {
+            problems.push("init_bet must be positive".to_string());
+        }
+        if self.bet_increment == 0 {
+            problems.push("bet_increment must be positive".to_string());
+        }
+        if self.table_min > self.table_max {
+            problems.push(format!("table_min ({}) is above table_max ({})", self.table_min, self.table_max));
+        }
+        else if self.bet_increment > 0 && self.table_min.div_ceil(self.bet_increment) > self.table_max / self.bet_increment {
+            problems.push(format!("no bet from table_min ({}) to table_max ({}) is a multiple of bet_increment ({})",
+                self.table_min, self.table_max, self.bet_increment));
+        }
+        for strat in [&self.dealer_strat, &self.player_strat, &self.betting_strat, &self.counting_strat, &self.insurance_strat] {
+            problems.extend(strat.problems().into_iter().map(|problem| format!("{}: {}", strat.to_string(), problem)));
+        }
+        let betting = self.betting_strat.to_string();
+        if let Some(unit) = self.betting_strat.bet_unit().filter(|unit| *unit > 0) {
+            if self.bet_increment > 0 && !unit.is_multiple_of(self.bet_increment) {
+                problems.push(format!("{} unit ({}) is not a multiple of bet_increment ({})", betting, unit, self.bet_increment));
+            }
+            if !self.init_bet.is_multiple_of(unit) {
+                problems.push(format!("init_bet ({}) is not a multiple of the {} unit ({})", self.init_bet, betting, unit));
+            }
+        }
+        if let Some((min_bet, max_bet)) = self.betting_strat.bet_limits() {
+            if min_bet < self.table_min || max_bet > self.table_max {
+                problems.push(format!("{} bets {}-{}, outside the table's {}-{}", betting, min_bet, max_bet, self.table_min, self.table_max));
+            }
+        }
+        if self.allow_early_surrender && !self.player_strat.surrenders_early() {
+            problems.push(format!("early surrender allowed but player strategy '{}' never surrenders early", self.player_strat.to_string()));
+        }
+        if self.allow_late_surrender && !self.player_strat.surrenders_late() {
+            problems.push(format!("late surrender allowed but player strategy '{}' never surrenders late", self.player_strat.to_string()));
+        }
+        problems
+    }
+}
+
+// `GameSettings` that passed `validate`, read only
+// Changing a copy means validating it again: `GameSettings::clone(&valid)`
+#[derive(Clone)]
+pub struct ValidSettings<R: Rng>(GameSettings<R>);
+
+impl<R: Rng> Deref for ValidSettings<R> {
+    type Target = GameSettings<R>;
+
+    fn deref(&self) -> &GameSettings<R> {
+        &self.0
+    }
+}
+
 
@@ -0,0 +1,156 @@
+//! Hooks into a running game. Observers see every shuffle, deal, decision, draw & settlement
+//! without the engine knowing what they collect.
+
+use std::sync::{Arc, Mutex};
+use rand::Rng;
+use indicatif::ProgressBar;
+use crate::deck::{Card, Hand};
+use crate::event_log::StateSummary;
+use crate::playing_strategy::PlayerDecision;
+use crate::{Game, Winner};
+
+// Who made a decision
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Seat {
+    Player,
+    Dealer,
+}
+
+// Every callback defaults to doing nothing, implement only what's needed
+pub trait GameObserver: Send + Sync {
+    // New shoe in play, drawn from the back
+    fn on_shuffle(&mut self, _shoe: &[Card]) {}
+    // Bet after table limits, not accepted if rejected
+    fn on_bet(&mut self, _bet: u32, _accepted: bool) {}
+    fn on_deal(&mut self, _player: &Hand, _dealer: &Hand) {}
+    fn on_decision(&mut self, _seat: Seat, _hand: &Hand, _decision: &PlayerDecision, _state: &StateSummary) {}
+    fn on_draw(&mut self, _card: &Card) {}
+    // Once per player hand, split hands settled separately
+    fn on_hand_settled(&mut self, _hand: &Hand, _dealer: &Hand, _winner: &Winner, _payoff: i64) {}
+    // After every hand of the round is settled
+    fn on_round_end(&mut self, _bankroll: i64) {}
+    // Cut card reached or shoe ran out, cards played since the last shuffle
+    fn on_shoe_end(&mut self, _played: &[Card]) {}
+}
+
+// Shared observer, keep a clone to read what it collected
+impl <T: GameObserver> GameObserver for Arc<Mutex<T>> {
+    fn on_shuffle(&mut self, shoe: &[Card]) {
+        self.lock().expect("Observer poisoned").on_shuffle(shoe)
+    }
+    fn on_bet(&mut self, bet: u32, accepted: bool) {
+        self.lock().expect("Observer poisoned").on_bet(bet, accepted)
+    }
+    fn on_deal(&mut self, player: &Hand, dealer: &Hand) {
+        self.lock().expect("Observer poisoned").on_deal(player, dealer)
+    }
+    fn on_decision(&mut self, seat: Seat, hand: &Hand, decision: &PlayerDecision, state: &StateSummary) {
+        self.lock().expect("Observer poisoned").on_decision(seat, hand, decision, state)
+    }
+    fn on_draw(&mut self, card: &Card) {
+        self.lock().expect("Observer poisoned").on_draw(card)
+    }
+    fn on_hand_settled(&mut self, hand: &Hand, dealer: &Hand, winner: &Winner, payoff: i64) {
+        self.lock().expect("Observer poisoned").on_hand_settled(hand, dealer, winner, payoff)
+    }
+    fn on_round_end(&mut self, bankroll: i64) {
+        self.lock().expect("Observer poisoned").on_round_end(bankroll)
+    }
+    fn on_shoe_end(&mut self, played: &[Card]) {
+        self.lock().expect("Observer poisoned").on_shoe_end(played)
+    }
+}
+
+// |-------------------------|
+// |     BUILT IN OBSERVERS  |
+// |-------------------------|
+
+// Prints the game as it's played, `echo` in the settings
+pub struct EchoObserver;
+
+impl GameObserver for EchoObserver {
+    fn on_shuffle(&mut self, shoe: &[Card]) {
+        println!("Creating New Deck");
+        println!("New Deck size: {}", shoe.len());
+    }
+
+    fn on_decision(&mut self, seat: Seat, hand: &Hand, decision: &PlayerDecision, state: &StateSummary) {
+        match seat {
+            Seat::Player => {
+                println!("\n!! New Hand !!\nBet: {}", &hand.init_bet);
+                println!("\n___PLAYER___");
+            }
+            // First dealer decision, always 2 cards
+            Seat::Dealer if hand.cards.len() == 2 => {
+                println!("\n ___DEALER___");
+                println!("Upcard: {}", state.dealer_upcard.map_or("?".to_string(), |card| card.to_string()));
+            }
+            Seat::Dealer => {}
+        }
+        println!("Current Hand: {}", hand);
+        println!("Value: {}", hand.value());
+        println!("Decision: {:?}", decision);
+    }
+
+    fn on_draw(&mut self, card: &Card) {
+        println!("Card Draw: {}", card);
+    }
+}
+
+// One tick per betting round, filled on drop
+pub struct ProgressObserver {
+    bar: ProgressBar,
+}
+
+impl ProgressObserver {
+    pub fn new(rounds: u64) -> Self {
+        ProgressObserver { bar: ProgressBar::new(rounds) }
+    }
+}
+
+impl GameObserver for ProgressObserver {
+    fn on_bet(&mut self, _bet: u32, accepted: bool) {
+        // Sat out rounds never settle
+        if !accepted {
+            self.bar.inc(1);
+        }
+    }
+
+    fn on_round_end(&mut self, _bankroll: i64) {
+        self.bar.inc(1);
+    }
+}
+
+impl Drop for ProgressObserver {
+    fn drop(&mut self) {
+        self.bar.finish();
+    }
+}
+
+impl <R: Rng + Clone> Game <R> {
+    pub fn add_observer(&mut self, observer: Box<dyn GameObserver>) {
+        self.observers.push(observer);
+    }
+
+    // Nothing to build callback args for
+    pub(crate) fn is_observed(&self) -> bool {
+        !self.observers.is_empty() || self.event_log.is_some()
+    }
+
+    // Calls `f` on every observer, the event log last
+    pub(crate) fn notify(&mut self, mut f: impl FnMut(&mut dyn GameObserver, &Self)) {
+        if !self.is_observed() {
+            return;
+        }
+        let mut observers = std::mem::take(&mut self.observers);
+        let mut event_log = self.event_log.take();
+        for observer in observers.iter_mut() {
+            f(observer.as_mut(), self);
+        }
+        if let Some(log) = event_log.as_mut() {
+            f(log, self);
+        }
+        self.observers = observers;
+        self.event_log = event_log;
+    }
+}
@@ -4,7 +4,7 @@ use std::sync::Arc;
 
 use crate::playing_strategy::{StratReturn, PlayerDecision, StrategyFunc};
 
-use crate::{deck::{Hand, HandState}, GameState};
+use crate::{deck::{Hand, HandState}, error::GameError, GameView};
 
 enum PlayerStrategy {
     DealerEmulation
@@ -34,24 +34,32 @@ impl Player {
         } 
     }
 
-    pub fn decide_bet(&self, state: GameState) -> u32 { 
-        match self.betting_strat.get_decision(state) {
-            StratReturn::Bet(amt) => amt,
-            _ => unreachable!("Always `bet` decision")
+    // Strategies in the wrong slot answer w/ the wrong variant
+    pub fn decide_bet(&self, state: &GameView) -> Result<u32, GameError> { 
+        match self.betting_strat.get_decision(state)? {
+            StratReturn::Bet(amt) => Ok(amt),
+            _ => Err(GameError::wrong_decision(self.betting_strat.as_ref().as_ref(), "Bet")),
         } 
     }
 
-    pub fn decide_insurance(&self, state: GameState) -> bool {
-        match self.insurance_strat.get_decision(state) {
-            StratReturn::Insurance(decision) => decision,
-            _ => unreachable!("Always Insurnace variant")
+    pub fn decide_insurance(&self, state: &GameView) -> Result<bool, GameError> {
+        match self.insurance_strat.get_decision(state)? {
+            StratReturn::Insurance(decision) => Ok(decision),
+            _ => Err(GameError::wrong_decision(self.insurance_strat.as_ref().as_ref(), "Insurance")),
         }
     }
 
-    pub fn decide_play(&self, state: GameState) -> PlayerDecision { 
-        match self.playing_strat.get_decision(state) {
-            StratReturn::Play(decision) => decision,
-            _ => unreachable!("Player decision always `Play` variant")
+    pub fn decide_play(&self, state: &GameView) -> Result<PlayerDecision, GameError> { 
+        match self.playing_strat.get_decision(state)? {
+            StratReturn::Play(decision) => Ok(decision),
+            _ => Err(GameError::wrong_decision(self.playing_strat.as_ref().as_ref(), "Play")),
+        }
+    }
+
+    pub fn decide_count(&self, state: &GameView) -> Result<i8, GameError> {
+        match self.counting_strat.get_decision(state)? {
+            StratReturn::Count(delta) => Ok(delta),
+            _ => Err(GameError::wrong_decision(self.counting_strat.as_ref().as_ref(), "Count")),
         }
     }
 
@@ -75,15 +83,16 @@ impl Dealer {
     }
 
     // Dealer never doubles down, splits, or surrenders
-    pub fn decide_play(&self, state: GameState) -> PlayerDecision {
-        match self.strategy.get_decision(state) {
-            StratReturn::Play(decision) => {decision},
-            _ => {unreachable!("Dealer choice should always be `Play` variant")}
+    pub fn decide_play(&self, state: &GameView) -> Result<PlayerDecision, GameError> {
+        match self.strategy.get_decision(state)? {
+            StratReturn::Play(decision) => Ok(decision),
+            _ => Err(GameError::wrong_decision(self.strategy.as_ref().as_ref(), "Play")),
         }
     }
 
+    // No hand, nothing left to play
     pub fn is_finished(&self) -> bool {
-        self.hand.as_ref().expect("").state == HandState::Finished
+        self.hand.as_ref().is_none_or(|hand| hand.state == HandState::Finished)
     }
 
     pub fn set_cutoff(&mut self, new_cutoff: u8) { self.cutoff = new_cutoff }
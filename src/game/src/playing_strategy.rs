@@ -1,9 +1,12 @@
+use serde::{Deserialize, Serialize};
 use crate::{
-    deck::{Card, Rank},
-    GameState,
+    deck::{Card, Hand, Rank},
+    registry::StrategySpec,
+    error::GameError,
+    BetRecord, GameView,
 };
 
-#[derive(Clone, PartialEq, Debug)]
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
 #[repr(u8)]
 pub enum PlayerDecision {
     Split,
@@ -28,87 +31,125 @@ pub enum StratReturn {
     Count(i8),
     Insurance(bool)
 }
-pub trait StrategyFunc {
-    fn get_decision(&self, state: GameState) -> StratReturn;
+pub trait StrategyFunc: Send + Sync {
+    fn get_decision(&self, state: &GameView) -> Result<StratReturn, GameError>;
     fn to_string(&self) -> String;
+
+    // Betting strats only: number of most recent rounds needed for the next bet.
+    // Older history dropped by the game after each round.
+    fn history_needed(&self, _init_bet: u32, _history: &[BetRecord]) -> usize { 1 }
+
+    // Playing strats only: whether it ever answers w/ an early or a late surrender.
+    // Tables allowing either kind are rejected for strats that never take that kind.
+    fn surrenders_early(&self) -> bool { false }
+    fn surrenders_late(&self) -> bool { false }
+
+    // Playing & dealer strats only: decisions read just the hand, upcard & table rules.
+    // The fast engine looks decisions up in tables filled once, so it only takes these.
+    fn count_independent(&self) -> bool { false }
+
+    // Betting strats only: every bet is init_bet, the fast engine never asks for one.
+    fn bets_flat(&self) -> bool { false }
+
+    // Parameters no game could be played with, checked when settings are built
+    fn problems(&self) -> Vec<String> { vec![] }
+
+    // Betting strats w/ their own sizing: unit bets are multiples of, & (min, max) bet.
+    // Checked against init_bet & the table limits when settings are built.
+    fn bet_unit(&self) -> Option<u32> { None }
+    fn bet_limits(&self) -> Option<(u32, u32)> { None }
+
+    // Registry name & params it was built from, set by `StrategyRegistry`
+    fn spec(&self) -> Option<StrategySpec> { None }
 }
 
-/// PLAYING STRATEGY FUNCTIONS
+// PLAYING STRATEGY FUNCTIONS
 
 // Dealer Strat
 // Dealer never doubles down, splits, or surrenders
-// Won't hit above cutoff even w/ soft ace
+// Won't hit above cutoff even w/ soft ace, hits soft 17 if the table says so
 
 pub struct DealerPlay;
 impl StrategyFunc for DealerPlay {
-    fn get_decision(&self, state: GameState) -> StratReturn {
-        let dealer_hand_value = state.dealer_hand.expect("").value();
-        if dealer_hand_value >= state.dealer_cutoff {
-            StratReturn::Play(PlayerDecision::Stand)
+    fn get_decision(&self, state: &GameView) -> Result<StratReturn, GameError> {
+        let dealer_hand = state.dealer()?;
+        let dealer_hand_value = dealer_hand.value();
+        let soft_17 = dealer_hand_value == 17 && dealer_hand.contains_soft_ace();
+        if dealer_hand_value >= state.dealer_cutoff && !(soft_17 && state.rules.hit_soft_17) {
+            Ok(StratReturn::Play(PlayerDecision::Stand))
         } else {
-            StratReturn::Play(PlayerDecision::Hit)
+            Ok(StratReturn::Play(PlayerDecision::Hit))
         }
     }
 
     fn to_string(&self) -> String {
         "Dealer Play".to_string()
     }
+
+    fn count_independent(&self) -> bool {
+        true
+    }
 }
 
 // -- Player Strats --
 
 pub struct MimicDealer;
 impl StrategyFunc for MimicDealer {
-    fn get_decision(&self, state: GameState) -> StratReturn {
-        assert!(state.player_hand.is_some());
-        if state.player_hand.expect("asserted").value() >= state.dealer_cutoff {
-            StratReturn::Play(PlayerDecision::Stand)
+    fn get_decision(&self, state: &GameView) -> Result<StratReturn, GameError> {
+        if state.hand()?.value() >= state.dealer_cutoff {
+            Ok(StratReturn::Play(PlayerDecision::Stand))
         } else {
-            StratReturn::Play(PlayerDecision::Hit)
+            Ok(StratReturn::Play(PlayerDecision::Hit))
         }
     }
 
     fn to_string(&self) -> String {
         "Mimic Dealer".to_string()
     }
+
+    fn count_independent(&self) -> bool {
+        true
+    }
 }
 
 pub struct NaiveSoft;
 impl StrategyFunc for NaiveSoft {
-    fn get_decision(&self, state: GameState) -> StratReturn {
-        assert!(state.player_hand.is_some());
-        let player_hand = state.player_hand.expect("Asserted");
+    fn get_decision(&self, state: &GameView) -> Result<StratReturn, GameError> {
+        let player_hand = state.hand()?;
         // If at or above cutoff
         if player_hand.value() >= state.dealer_cutoff {
             // If Soft Ace
             if player_hand.contains_soft_ace() && player_hand.value() < 18 {
-                StratReturn::Play(PlayerDecision::Hit)
+                Ok(StratReturn::Play(PlayerDecision::Hit))
             }
             // Else Stand
             else {
-                StratReturn::Play(PlayerDecision::Stand)
+                Ok(StratReturn::Play(PlayerDecision::Stand))
             }
         }
         // Hit if below cutoff
         else {
-            StratReturn::Play(PlayerDecision::Hit)
+            Ok(StratReturn::Play(PlayerDecision::Hit))
         }
     }
 
     fn to_string(&self) -> String {
         "Naive Soft".to_string()
     }
+
+    fn count_independent(&self) -> bool {
+        true
+    }
 }
 
 // Player uses dealer upcard strength to set a 'stand' cutoff value.
 // No other playing options considered
 pub struct CutoffOnly;
 impl StrategyFunc for CutoffOnly {
-    fn get_decision(&self, state: GameState) -> StratReturn {
-        assert!(state.player_hand.is_some());
-        let player_hand = state.player_hand.expect("Asserted");
+    fn get_decision(&self, state: &GameView) -> Result<StratReturn, GameError> {
+        let player_hand = state.hand()?;
 
-        let dealer_upcard_str = state.dealer_upcard_str.expect("");
+        let (_, dealer_upcard_str) = state.upcard()?;
         let player_cutoff = match &dealer_upcard_str {
             DealerUpcardStrength::Good => 17,
             DealerUpcardStrength::Fair => 13,
@@ -124,44 +165,44 @@ impl StrategyFunc for CutoffOnly {
         player_val != 21
         {
             // Not if blackjack
-            StratReturn::Play(PlayerDecision::Hit)
+            Ok(StratReturn::Play(PlayerDecision::Hit))
         }
         // Else Stand (above cutoff w/ no soft ace)
         else {
-            StratReturn::Play(PlayerDecision::Stand)
+            Ok(StratReturn::Play(PlayerDecision::Stand))
         }
     }
 
     fn to_string(&self) -> String {
         "Cutoff Only".to_string()
     }
+
+    fn count_independent(&self) -> bool {
+        true
+    }
 }
 
 
 pub struct DoubleOnly;
 impl StrategyFunc for DoubleOnly {
-    fn get_decision(&self, state: GameState) -> StratReturn {
-        assert!(state.player_hand.is_some());
-        let player_hand = state.player_hand.clone().expect("Asserted");
+    fn get_decision(&self, state: &GameView) -> Result<StratReturn, GameError> {
+        let player_hand = state.hand()?;
         
-        let dealer_upcard = state.dealer_upcard.expect("");
-        let dealer_upcard_str = state.dealer_upcard_str.expect("");
+        let (dealer_upcard, dealer_upcard_str) = state.upcard()?;
         // Double Check
         if !player_hand.doubled {
             match &player_hand.value() {
                 11 => {
-                    return StratReturn::Play(PlayerDecision::Double);
+                    return Ok(StratReturn::Play(PlayerDecision::Double));
                 }
-                10 => {
-                    // No double if Ace or 10 upcard
-                    if ![10, 11_u8].contains(&dealer_upcard.value()) {
-                        return StratReturn::Play(PlayerDecision::Double);
-                    }
+                // No double if Ace or 10 upcard
+                10 if ![10, 11_u8].contains(&dealer_upcard.value()) => {
+                    return Ok(StratReturn::Play(PlayerDecision::Double));
                 }
                 9 => {
                     match &dealer_upcard_str {
                         DealerUpcardStrength::Good => {}
-                        _ => return StratReturn::Play(PlayerDecision::Double), // Double if Fair or Poor
+                        _ => return Ok(StratReturn::Play(PlayerDecision::Double)), // Double if Fair or Poor
                     }
                 }
 
@@ -176,17 +217,19 @@ impl StrategyFunc for DoubleOnly {
     fn to_string(&self) -> String {
         "Double Only".to_string()
     }
+
+    fn count_independent(&self) -> bool {
+        true
+    }
 }
 
 pub struct SplitOnly;
 impl StrategyFunc for SplitOnly {
-    fn get_decision(&self, state: GameState) -> StratReturn {
-        assert!(state.player_hand.is_some());
-        let player_hand = state.player_hand.clone().expect("Asserted");
+    fn get_decision(&self, state: &GameView) -> Result<StratReturn, GameError> {
+        let player_hand = state.hand()?;
 
         
-        let dealer_upcard = state.dealer_upcard.expect("");
-        let dealer_upcard_str = state.dealer_upcard_str.expect("");
+        let (dealer_upcard, dealer_upcard_str) = state.upcard()?;
 
         if player_hand.contains_pair() {
             // Split 8's and Aces
@@ -194,20 +237,20 @@ impl StrategyFunc for SplitOnly {
                 .contains_pair_of(Card::from_rank(Rank::Eight))
                 || player_hand.contains_pair_of(Card::from_rank(Rank::Ace))
             {
-                return StratReturn::Play(PlayerDecision::Split);
+                return Ok(StratReturn::Play(PlayerDecision::Split));
             }
 
             // Generally Split 2's, 3's, & 7's
             if [4, 6, 14_u8].contains(&player_hand.value()) {
                 // Only split if upcard val not in array
                 if ![8, 9, 10, 11].contains(&dealer_upcard.value()) {
-                    return StratReturn::Play(PlayerDecision::Split);
+                    return Ok(StratReturn::Play(PlayerDecision::Split));
                 }
             }
 
             // Split 6's if Poor upcard
             if player_hand.value() == 12 && dealer_upcard_str == DealerUpcardStrength::Poor {
-                return StratReturn::Play(PlayerDecision::Split);
+                return Ok(StratReturn::Play(PlayerDecision::Split));
             }
         }
         // Default behavior if no split
@@ -217,18 +260,17 @@ impl StrategyFunc for SplitOnly {
     fn to_string(&self) -> String {
         "Split Only".to_string()
     }
+
+    fn count_independent(&self) -> bool {
+        true
+    }
 }
 
 pub struct BasicStrategy;
 impl StrategyFunc for BasicStrategy {
-    fn get_decision(&self, state: GameState) -> StratReturn {
-        // Assertions
-        assert!(state.dealer_upcard.is_some());
-        assert!(state.dealer_upcard_str.is_some());
-        assert!(state.player_hand.is_some());
-        let player_hand = state.player_hand.expect("Asserted");
-        let dealer_upcard = state.dealer_upcard.expect("Asserted");
-        let dealer_upcard_str = state.dealer_upcard_str.expect("Asserted");
+    fn get_decision(&self, state: &GameView) -> Result<StratReturn, GameError> {
+        let player_hand = state.hand()?;
+        let (dealer_upcard, dealer_upcard_str) = state.upcard()?;
 
         // Early Surrender Check
 
@@ -241,20 +283,20 @@ impl StrategyFunc for BasicStrategy {
                 || player_hand
                     .contains_pair_of(Card::from_rank(Rank::Ace))
             {
-                return StratReturn::Play(PlayerDecision::Split);
+                return Ok(StratReturn::Play(PlayerDecision::Split));
             }
 
             // Generally Split 2's, 3's, & 7's
             if [4, 6, 14_u8].contains(&player_hand.value()) {
                 // Only split if upcard val not in array
                 if ![8, 9, 10, 11].contains(&dealer_upcard.value()) {
-                    return StratReturn::Play(PlayerDecision::Split);
+                    return Ok(StratReturn::Play(PlayerDecision::Split));
                 }
             }
 
             // Split 6's if Poor upcard
             if player_hand.value() == 12 && dealer_upcard_str == DealerUpcardStrength::Poor {
-                return StratReturn::Play(PlayerDecision::Split);
+                return Ok(StratReturn::Play(PlayerDecision::Split));
             }
         }
 
@@ -264,18 +306,16 @@ impl StrategyFunc for BasicStrategy {
         if !player_hand.doubled {
             match &player_hand.value() {
                 11 => {
-                    return StratReturn::Play(PlayerDecision::Double);
+                    return Ok(StratReturn::Play(PlayerDecision::Double));
                 }
-                10 => {
-                    // No double if Ace or 10 upcard
-                    if ![10, 11_u8].contains(&dealer_upcard.value()) {
-                        return StratReturn::Play(PlayerDecision::Double);
-                    }
+                // No double if Ace or 10 upcard
+                10 if ![10, 11_u8].contains(&dealer_upcard.value()) => {
+                    return Ok(StratReturn::Play(PlayerDecision::Double));
                 }
                 9 => {
                     match &dealer_upcard_str {
                         DealerUpcardStrength::Good => {}
-                        _ => return StratReturn::Play(PlayerDecision::Double), // Double if Fair or Poor
+                        _ => return Ok(StratReturn::Play(PlayerDecision::Double)), // Double if Fair or Poor
                     }
                 }
 
@@ -298,19 +338,129 @@ impl StrategyFunc for BasicStrategy {
         player_hand.value() < 18
         {
             // Not if blackjack
-            StratReturn::Play(PlayerDecision::Hit)
+            Ok(StratReturn::Play(PlayerDecision::Hit))
         }
         // Else Stand (above cutoff w/ no soft ace)
         else {
-            StratReturn::Play(PlayerDecision::Stand)
+            Ok(StratReturn::Play(PlayerDecision::Stand))
         }
     }
 
     fn to_string(&self) -> String {
         "Basic Strategy".to_string()
     }
+
+    fn count_independent(&self) -> bool {
+        true
+    }
 }
 
+// Multi deck basic strategy charts, adjusted for the table's rules
+// H17/S17, DAS, double restrictions, late surrender & no hole card
+pub struct FullBasicStrategy;
+
+impl FullBasicStrategy {
+    // Dealer upcard 2-11, aces as 11
+    fn upcard(state: &GameView) -> Result<u8, GameError> {
+        Ok(state.upcard()?.0.rank.value(true))
+    }
+
+    fn split(state: &GameView, hand: &Hand, up: u8) -> bool {
+        if !state.can_split(hand) {
+            return false;
+        }
+        let das = state.rules.double_after_split;
+        // No hole card: a dealer natural takes the split bets too
+        if !state.rules.dealer_peeks && up >= 10 && !(up == 10 && hand.cards[0].rank == Rank::Ace) {
+            return false;
+        }
+        match hand.cards[0].rank.value(true) {
+            11 | 8 => true,
+            9 => !matches!(up, 7 | 10 | 11),
+            7 => up <= 7,
+            6 => (if das { 2 } else { 3 }..=6).contains(&up),
+            4 => das && (5..=6).contains(&up),
+            2 | 3 => (if das { 2 } else { 4 }..=7).contains(&up),
+            _ => false, // 5s played as 10, 10s stood
+        }
+    }
+
+    fn surrender(state: &GameView, hand: &Hand, up: u8) -> bool {
+        if !state.allow_late_surrender || hand.cards.len() != 2 || hand.split_child || hand.contains_soft_ace() {
+            return false;
+        }
+        let h17 = state.rules.hit_soft_17;
+        match hand.value() {
+            16 => up >= 9,
+            15 => up == 10 || (h17 && up == 11),
+            17 => h17 && up == 11,
+            _ => false,
+        }
+    }
+
+    // Double wanted, hit or stand decided after
+    fn double(state: &GameView, hand: &Hand, up: u8) -> bool {
+        let h17 = state.rules.hit_soft_17;
+        if !state.rules.dealer_peeks && up >= 10 {
+            return false;
+        }
+        match (hand.contains_soft_ace(), hand.value()) {
+            (true, 13..=14) => (5..=6).contains(&up),
+            (true, 15..=16) => (4..=6).contains(&up),
+            (true, 17) => (3..=6).contains(&up),
+            (true, 18) => (if h17 { 2 } else { 3 }..=6).contains(&up),
+            (true, 19) => h17 && up == 6,
+            (false, 9) => (3..=6).contains(&up),
+            (false, 10) => up <= 9,
+            (false, 11) => up <= 10 || h17,
+            _ => false,
+        }
+    }
+
+    fn stand(hand: &Hand, up: u8) -> bool {
+        match (hand.contains_soft_ace(), hand.value()) {
+            (true, total) => total >= 19 || (total == 18 && up <= 8),
+            (false, total) if total >= 17 => true,
+            (false, 13..=16) => up <= 6,
+            (false, 12) => (4..=6).contains(&up),
+            _ => false,
+        }
+    }
+}
+
+impl StrategyFunc for FullBasicStrategy {
+    fn get_decision(&self, state: &GameView) -> Result<StratReturn, GameError> {
+        let hand = state.hand()?;
+        let up = FullBasicStrategy::upcard(state)?;
+
+        // 8s split rather than surrendered
+        let decision = if FullBasicStrategy::surrender(state, hand, up) && !(hand.contains_pair() && state.can_split(hand) && hand.value() == 16) {
+            PlayerDecision::LateSurrender
+        } else if FullBasicStrategy::split(state, hand, up) {
+            PlayerDecision::Split
+        } else if state.can_double(hand) && FullBasicStrategy::double(state, hand, up) {
+            PlayerDecision::Double
+        } else if FullBasicStrategy::stand(hand, up) {
+            PlayerDecision::Stand
+        } else {
+            PlayerDecision::Hit
+        };
+        Ok(StratReturn::Play(decision))
+    }
+
+    fn to_string(&self) -> String {
+        "Full Basic Strategy".to_string()
+    }
+
+    fn count_independent(&self) -> bool {
+        true
+    }
+
+    // Late only, never before the dealer checks for blackjack
+    fn surrenders_late(&self) -> bool {
+        true
+    }
+}
 
 // |-------------------------|
 // |  CARD COUNTING STRATS   |
@@ -324,9 +474,8 @@ impl StrategyFunc for BasicStrategy {
 // Neg count means lower number of 10 value cards
 pub struct HiLo;
 impl StrategyFunc for HiLo {
-    fn get_decision(&self, state: GameState) -> StratReturn {
-        assert_eq!(false, state.played_cards.is_empty());
-        let last_card = state.played_cards.last().expect("Asserted");
+    fn get_decision(&self, state: &GameView) -> Result<StratReturn, GameError> {
+        let last_card = state.last_card()?;
         let delta = match last_card.value() {
             2..=6 => 1,    // High
             7..=9 => 0,    // Neutral
@@ -334,7 +483,7 @@ impl StrategyFunc for HiLo {
             _ => 0,        // Neutral (Never Reached)
         };
 
-        StratReturn::Count(delta)
+        Ok(StratReturn::Count(delta))
     }
 
     fn to_string(&self) -> String {
@@ -344,9 +493,8 @@ impl StrategyFunc for HiLo {
 
 pub struct KnockOut;
 impl StrategyFunc for KnockOut {
-    fn get_decision(&self, state: GameState) -> StratReturn {
-        assert_eq!(false, state.played_cards.is_empty());
-        let last_card = state.played_cards.last().expect("Asserted");
+    fn get_decision(&self, state: &GameView) -> Result<StratReturn, GameError> {
+        let last_card = state.last_card()?;
         let delta = match last_card.value() {
             2..=7 => 1,    // High
             8..=9 => 0,    // Neutral
@@ -354,7 +502,7 @@ impl StrategyFunc for KnockOut {
             _ => 0,        // Neutral (Never Reached)
         };
 
-        StratReturn::Count(delta)
+        Ok(StratReturn::Count(delta))
     }
 
     fn to_string(&self) -> String {
@@ -364,16 +512,14 @@ impl StrategyFunc for KnockOut {
 
 pub struct OmegaTwo;
 impl StrategyFunc for OmegaTwo {
-    fn get_decision(&self, state: GameState) -> StratReturn {
+    fn get_decision(&self, state: &GameView) -> Result<StratReturn, GameError> {
         let plus_two = [4, 5, 6];
     let plus_one = [2, 3, 7];
         let zero = [8, 11];
         let minus_one = 9;
         let minus_two = 10;
 
-        // Get Last card
-        assert_eq!(false, state.played_cards.is_empty());
-        let last_card = state.played_cards.last().expect("Asserted");
+        let last_card = state.last_card()?;
 
         let delta = if plus_two.contains(&last_card.value()) {
             2
@@ -386,9 +532,9 @@ impl StrategyFunc for OmegaTwo {
         } else if last_card.value() == minus_two {
             -2
         } else {
-            unreachable!("All card values covered")
+            0 // Neutral (Never Reached)
         };
-        StratReturn::Count(delta)
+        Ok(StratReturn::Count(delta))
     }
 
     fn to_string(&self) -> String {
@@ -401,20 +547,19 @@ impl StrategyFunc for OmegaTwo {
 // |  INSURANCE STRATEGIES   |
 // |-------------------------|
 
-/// Offered on dealer ace upcard before hole card looked at
-/// 2:1 payout
-/// Max bet of half init bet
-/// Paid out if dealer natural
-
+// Offered on dealer ace upcard before hole card looked at
+// 2:1 payout
+// Max bet of half init bet
+// Paid out if dealer natural
 
 pub struct NoInsurance;
 impl StrategyFunc for NoInsurance {
-    fn get_decision(&self, _: GameState) -> StratReturn { StratReturn::Insurance(false) }
+    fn get_decision(&self, _: &GameView) -> Result<StratReturn, GameError> { Ok(StratReturn::Insurance(false)) }
 
     fn to_string(&self) -> String { "No Insurance".to_string() }
 }
 
 
-// pub fn card_counter_insurance(_state: GameState) -> bool {
+// pub fn card_counter_insurance(_state: GameView) -> bool {
 //     false
 // }
@@ -0,0 +1,114 @@
+//! Named casino rule sets w/ their published house edges.
+
+use crate::config::{RulesConfig, ShoeConfig, SimulationConfig, StrategiesConfig};
+use crate::{BlackjackPayout, DoubleRule, TableRules};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Preset {
+    pub key: &'static str,
+    pub name: &'static str,
+    pub description: &'static str,
+    pub house_edge: f64, // Published edge for basic strategy, % of the initial bet
+    pub decks: u8,
+    pub penetration: f64,
+    pub rules: RulesConfig,
+}
+
+impl Preset {
+    // Preset's rules & shoe, played w/ full basic strategy like the published edge
+    pub fn config(&self) -> SimulationConfig {
+        SimulationConfig {
+            preset: Some(self.key.to_string()),
+            rules: self.rules.clone(),
+            shoe: ShoeConfig { decks: self.decks, cut_card: true, penetration: Some(self.penetration), ..Default::default() },
+            strategies: StrategiesConfig { player: "full-basic".into(), ..Default::default() },
+            ..Default::default()
+        }
+    }
+}
+
+// Hole card, split to 4 hands, split aces get one card
+fn casino_rules(rules: TableRules, late_surrender: bool) -> RulesConfig {
+    RulesConfig {
+        late_surrender,
+        ..RulesConfig::from_table_rules(TableRules { dealer_peeks: true, split_aces_one_card: true, ..rules })
+    }
+}
+
+pub fn presets() -> Vec<Preset> {
+    let s17_das = TableRules::default();
+    let h17_das = TableRules { hit_soft_17: true, ..s17_das };
+    vec![
+        Preset {
+            key: "vegas-strip",
+            name: "Vegas Strip",
+            description: "6 decks, dealer stands on soft 17, double any two, DAS, late surrender",
+            house_edge: 0.36,
+            decks: 6,
+            penetration: 0.75,
+            rules: casino_rules(s17_das, true),
+        },
+        Preset {
+            key: "vegas-strip-h17",
+            name: "Vegas Strip H17",
+            description: "6 decks, dealer hits soft 17, double any two, DAS, late surrender",
+            house_edge: 0.56,
+            decks: 6,
+            penetration: 0.75,
+            rules: casino_rules(h17_das, true),
+        },
+        Preset {
+            key: "downtown",
+            name: "Downtown Vegas",
+            description: "2 decks, dealer hits soft 17, double any two, DAS, no surrender",
+            house_edge: 0.46,
+            decks: 2,
+            penetration: 0.65,
+            rules: casino_rules(h17_das, false),
+        },
+        Preset {
+            key: "atlantic-city",
+            name: "Atlantic City",
+            description: "8 decks, dealer stands on soft 17, double any two, DAS, late surrender",
+            house_edge: 0.43,
+            decks: 8,
+            penetration: 0.75,
+            rules: casino_rules(s17_das, true),
+        },
+        Preset {
+            key: "single-deck-6-5",
+            name: "Single Deck 6:5",
+            description: "1 deck, naturals pay 6:5, dealer hits soft 17, double any two, no DAS",
+            house_edge: 1.55,
+            decks: 1,
+            penetration: 0.6,
+            rules: casino_rules(TableRules { double_after_split: false, blackjack_pays: BlackjackPayout::SixToFive, ..h17_das }, false),
+        },
+        Preset {
+            key: "european",
+            name: "European No Hole Card",
+            description: "6 decks, no hole card, dealer stands on soft 17, double 9-11, DAS, no surrender",
+            house_edge: 0.62,
+            decks: 6,
+            penetration: 0.75,
+            rules: RulesConfig {
+                dealer_peeks: false,
+                ..casino_rules(TableRules { double_on: DoubleRule::NineToEleven, ..s17_das }, false)
+            },
+        },
+        Preset {
+            key: "reno",
+            name: "Reno",
+            description: "6 decks, dealer hits soft 17, double 10-11 only, no DAS, no surrender",
+            house_edge: 0.95,
+            decks: 6,
+            penetration: 0.75,
+            rules: casino_rules(TableRules { double_on: DoubleRule::TenToEleven, double_after_split: false, ..h17_das }, false),
+        },
+    ]
+}
+
+// By key or name, ignoring case
+pub fn find_preset(name: &str) -> Option<Preset> {
+    presets().into_iter().find(|preset| preset.key.eq_ignore_ascii_case(name) || preset.name.eq_ignore_ascii_case(name))
+}
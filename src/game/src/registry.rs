@@ -0,0 +1,256 @@
+//! Strategies by name, so runs can be configured without recompiling.
+
+use std::error::Error;
+use std::fmt;
+use std::sync::Arc;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{Map, Value};
+use crate::{error::GameError, BetRecord, GameView};
+use crate::playing_strategy::{StratReturn, StrategyFunc,
+    BasicStrategy, FullBasicStrategy, SplitOnly, DoubleOnly, CutoffOnly, DealerPlay, NaiveSoft, MimicDealer,
+    HiLo, KnockOut, OmegaTwo, NoInsurance,
+};
+use crate::betting_strategy::{ConstantBet, Martingale, KellyCriterion, BetRamp,
+    Paroli, OneThreeTwoSix, DAlembert, Fibonacci, Labouchere, OscarsGrind,
+};
+
+// Which `GameSettings` slot a strategy fills
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StrategyKind {
+    Playing,
+    Dealer,
+    Betting,
+    Counting,
+    Insurance,
+}
+
+impl fmt::Display for StrategyKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StrategyKind::Playing => write!(f, "playing"),
+            StrategyKind::Dealer => write!(f, "dealer"),
+            StrategyKind::Betting => write!(f, "betting"),
+            StrategyKind::Counting => write!(f, "counting"),
+            StrategyKind::Insurance => write!(f, "insurance"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownStrategy {
+    pub kind: StrategyKind,
+    pub name: String,
+    pub known: Vec<String>,
+}
+
+impl fmt::Display for UnknownStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Unknown {} strategy '{}', expected one of: {}", self.kind, self.name, self.known.join(", "))
+    }
+}
+
+impl Error for UnknownStrategy {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StrategyError {
+    Unknown(UnknownStrategy),
+    InvalidParams { kind: StrategyKind, name: String, message: String },
+}
+
+impl fmt::Display for StrategyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StrategyError::Unknown(err) => write!(f, "{}", err),
+            StrategyError::InvalidParams { kind, name, message } => write!(f, "Invalid parameters for {} strategy '{}': {}", kind, name, message),
+        }
+    }
+}
+
+impl Error for StrategyError {}
+
+impl From<UnknownStrategy> for StrategyError {
+    fn from(err: UnknownStrategy) -> Self {
+        StrategyError::Unknown(err)
+    }
+}
+
+// Strategy as written in a config: a name, or a table w/ a name & parameters
+//   betting = "martingale"
+//   betting = { name = "kelly", fraction = 0.5 }
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum StrategySpec {
+    Name(String),
+    WithParams {
+        name: String,
+        #[serde(flatten)]
+        params: Map<String, Value>,
+    },
+}
+
+impl StrategySpec {
+    pub fn name(&self) -> &str {
+        match self {
+            StrategySpec::Name(name) | StrategySpec::WithParams { name, .. } => name,
+        }
+    }
+}
+
+impl From<&str> for StrategySpec {
+    fn from(name: &str) -> Self {
+        StrategySpec::Name(name.to_string())
+    }
+}
+
+// Strategy w/ the spec it was built from, so runs can record it & be rebuilt
+struct Registered {
+    spec: StrategySpec,
+    strat: Box<dyn StrategyFunc>,
+}
+
+impl Registered {
+    fn wrap(spec: StrategySpec, strat: Box<dyn StrategyFunc>) -> Arc<Box<dyn StrategyFunc>> {
+        Arc::new(Box::new(Registered { spec, strat }))
+    }
+}
+
+impl StrategyFunc for Registered {
+    fn get_decision(&self, state: &GameView) -> Result<StratReturn, GameError> { self.strat.get_decision(state) }
+    fn to_string(&self) -> String { self.strat.to_string() }
+    fn history_needed(&self, init_bet: u32, history: &[BetRecord]) -> usize { self.strat.history_needed(init_bet, history) }
+    fn surrenders_early(&self) -> bool { self.strat.surrenders_early() }
+    fn surrenders_late(&self) -> bool { self.strat.surrenders_late() }
+    fn count_independent(&self) -> bool { self.strat.count_independent() }
+    fn bets_flat(&self) -> bool { self.strat.bets_flat() }
+    fn problems(&self) -> Vec<String> { self.strat.problems() }
+    fn bet_unit(&self) -> Option<u32> { self.strat.bet_unit() }
+    fn bet_limits(&self) -> Option<(u32, u32)> { self.strat.bet_limits() }
+    fn spec(&self) -> Option<StrategySpec> { Some(self.spec.clone()) }
+}
+
+// Builds a strategy from its params, Err w/ a readable message if they don't fit
+type Constructor = Box<dyn Fn(&Map<String, Value>) -> Result<Box<dyn StrategyFunc>, String> + Send + Sync>;
+
+struct Entry {
+    kind: StrategyKind,
+    name: String,
+    build: Constructor,
+}
+
+pub struct StrategyRegistry {
+    entries: Vec<Entry>,
+}
+
+impl Default for StrategyRegistry {
+    // Every built in strategy
+    fn default() -> Self {
+        let mut registry = StrategyRegistry { entries: vec![] };
+
+        // Playing
+        registry.register(StrategyKind::Playing, "basic", || Box::new(BasicStrategy));
+        registry.register(StrategyKind::Playing, "full-basic", || Box::new(FullBasicStrategy));
+        registry.register(StrategyKind::Playing, "naive-soft", || Box::new(NaiveSoft));
+        registry.register(StrategyKind::Playing, "mimic-dealer", || Box::new(MimicDealer));
+        registry.register(StrategyKind::Playing, "cutoff-only", || Box::new(CutoffOnly));
+        registry.register(StrategyKind::Playing, "double-only", || Box::new(DoubleOnly));
+        registry.register(StrategyKind::Playing, "split-only", || Box::new(SplitOnly));
+
+        // Dealer
+        registry.register(StrategyKind::Dealer, "dealer-play", || Box::new(DealerPlay));
+
+        // Betting
+        registry.register(StrategyKind::Betting, "constant", || Box::new(ConstantBet));
+        registry.register(StrategyKind::Betting, "martingale", || Box::new(Martingale));
+        registry.register_configurable::<KellyCriterion>(StrategyKind::Betting, "kelly");
+        registry.register(StrategyKind::Betting, "half-kelly", || Box::new(KellyCriterion { fraction: 0.5, ..Default::default() }));
+        registry.register(StrategyKind::Betting, "paroli", || Box::new(Paroli));
+        registry.register(StrategyKind::Betting, "1-3-2-6", || Box::new(OneThreeTwoSix));
+        registry.register(StrategyKind::Betting, "dalembert", || Box::new(DAlembert));
+        registry.register(StrategyKind::Betting, "fibonacci", || Box::new(Fibonacci));
+        registry.register_configurable::<Labouchere>(StrategyKind::Betting, "labouchere");
+        registry.register(StrategyKind::Betting, "oscars-grind", || Box::new(OscarsGrind));
+        registry.register_configurable::<BetRamp>(StrategyKind::Betting, "ramp");
+
+        // Counting
+        registry.register(StrategyKind::Counting, "hi-lo", || Box::new(HiLo));
+        registry.register(StrategyKind::Counting, "knock-out", || Box::new(KnockOut));
+        registry.register(StrategyKind::Counting, "omega-two", || Box::new(OmegaTwo));
+
+        // Insurance
+        registry.register(StrategyKind::Insurance, "none", || Box::new(NoInsurance));
+
+        registry
+    }
+}
+
+impl StrategyRegistry {
+    // Strategy w/o parameters
+    // Later registrations under the same kind & name replace earlier ones
+    pub fn register<F>(&mut self, kind: StrategyKind, name: &str, build: F)
+    where F: Fn() -> Box<dyn StrategyFunc> + Send + Sync + 'static {
+        self.insert(kind, name, Box::new(move |params| match params.keys().next() {
+            Some(key) => Err(format!("takes no parameters, found '{}'", key)),
+            None => Ok(build()),
+        }));
+    }
+
+    // Strategy deserialized from its params, missing params take the strategy's defaults
+    // Params the strategy reports problems with are rejected
+    pub fn register_configurable<T>(&mut self, kind: StrategyKind, name: &str)
+    where T: StrategyFunc + DeserializeOwned + 'static {
+        self.insert(kind, name, Box::new(|params| {
+            let strat = serde_json::from_value::<T>(Value::Object(params.clone())).map_err(|err| err.to_string())?;
+            match strat.problems() {
+                problems if problems.is_empty() => Ok(Box::new(strat) as Box<dyn StrategyFunc>),
+                problems => Err(problems.join("; ")),
+            }
+        }));
+    }
+
+    fn insert(&mut self, kind: StrategyKind, name: &str, build: Constructor) {
+        self.entries.retain(|entry| !(entry.kind == kind && entry.name == name));
+        self.entries.push(Entry { kind, name: name.to_string(), build });
+    }
+
+    // Registered names of a kind, in registration order
+    pub fn names(&self, kind: StrategyKind) -> Vec<String> {
+        self.entries.iter().filter(|entry| entry.kind == kind).map(|entry| entry.name.clone()).collect()
+    }
+
+    // By registered name, or the strategy's display name (e.g. from a report), ignoring case
+    // Default parameters
+    pub fn get(&self, kind: StrategyKind, name: &str) -> Result<Arc<Box<dyn StrategyFunc>>, UnknownStrategy> {
+        let no_params = Map::new();
+        let entries = self.entries.iter().filter(|entry| entry.kind == kind);
+        for entry in entries.clone() {
+            if entry.name.eq_ignore_ascii_case(name) {
+                if let Ok(strat) = (entry.build)(&no_params) {
+                    return Ok(Registered::wrap(StrategySpec::Name(entry.name.clone()), strat));
+                }
+            }
+        }
+        for entry in entries {
+            if let Ok(strat) = (entry.build)(&no_params) {
+                if strat.to_string().eq_ignore_ascii_case(name) {
+                    return Ok(Registered::wrap(StrategySpec::Name(entry.name.clone()), strat));
+                }
+            }
+        }
+        Err(UnknownStrategy { kind, name: name.to_string(), known: self.names(kind) })
+    }
+
+    // By registered name w/ parameters
+    pub fn build(&self, kind: StrategyKind, spec: &StrategySpec) -> Result<Arc<Box<dyn StrategyFunc>>, StrategyError> {
+        let params = match spec {
+            StrategySpec::Name(name) => return Ok(self.get(kind, name)?),
+            StrategySpec::WithParams { params, .. } => params,
+        };
+        let name = spec.name();
+        let entry = self.entries.iter()
+            .find(|entry| entry.kind == kind && entry.name.eq_ignore_ascii_case(name))
+            .ok_or_else(|| UnknownStrategy { kind, name: name.to_string(), known: self.names(kind) })?;
+        (entry.build)(params)
+            .map(|strat| Registered::wrap(spec.clone(), strat))
+            .map_err(|message| StrategyError::InvalidParams { kind, name: name.to_string(), message })
+    }
+}
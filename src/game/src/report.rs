@@ -0,0 +1,421 @@
+//! Machine readable simulation reports: JSON for one run, flat CSV rows for many.
+
+use std::fs;
+use std::io::{self, Write};
+use std::ops::Div;
+use std::path::Path;
+use std::time::Duration;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use crate::registry::StrategySpec;
+use crate::run_many::GamePool;
+use crate::shoe::{ShoeKind, CSM_RESERVOIR};
+use crate::shuffle::ShuffleProcedure;
+use crate::{EndState, GameSettings, LimitPolicy, LimitStats, TableRules, Winner};
+
+// Settings a run was made with, strategies by name
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SettingsSummary {
+    pub player_strat: String,
+    pub dealer_strat: String,
+    pub betting_strat: String,
+    pub counting_strat: String,
+    pub insurance_strat: String,
+    // Registry name & params, none for strategies built in code or before they were recorded
+    #[serde(default)]
+    pub player_spec: Option<StrategySpec>,
+    #[serde(default)]
+    pub dealer_spec: Option<StrategySpec>,
+    #[serde(default)]
+    pub betting_spec: Option<StrategySpec>,
+    #[serde(default)]
+    pub counting_spec: Option<StrategySpec>,
+    #[serde(default)]
+    pub insurance_spec: Option<StrategySpec>,
+    pub deck_count: u8,
+    pub contains_blank: bool,
+    #[serde(default)] // Random cut card if none
+    pub penetration: Option<f64>,
+    #[serde(default)] // Missing before shoes were pluggable
+    pub shoe: ShoeKind,
+    #[serde(default = "csm_reservoir")]
+    pub reservoir: usize,
+    #[serde(default)]
+    pub shuffle: ShuffleProcedure,
+    pub max_splits: u8,
+    pub init_bet: u32,
+    pub bankroll: u32,
+    pub dealer_cutoff: u8,
+    pub allow_early_surrender: bool,
+    pub allow_late_surrender: bool,
+    #[serde(default)] // Missing before table rules were configurable
+    pub rules: TableRules,
+    pub table_min: u32,
+    pub table_max: u32,
+    pub bet_increment: u32,
+    pub limit_policy: LimitPolicy,
+    pub seed: Option<u64>,
+}
+
+impl <R: Rng> From<&GameSettings<R>> for SettingsSummary {
+    fn from(settings: &GameSettings<R>) -> Self {
+        SettingsSummary {
+            player_strat: settings.player_strat.to_string(),
+            dealer_strat: settings.dealer_strat.to_string(),
+            betting_strat: settings.betting_strat.to_string(),
+            counting_strat: settings.counting_strat.to_string(),
+            insurance_strat: settings.insurance_strat.to_string(),
+            player_spec: settings.player_strat.spec(),
+            dealer_spec: settings.dealer_strat.spec(),
+            betting_spec: settings.betting_strat.spec(),
+            counting_spec: settings.counting_strat.spec(),
+            insurance_spec: settings.insurance_strat.spec(),
+            deck_count: settings.deck.deck_count,
+            contains_blank: settings.contains_blank,
+            penetration: settings.deck.penetration,
+            shoe: settings.deck.kind,
+            reservoir: settings.deck.reservoir,
+            shuffle: settings.deck.shuffle.clone(),
+            max_splits: settings.max_splits,
+            init_bet: settings.init_bet,
+            bankroll: settings.bankroll,
+            dealer_cutoff: settings.dealer_cutoff,
+            allow_early_surrender: settings.allow_early_surrender,
+            allow_late_surrender: settings.allow_late_surrender,
+            rules: settings.rules,
+            table_min: settings.table_min,
+            table_max: settings.table_max,
+            bet_increment: settings.bet_increment,
+            limit_policy: settings.limit_policy,
+            seed: settings.seed,
+        }
+    }
+}
+
+fn csm_reservoir() -> usize {
+    CSM_RESERVOIR
+}
+
+// Hand outcome counts, split hands counted separately
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OutcomeTally {
+    pub player_wins: u64,
+    pub dealer_wins: u64,
+    pub ties: u64,
+    pub player_naturals: u64,
+    pub dealer_naturals: u64,
+    pub player_busts: u64,
+    pub dealer_busts: u64,
+    pub doubles: u64,
+    pub surrenders: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SimulationReport {
+    pub settings: SettingsSummary,
+    pub rounds: u64, // Betting rounds simulated
+    pub hands: u64,  // Settled hands, incl. split hands
+    pub outcomes: OutcomeTally,
+    pub limit_stats: LimitStats,
+    pub total_bet: u64,       // Sum of initial hand bets
+    pub payoff: i64,          // Net player result
+    pub ev_per_hand: f64,     // Mean payoff per hand
+    pub ev_std_error: f64,    // Standard error of ev_per_hand
+    pub ev_percent: f64,      // Payoff as % of initial bets
+    pub ev_percent_ci95: f64, // +/- 95% confidence half width of ev_percent
+    // Per round, splits & doubles included: house edges are quoted this way
+    #[serde(default)] // Missing before rounds were tallied
+    pub ev_per_round: f64,
+    #[serde(default)]
+    pub ev_round_percent: f64,      // Payoff as % of the rounds' initial bets
+    #[serde(default)]
+    pub ev_round_percent_ci95: f64, // +/- 95% confidence half width of ev_round_percent
+    pub elapsed_secs: f64,
+    pub hands_per_sec: f64,
+}
+
+// Running sums a report is made from, hand by hand
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Totals {
+    outcomes: OutcomeTally,
+    hands: u64,
+    total_bet: u64,
+    payoff: i64,
+    payoff_sum: f64,
+    payoff_sq_sum: f64,
+    pub(crate) rounds: RoundTotals,
+}
+
+// Running sums round by round, all of a round's hands against its initial bet
+#[derive(Debug, Clone, Default)]
+pub(crate) struct RoundTotals {
+    rounds: u64,
+    total_bet: u64,
+    payoff_sum: f64,
+    payoff_sq_sum: f64,
+}
+
+impl RoundTotals {
+    pub(crate) fn add(&mut self, bet: u32, payoff: i64) {
+        self.rounds += 1;
+        self.total_bet += bet as u64;
+        self.payoff_sum += payoff as f64;
+        self.payoff_sq_sum += (payoff * payoff) as f64;
+    }
+
+    pub(crate) fn add_results(&mut self, bet: u32, results: &[(Winner, EndState)]) {
+        self.add(bet, results.iter().map(|(winner, state)| state.payoff(winner)).sum());
+    }
+
+    // (Mean payoff, payoff % of the initial bet, +/- 95% half width of the %)
+    fn ev(&self) -> (f64, f64, f64) {
+        let n = self.rounds as f64;
+        if n == 0.0 || self.total_bet == 0 {
+            return (0.0, 0.0, 0.0);
+        }
+        let ev = self.payoff_sum.div(n);
+        let variance = if n > 1.0 { (self.payoff_sq_sum - n * ev * ev).div(n - 1.0).max(0.0) } else { 0.0 };
+        let mean_bet = (self.total_bet as f64).div(n);
+        (ev, 100_f64 * ev.div(mean_bet), 100_f64 * 1.96 * (variance / n).sqrt().div(mean_bet))
+    }
+}
+
+impl Totals {
+    pub(crate) fn add(&mut self, winner: &Winner, state: &EndState) {
+        let outcomes = &mut self.outcomes;
+        match winner {
+            Winner::Player => outcomes.player_wins += 1,
+            Winner::Dealer => outcomes.dealer_wins += 1,
+            Winner::Tie => outcomes.ties += 1,
+            Winner::None => {}
+        }
+        outcomes.player_naturals += state.p_natural as u64;
+        outcomes.dealer_naturals += state.d_natural as u64;
+        outcomes.player_busts += state.p_bust as u64;
+        outcomes.dealer_busts += state.d_bust as u64;
+        outcomes.doubles += state.p_doubled as u64;
+        outcomes.surrenders += (state.p_surrender_early || state.p_surrender_late) as u64;
+
+        let hand_payoff = state.payoff(winner);
+        self.hands += 1;
+        self.total_bet += state.hand_bet as u64;
+        self.payoff += hand_payoff;
+        self.payoff_sum += hand_payoff as f64;
+        self.payoff_sq_sum += (hand_payoff * hand_payoff) as f64;
+    }
+
+    pub(crate) fn report(&self, settings: SettingsSummary, rounds: u64, limit_stats: LimitStats, elapsed: Duration) -> SimulationReport {
+        // Mean & standard error of per hand payoff
+        let n = self.hands as f64;
+        let ev_per_hand = if n > 0.0 { self.payoff_sum.div(n) } else { 0.0 };
+        let variance = if n > 1.0 { (self.payoff_sq_sum - n * ev_per_hand * ev_per_hand).div(n - 1.0).max(0.0) } else { 0.0 };
+        let ev_std_error = if n > 0.0 { (variance / n).sqrt() } else { 0.0 };
+
+        // Same in % of the average initial bet
+        let mean_bet = if n > 0.0 { (self.total_bet as f64).div(n) } else { 0.0 };
+        let to_percent = |amount: f64| if mean_bet > 0.0 { 100_f64 * amount.div(mean_bet) } else { 0.0 };
+
+        let (ev_per_round, ev_round_percent, ev_round_percent_ci95) = self.rounds.ev();
+        let elapsed_secs = elapsed.as_secs_f64();
+
+        SimulationReport {
+            settings,
+            rounds,
+            hands: self.hands,
+            outcomes: self.outcomes.clone(),
+            limit_stats,
+            total_bet: self.total_bet,
+            payoff: self.payoff,
+            ev_per_hand,
+            ev_std_error,
+            ev_percent: to_percent(ev_per_hand),
+            ev_percent_ci95: to_percent(1.96 * ev_std_error),
+            ev_per_round,
+            ev_round_percent,
+            ev_round_percent_ci95,
+            elapsed_secs,
+            hands_per_sec: if elapsed_secs > 0.0 { n.div(elapsed_secs) } else { 0.0 },
+        }
+    }
+}
+
+impl <R: Rng + Clone> GamePool <R> {
+    pub fn report(&self) -> SimulationReport {
+        let mut totals = Totals::default();
+        for (winner, state) in self.results.iter() {
+            totals.add(winner, state);
+        }
+        totals.rounds = self.rounds.clone();
+        totals.report(SettingsSummary::from(&**self.settings), self.simulated_games, self.limit_stats.clone(), self.elapsed)
+    }
+
+    pub fn write_json(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        self.report().write_json(path)
+    }
+
+    pub fn append_csv(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        self.report().append_csv(path)
+    }
+}
+
+impl SimulationReport {
+    pub fn write_json(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let json = serde_json::to_string_pretty(self).map_err(invalid_data)?;
+        fs::write(path, json)
+    }
+
+    pub fn load_json(path: impl AsRef<Path>) -> io::Result<Self> {
+        let json = fs::read_to_string(path)?;
+        serde_json::from_str(&json).map_err(invalid_data)
+    }
+
+    // Column names, nested fields joined w/ '.' (e.g. settings.deck_count)
+    pub fn csv_header(&self) -> Vec<String> {
+        self.flatten().into_iter().map(|(key, _)| key).collect()
+    }
+
+    pub fn csv_row(&self) -> Vec<String> {
+        self.flatten().into_iter().map(|(_, value)| match value {
+            Value::String(text) => text,
+            Value::Null => String::new(),
+            other => other.to_string(),
+        }).collect()
+    }
+
+    // One row per report, header written when the file is new
+    // Files w/ other columns (e.g. from an older version) are refused, not mixed
+    pub fn append_csv(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let path = path.as_ref();
+        let header = self.csv_header();
+        let existing = match fs::read_to_string(path) {
+            Ok(text) => text.lines().find(|line| !line.is_empty()).map(parse_csv_line),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => None,
+            Err(err) => return Err(err),
+        };
+        if let Some(existing) = &existing {
+            if existing != &header {
+                let missing = header.iter().filter(|key| !existing.contains(key)).cloned().collect::<Vec<_>>();
+                let extra = existing.iter().filter(|key| !header.contains(key)).cloned().collect::<Vec<_>>();
+                return Err(invalid_data(format!("{} has other columns than this report (missing [{}], extra [{}]), append to a new file",
+                    path.display(), missing.join(", "), extra.join(", "))));
+            }
+        }
+
+        let mut file = fs::OpenOptions::new().create(true).append(true).open(path)?;
+        if existing.is_none() {
+            writeln!(file, "{}", to_csv_line(&header))?;
+        }
+        writeln!(file, "{}", to_csv_line(&self.csv_row()))
+    }
+
+    // Every row of a CSV written by `append_csv`
+    pub fn load_csv(path: impl AsRef<Path>) -> io::Result<Vec<Self>> {
+        let text = fs::read_to_string(path)?;
+        let mut lines = text.lines().filter(|line| !line.is_empty());
+        let header = match lines.next() {
+            Some(line) => parse_csv_line(line),
+            None => return Ok(vec![]),
+        };
+
+        lines.map(|line| {
+            let cells = parse_csv_line(line);
+            if cells.len() != header.len() {
+                return Err(invalid_data(format!("expected {} columns, found {}", header.len(), cells.len())));
+            }
+
+            // Rebuild nested JSON from the flat columns
+            let mut root = Map::new();
+            for (key, cell) in header.iter().zip(cells) {
+                insert_path(&mut root, key, cell_value(key, cell));
+            }
+            serde_json::from_value(Value::Object(root)).map_err(invalid_data)
+        }).collect()
+    }
+
+    fn flatten(&self) -> Vec<(String, Value)> {
+        let mut columns = vec![];
+        // Serializing plain structs can't fail
+        let value = serde_json::to_value(self).expect("Report serializable");
+        flatten_value("", value, &mut columns);
+        columns
+    }
+}
+
+// |-------------------------|
+// |       CSV HELPERS       |
+// |-------------------------|
+
+fn invalid_data(err: impl ToString) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, err.to_string())
+}
+
+// Strategy specs kept whole as JSON, their params would change the columns per strategy
+fn flatten_value(prefix: &str, value: Value, columns: &mut Vec<(String, Value)>) {
+    match value {
+        Value::Object(map) if !prefix.ends_with("_spec") => {
+            for (key, value) in map {
+                let key = if prefix.is_empty() { key } else { format!("{}.{}", prefix, key) };
+                flatten_value(&key, value, columns);
+            }
+        }
+        other => columns.push((prefix.to_string(), other)),
+    }
+}
+
+fn insert_path(root: &mut Map<String, Value>, key: &str, value: Value) {
+    match key.split_once('.') {
+        Some((head, rest)) => {
+            let child = root.entry(head).or_insert_with(|| Value::Object(Map::new()));
+            if let Value::Object(map) = child {
+                insert_path(map, rest, value);
+            }
+        }
+        None => {
+            root.insert(key.to_string(), value);
+        }
+    }
+}
+
+// Strategy names & enum variants stay text, everything else parsed as JSON
+fn cell_value(key: &str, cell: String) -> Value {
+    let text_column = key.ends_with("_strat") || key.ends_with("limit_policy");
+    if text_column {
+        return Value::String(cell);
+    }
+    if cell.is_empty() {
+        return Value::Null;
+    }
+    serde_json::from_str(&cell).unwrap_or(Value::String(cell))
+}
+
+pub(crate) fn to_csv_line(cells: &[String]) -> String {
+    cells.iter().map(|cell| {
+        if cell.contains([',', '"', '\n']) {
+            format!("\"{}\"", cell.replace('"', "\"\""))
+        } else {
+            cell.clone()
+        }
+    }).collect::<Vec<String>>().join(",")
+}
+
+fn parse_csv_line(line: &str) -> Vec<String> {
+    let mut cells = vec![];
+    let mut cell = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match (c, quoted) {
+            ('"', true) if chars.peek() == Some(&'"') => {
+                cell.push('"');
+                chars.next();
+            }
+            ('"', _) => quoted = !quoted,
+            (',', false) => cells.push(std::mem::take(&mut cell)),
+            _ => cell.push(c),
+        }
+    }
+    cells.push(cell);
+    cells
+}
@@ -1,7 +1,25 @@
-use crate::{deck::{Hand, Rank}, playing_strategy::PlayerDecision, EndState, Game, HandState, Winner};
+use crate::{deck::{Hand, Rank}, error::GameError, playing_strategy::PlayerDecision, observer::Seat, BetRecord, BlackjackPayout, EndState, Game, HandState, Round, Winner};
 use rand::Rng;
 
 impl<R: Rng + Clone> Game<R> {
+    // Bet, deal, play & settle one round w/ table limits checked
+    // Ruin only ends a bankroll limited game, others let the bankroll run negative
+    pub fn play_round(&mut self) -> Result<Round, GameError> {
+        let hand_bet = self.player.decide_bet(&self.view(None))?;
+        let hand_bet = match self.place_bet(hand_bet) {
+            Some(bet) => bet,
+            None => return Ok(Round::Rejected),
+        };
+        if self.bankroll_limited && !self.can_cover(hand_bet) {
+            return Ok(Round::Ruined);
+        }
+
+        self.deal(hand_bet)?;
+        let hand_results = self.play_hand()?;
+        self.reset_hands();
+        Ok(Round::Played(hand_results))
+    }
+
     pub fn natural_check(&self, hand: &Hand) -> bool {
         if hand.cards.len() == 2 && !hand.split_child && hand.value() == 21 {
             return true;
@@ -9,203 +27,252 @@ impl<R: Rng + Clone> Game<R> {
         false
     }
 
-    pub fn handle_player_hand(&mut self, hand: &Hand) {
-        assert!(self.dealer.hand.is_some()); // Dealer must have hand
-        let upcard = self.get_dealer_upcard().unwrap();
+    pub fn dealer_natural(&self) -> bool {
+        self.dealer.hand.as_ref().is_some_and(|hand| hand.cards.len() == 2 && hand.value() == 21)
+    }
+
+    pub fn handle_player_hand(&mut self, index: usize) -> Result<(), GameError> {
+        let upcard = self.get_dealer_upcard().ok_or(GameError::NotDealt)?;
+
+        // Second card of a split hand comes when its turn does
+        if self.player_hand_mut(index)?.cards.len() == 1 {
+            let draw = self.draw()?;
+            self.player.hands[index].add_card(draw);
+        }
+        let hand = &self.player.hands[index].clone();
         
         if !hand.is_finished() {
             // Check for Natural on first iteration
             if self.natural_check(hand) {
-                self.player_natural(hand);
+                self.player_natural(index)?;
             }
 
             // Check for Dealer Ace if insurance
             if upcard.rank == Rank::Ace {
-                self.player.decide_insurance(self.get_state(Some(hand.clone())));
+                self.player.decide_insurance(&self.view(Some(hand)))?;
             }
 
             // Player hand response
-            let decision = self.player.decide_play(self.get_state(Some(hand.clone())));
-            if self.echo {
-                println!("\n!! New Hand !!\nBet: {}", &hand.init_bet);
-                println!("\n___PLAYER___");
-                println!("Current Hand: {}", hand);
-                println!("Value: {}", hand.value());
-                println!("Decision: {:?}", decision);
+            // Doubles, splits & surrenders the table or bankroll doesn't allow are hit instead
+            let state = self.view(Some(hand));
+            let decision = match self.player.decide_play(&state)? {
+                PlayerDecision::Double if !state.can_double(hand) || !self.can_raise(hand.init_bet) => PlayerDecision::Hit,
+                PlayerDecision::Split if !state.can_split(hand) || !self.can_raise(hand.init_bet) => PlayerDecision::Hit,
+                PlayerDecision::EarlySurrender if !state.can_surrender(hand, true) => PlayerDecision::Hit,
+                PlayerDecision::LateSurrender if !state.can_surrender(hand, false) => PlayerDecision::Hit,
+                decision => decision,
+            };
+            if self.is_observed() {
+                let state = self.state_summary();
+                self.notify(|observer, _| observer.on_decision(Seat::Player, hand, &decision, &state));
             }
             match decision {
-                PlayerDecision::Stand => self.stand_player(hand),
-                PlayerDecision::Hit => self.hit_player(hand),
-                PlayerDecision::Split => self.split_hand(hand),
-                PlayerDecision::Double => self.double_hand(hand),
-                PlayerDecision::EarlySurrender => self.surrender(hand, HandState::EarlySurrender),
-                PlayerDecision::LateSurrender => self.surrender(hand, HandState::LateSurrender)
+                PlayerDecision::Stand => self.stand_player(index)?,
+                PlayerDecision::Hit => self.hit_player(index)?,
+                PlayerDecision::Split => self.split_hand(index)?,
+                PlayerDecision::Double => self.double_hand(index)?,
+                PlayerDecision::EarlySurrender => self.surrender(index, HandState::EarlySurrender)?,
+                PlayerDecision::LateSurrender => self.surrender(index, HandState::LateSurrender)?,
             }
         }
+        Ok(())
     }
 
-    pub fn play_hand(&mut self) -> Vec<(Winner, EndState)> {
-        // Set Game Bet
-        assert!(self.dealer.hand.is_some());
+    pub fn play_hand(&mut self) -> Result<Vec<(Winner, EndState)>, GameError> {
         // Dealer always has one hand and the initial bet if variable due to player
-        self.last_bet = self.dealer.hand.as_ref().expect("Handled").init_bet;
+        self.last_bet = self.dealer.hand.as_ref().ok_or(GameError::NotDealt)?.init_bet;
 
-        // Player: Always First
-        // Play until player hand complete
-        loop {
-            // End Condition
-            if self.player.is_finished() {
-                break;
+        // Dealer checks the hole card first, a natural ends the round before the player acts
+        // Only early surrender & player naturals still count
+        if self.rules.dealer_peeks && self.dealer_natural() {
+            if let Some(dealer_hand) = self.dealer.hand.as_mut() {
+                dealer_hand.natural = true;
             }
-
-            // Player may have multiple hands if split
-            let hands = self.player.hands.clone();
-            for hand in hands.iter() {
-                self.handle_player_hand(hand)
+            for index in 0..self.player.hands.len() {
+                if self.natural_check(&self.player.hands[index]) {
+                    self.player_natural(index)?;
+                }
+                else if self.allow_early_surrender
+                && self.player.decide_play(&self.view(Some(&self.player.hands[index])))? == PlayerDecision::EarlySurrender {
+                    self.surrender(index, HandState::EarlySurrender)?;
+                    continue;
+                }
+                self.stand_player(index)?;
             }
         }
 
-        // Dealer Play
-        if self.echo {
-            println!("\n ___DEALER___");
-            println!("Upcard: {:?}", self.get_dealer_upcard());
-            println!("Cutoff: {}", self.dealer.cutoff);
-        }
-        loop {
-            if self.dealer.is_finished() {
-                break;
+        // Player: Always First
+        // Hands played left to right, each to completion before the next
+        // Split hands are inserted in place, right of the hand split
+        let mut index = 0;
+        while index < self.player.hands.len() {
+            while !self.player.hands[index].is_finished() {
+                self.handle_player_hand(index)?;
             }
+            index += 1;
+        }
 
-            assert!(self.dealer.hand.is_some());
-            // USE THIS HAND ONLY FOR CONDITIONS
-            // CHANGES NOT REFLECTED IN STRUCTURE
-            let dealer_hand = self.dealer.hand.clone().expect("Asserted");
+        // Dealer Play
+        while let Some(dealer_hand) = self.dealer.hand.as_mut().filter(|hand| hand.state != HandState::Finished) {
             // Check for Natural on first iteration
             if dealer_hand.cards.len() == 2
                 && !dealer_hand.split_child
                 && dealer_hand.value() == 21
             {
-                self.dealer.hand.as_mut().expect("").natural = true;
+                dealer_hand.natural = true;
             }
-
+            // USE THIS HAND ONLY FOR CONDITIONS
+            // CHANGES NOT REFLECTED IN STRUCTURE
+            let dealer_hand = dealer_hand.clone();
 
             let decision = self.dealer
-                .decide_play(self.get_state(Some(dealer_hand)));
-
-            if self.echo {
-                println!("\nCurrent Hand: {}", self.dealer.hand.as_ref().expect(""));
-                println!("Value: {}", &self.dealer.hand.as_ref().expect("").value());
-                println!("Decision: {:?}", decision);
+                .decide_play(&self.view(Some(&dealer_hand)))?;
+            if self.is_observed() {
+                let state = self.state_summary();
+                self.notify(|observer, _| observer.on_decision(Seat::Dealer, &dealer_hand, &decision, &state));
             }
 
             match decision {
-                PlayerDecision::Hit => self.hit_dealer(),
-                PlayerDecision::Stand => {
-                    self.dealer
-                        .hand
-                        .as_mut()
-                        .expect("")
-                        .set_state(HandState::Finished);
+                PlayerDecision::Hit => self.hit_dealer()?,
+                // Dealer can only hit or stand, anything else stands
+                _ => {
+                    if let Some(hand) = self.dealer.hand.as_mut() {
+                        hand.set_state(HandState::Finished);
+                    }
                 }
-                _ => {} // Dealer can only hit or stand
             }
         }
 
         // Determine winner
+        let dealer = HandSummary::from(self.dealer.hand.as_ref().ok_or(GameError::NotDealt)?);
         let hand_results: Vec<(Winner, EndState)> = self
             .player
             .hands
             .iter()
-            .map(|player_hand| {
-                // Var initialization
-                let mut winner: Option<Winner> = None;
-                
-                let mut end_state = EndState::default();
-
-                let dealer_hand = self.dealer.hand.as_ref().expect("");
+            .map(|player_hand| settle(&HandSummary::from(player_hand), &dealer, self.last_bet, self.rules.blackjack_pays))
+            .collect();
 
-                // Assign State flag
-                end_state.hand_bet = self.last_bet;
+        // Settle bets against bankroll
+        let payoff = hand_results.iter().map(|(winner, state)| state.payoff(winner)).sum::<i64>();
+        self.bankroll += payoff;
 
-                // Player Double
-                if player_hand.doubled {
-                    end_state.p_doubled = true
-                }
-                // Player Natural (Winner undetermined)
-                if player_hand.natural {
-                    end_state.p_natural = true
+        self.notify(|observer, game| {
+            if let Some(dealer) = game.dealer.hand.as_ref() {
+                for (hand, (winner, state)) in game.player.hands.iter().zip(hand_results.iter()) {
+                    observer.on_hand_settled(hand, dealer, winner, state.payoff(winner));
                 }
+            }
+            observer.on_round_end(game.bankroll);
+        });
 
-                // Player Surrender
-                // First winner assignment
-                if player_hand.is_surrendered(){
-                    match player_hand.state {
-                        HandState::EarlySurrender => end_state.p_surrender_early = true,
-                        HandState::LateSurrender => end_state.p_surrender_late = true,
-                        _ => {assert!(1==2)} // Unreached by line 131 condition
-                    }
-                    winner = Some(Winner::Dealer);
-                }
+        // Assign last winner
+        self.last_winner = determine_last_winner(&hand_results);
 
-                // Dealer Natural
-                if dealer_hand.natural {
-                    end_state.d_natural = true;
-                    // Dealer wins w/ natural if player doesn't have
-                    if !player_hand.natural
-                    && winner.is_none() {
-                        winner = Some(Winner::Dealer)
-                    }
-                }
+        // Record round, keep only what the betting progression still needs
+        self.bet_history.push(BetRecord { bet: self.last_bet, winner: self.last_winner.clone(), payoff });
+        let keep = self.player.betting_strat.history_needed(self.init_bet, &self.bet_history);
+        if self.bet_history.len() > keep {
+            self.bet_history.drain(..self.bet_history.len() - keep);
+        }
 
-                // Bust Checks
-                // Player would bust before dealer, so check first and award dealer win even if
-                // they go over 21 drawing, b/c wouldn't draw any in real life.
+        Ok(hand_results)
+    }
 
-                // Player Bust
-                if player_hand.value() > 21 {
-                    end_state.p_bust = true;
-                    if winner.is_none() {
-                        winner = Some(Winner::Dealer)
-                    }
-                }
+}
 
-                // Dealer Bust
-                if dealer_hand.value() > 21 {
-                    end_state.d_bust = true;
-                    if winner.is_none() {
-                        winner = Some(Winner::Player)
-                    }
-                }
+// What settling needs of a hand, shared w/ the fast path
+pub(crate) struct HandSummary {
+    pub state: HandState,
+    pub natural: bool,
+    pub doubled: bool,
+    pub value: u8,
+}
 
-                // Tie
-                if player_hand.value() == dealer_hand.value()
-                && winner.is_none() {
-                    winner = Some(Winner::Tie)
-                }
-                // Player Win
-                else if player_hand.value() > dealer_hand.value()
-                && winner.is_none() {
-                    winner = Some(Winner::Player);
-                }
-                // Dealer Win
-                else {
-                    if winner.is_none() {
-                        winner = Some(Winner::Dealer);
-                    }
-                }
+impl From<&Hand> for HandSummary {
+    fn from(hand: &Hand) -> Self {
+        HandSummary { state: hand.state, natural: hand.natural, doubled: hand.doubled, value: hand.value() }
+    }
+}
 
-                (winner.expect("No Winner Found"), end_state)
-            })
-            .collect();
+pub(crate) fn settle(player_hand: &HandSummary, dealer_hand: &HandSummary, hand_bet: u32, natural_pays: BlackjackPayout) -> (Winner, EndState) {
+    // Var initialization
+    let mut winner: Option<Winner> = None;
+    let mut end_state = EndState { hand_bet, natural_pays, ..EndState::default() };
 
-        // Assign last winner & return hand results
-        self.last_winner = determine_last_winner(&hand_results);
-        hand_results
+    // Player Double
+    if player_hand.doubled {
+        end_state.p_doubled = true
+    }
+    // Player Natural (Winner undetermined)
+    if player_hand.natural {
+        end_state.p_natural = true
+    }
+
+    // Player Surrender
+    // First winner assignment
+    match player_hand.state {
+        HandState::EarlySurrender => end_state.p_surrender_early = true,
+        HandState::LateSurrender => end_state.p_surrender_late = true,
+        _ => {}
+    }
+    if end_state.p_surrender_early || end_state.p_surrender_late {
+        winner = Some(Winner::Dealer);
+    }
+
+    // Dealer Natural
+    if dealer_hand.natural {
+        end_state.d_natural = true;
+        // Dealer wins w/ natural if player doesn't have
+        if !player_hand.natural
+        && winner.is_none() {
+            winner = Some(Winner::Dealer)
+        }
+    }
+
+    // Player Natural beats every dealer hand but a natural
+    if player_hand.natural && !dealer_hand.natural && winner.is_none() {
+        winner = Some(Winner::Player)
+    }
+
+    // Bust Checks
+    // Player would bust before dealer, so check first and award dealer win even if
+    // they go over 21 drawing, b/c wouldn't draw any in real life.
+
+    // Player Bust
+    if player_hand.value > 21 {
+        end_state.p_bust = true;
+        if winner.is_none() {
+            winner = Some(Winner::Dealer)
+        }
+    }
+
+    // Dealer Bust
+    if dealer_hand.value > 21 {
+        end_state.d_bust = true;
+        if winner.is_none() {
+            winner = Some(Winner::Player)
+        }
+    }
+
+    // Tie
+    if player_hand.value == dealer_hand.value
+    && winner.is_none() {
+        winner = Some(Winner::Tie)
+    }
+    // Player Win
+    else if player_hand.value > dealer_hand.value
+    && winner.is_none() {
+        winner = Some(Winner::Player);
+    }
+    // Dealer Win
+    else if winner.is_none() {
+        winner = Some(Winner::Dealer);
     }
 
+    (winner.expect("No Winner Found"), end_state)
 }
 
-fn determine_last_winner(hand_results: &Vec<(Winner, EndState)>) -> Winner {
+fn determine_last_winner(hand_results: &[(Winner, EndState)]) -> Winner {
     let mut last_winner = Winner::None;
     // Split given to participant w/ most wins, Tie if even
     match hand_results.len() {
@@ -1,123 +1,109 @@
 use std::ops::Div;
 use std::sync::Arc;
 use rand::Rng;
-use indicatif::ProgressBar;
-use crate::deck::Hand;
-use crate::{Game, GameSettings, Winner, EndState, playing_strategy};
-use std::time::Instant;
-use std::sync::atomic::{AtomicBool, Ordering};
-use ctrlc;
+use crate::{Game, ValidSettings, Winner, EndState, LimitStats, Round};
+use crate::error::GameError;
+use crate::event_log::{EventLog, GameEvent};
+use crate::observer::{GameObserver, ProgressObserver};
+use crate::report::{RoundTotals, SettingsSummary};
+use std::io;
+use std::path::Path;
+use std::time::{Duration, Instant};
 use num_format::{Locale, ToFormattedString};
 
-macro_rules! log_fn {
-    ($func:ident) => {
-        { stringify!($func) }
-    };
-}
-
 pub struct GamePool <R: Rng> {
-    pub settings: Arc<GameSettings<R>>,
+    pub settings: Arc<ValidSettings<R>>,
     pub results: Vec<(Winner, EndState)>,
     pub simulated_games: u64,
+    pub limit_stats: LimitStats,
+    pub elapsed: Duration,
+    pub observers: Vec<Box<dyn GameObserver>>, // Handed to each game & back
+    pub event_log: Option<EventLog>,           // See `log_events`
+    pub(crate) rounds: RoundTotals,            // Per round payoffs, `results` are per hand
 }
 
 impl <R:Rng + Clone> GamePool <R> {
-    pub fn new(settings: Arc<GameSettings<R>>) -> Self {
-        Self { settings, results: vec![], simulated_games: 0 }
+    // Settings already validated, see `GameSettings::validate`
+    pub fn new(settings: Arc<ValidSettings<R>>) -> Self {
+        Self { settings, results: vec![], simulated_games: 0, limit_stats: LimitStats::default(), elapsed: Duration::ZERO, observers: vec![], event_log: None, rounds: RoundTotals::default() }
+    }
+
+    pub fn add_observer(&mut self, observer: Box<dyn GameObserver>) {
+        self.observers.push(observer);
+    }
+
+    // Log every following game to `path`, one JSON event per line
+    pub fn log_events(&mut self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut log = EventLog::create(path)?;
+        log.record(GameEvent::Start { settings: Box::new(SettingsSummary::from(&**self.settings)) });
+        self.event_log = Some(log);
+        Ok(())
+    }
+
+    // Flushes & drops the event log, reports any write error
+    pub fn close_event_log(&mut self) -> io::Result<()> {
+        match self.event_log.take() {
+            Some(mut log) => log.flush(),
+            None => Ok(()),
+        }
     }
 
-    pub fn simulate(&mut self, n: u64, progress_bar: bool) {
+    // Game w/ the pool's observers & event log attached, plus a progress bar of `rounds` if wanted
+    pub(crate) fn new_game(&mut self, progress_rounds: Option<u64>) -> Result<Game<R>, GameError> {
         let mut bj = Game::from_settings(self.settings.clone());
-        
-        let bar = ProgressBar::new(n);
-        
-        for _ in 0..n {
-            self.simulated_games += 1;
-            // Player decides init bet
-            // Filler hand passed
-            let hand_bet = bj.player.decide_bet(bj.get_state(Some(Hand::new(bj.init_bet))));
-            
-
-            // Deal cards after bet decided
-            bj.deal(hand_bet);
-
-            // Append Hand Results & incriment run count
-            self.results.append(&mut bj.play_hand());
-            
-            // Empty Hands
-            bj.reset_hands();
-            
-            if progress_bar {    
-                bar.inc(1); // Progress Bar
-            }
-            
+        bj.observers.append(&mut self.observers);
+        if let Some(rounds) = progress_rounds {
+            bj.add_observer(Box::new(ProgressObserver::new(rounds)));
         }
+        if let Some(log) = self.event_log.take() {
+            bj.attach_event_log(log);
+        }
+        Ok(bj)
+    }
+
+    // Takes observers & the event log back, collects table limit stats
+    pub(crate) fn finish_game(&mut self, bj: &mut Game<R>, progress_bar: bool) {
+        self.limit_stats.merge(&bj.limit_stats);
         if progress_bar {
-            bar.finish();
+            bj.observers.pop(); // Fills the bar
         }
-        
+        // Game's own echo observer comes first
+        let own = self.settings.echo as usize;
+        self.observers = bj.observers.split_off(own.min(bj.observers.len()));
+        self.event_log = bj.event_log.take();
+    }
 
+    pub fn simulate(&mut self, n: u64, progress_bar: bool) -> Result<(), GameError> {
+        self.run(n, progress_bar)?;
         self.sum_results();
-        
+        Ok(())
     }
 
-    pub fn debug_simulate(&mut self, n: u64) {
-        let running = Arc::new(AtomicBool::new(true));
-        let r = running.clone();
-
-        ctrlc::set_handler(move || {
-            r.store(false, Ordering::SeqCst);
-        }).expect("Error setting Ctrl-C handler");
-
-    
+    // Same as `simulate` without printing results
+    // Observers & the event log handed back even if a round fails
+    pub fn run(&mut self, n: u64, progress_bar: bool) -> Result<(), GameError> {
         let start = Instant::now();
-        let mut bj = Game::from_settings(self.settings.clone());
-        let settings_time = start.elapsed();
-    
-        let bar = ProgressBar::new(n);
-        let mut deal_time_total = std::time::Duration::new(0, 0);
-        let mut play_hand_time_total = std::time::Duration::new(0, 0);
-        let mut reset_time_total = std::time::Duration::new(0, 0);
-    
-        for i in 0..n {
-            // Cntl-C Break
-            if !running.load(Ordering::SeqCst) {
-                println!("Simulation interrupted at game {}", i);
-                break;
+        let mut bj = self.new_game(progress_bar.then_some(n))?;
+        let played = self.run_rounds(&mut bj, n);
+        self.finish_game(&mut bj, progress_bar);
+        self.elapsed += start.elapsed();
+        played
+    }
+
+    fn run_rounds(&mut self, bj: &mut Game<R>, n: u64) -> Result<(), GameError> {
+        for _ in 0..n {
+            self.simulated_games += 1;
+            match bj.play_round()? {
+                Round::Played(mut results) => {
+                    self.rounds.add_results(bj.last_bet, &results);
+                    self.results.append(&mut results);
+                }
+                Round::Rejected => {} // Round sat out
+                Round::Ruined => break, // Bankroll limited games only
             }
-            // Timer Start
-            let deal_start = Instant::now();
-            bj.deal(bj.init_bet);
-            deal_time_total += deal_start.elapsed();
-    
-            let play_hand_start = Instant::now();
-            let mut res = bj.play_hand();
-            play_hand_time_total += play_hand_start.elapsed();
-    
-            self.results.append(&mut res);
-    
-            let reset_start = Instant::now();
-            bj.reset_hands();
-            reset_time_total += reset_start.elapsed();
-    
-            bar.inc(1); // Progress Bar
         }
-    
-        bar.finish();
-    
-        let sum_results_start = Instant::now();
-        self.sum_results();
-        let sum_results_time = sum_results_start.elapsed();
-    
-        let total_time = start.elapsed();
-    
-        println!("Settings clone time: {:?}", settings_time);
-        println!("Average deal time: {:?}", deal_time_total / (n as u32));
-        println!("Average play hand time: {:?}", play_hand_time_total / (n as u32));
-        println!("Average reset time: {:?}", reset_time_total / (n as u32));
-        println!("Sum results time: {:?}", sum_results_time);
-        println!("Total simulation time: {:?}", total_time);
-    } 
+        Ok(())
+    }
 
     // ---RESULT FUNCTIONS---
 
@@ -141,33 +127,7 @@ impl <R:Rng + Clone> GamePool <R> {
     }
 
     pub fn get_player_payoff(&self) -> i64 {
-        let mut total_payoff: i64 = 0;
-        self.results.iter().for_each(|(winner, state)| {
-            let mut hand_payoff: i32 = 0;
-            match winner {
-                Winner::Player => {
-                    // Player Natural && Not Dealer Natural
-                    if state.p_natural && !state.d_natural {
-                        hand_payoff += (state.hand_bet * 3).div(2) as i32 // Natural BJ pays out 3/2 of bet
-                    }
-
-                    // If player doubled
-                    else if state.p_doubled {
-                        hand_payoff += state.hand_bet as i32 * 2
-                    }
-
-                    // Insurance
-                    else if state.p_insurance {}
-
-                }
-                Winner::Dealer => {hand_payoff -= state.hand_bet as i32}
-                Winner::Tie => {}
-                Winner::None => {}
-            }
-            total_payoff += hand_payoff as i64;
-        });
-
-        total_payoff
+        self.results.iter().map(|(winner, state)| state.payoff(winner)).sum()
     }
 
     pub fn sum_player_stats(&self) {
@@ -180,9 +140,20 @@ impl <R:Rng + Clone> GamePool <R> {
         println!(" - Percent Bust|Loss: {}%", percent_bust_given_loss);
     }
 
+    pub fn sum_limit_stats(&self) {
+        let perc = |count: u64| 100_f64 * (count as f64).div(self.simulated_games as f64);
+        let stats = &self.limit_stats;
+        println!("Table Limits");
+        println!(" - Clamped to Max: {} | {}%", stats.clamped_max.to_formatted_string(&Locale::en), perc(stats.clamped_max));
+        println!(" - Clamped to Min: {} | {}%", stats.clamped_min.to_formatted_string(&Locale::en), perc(stats.clamped_min));
+        println!(" - Rounded to Increment: {} | {}%", stats.rounded.to_formatted_string(&Locale::en), perc(stats.rounded));
+        println!(" - Rejected: {} | {}%", stats.rejected.to_formatted_string(&Locale::en), perc(stats.rejected));
+    }
+
     pub fn sum_results(&self) {
         #[allow(unused_variables)] // Used for fn name
         let player_strat = self.settings.player_strat.as_ref().as_ref();
+        let betting_strat = self.settings.betting_strat.as_ref().as_ref();
 
         let player_wins: u64 = self.results.iter()
         .map(|(winner, _)| match winner { Winner::Player => {1} _ => {0} })
@@ -209,6 +180,7 @@ impl <R:Rng + Clone> GamePool <R> {
         println!("\n -- Simulation Results --\n");
         // Settings
         println!("Player Strat: {}", player_strat_str);
+        println!("Betting Strat: {}", betting_strat.to_string());
         println!("n = {}", game_count_str);
 
         // Results
@@ -216,8 +188,9 @@ impl <R:Rng + Clone> GamePool <R> {
         println!("Dealer Wins: {} | {}%", dealer_wins_str, (100_f64*(dealer_wins as f64).div(self.results.len() as f64)));
         println!("Ties: {} | {}%", num_ties_str, (100_f64*(num_ties as f64).div(self.results.len() as f64)));
         println!("Player Payoff: ${}", payoff_str);
-        println!("Player Payoff/Game: ${}", (self.settings.init_bet as f64 * self.get_player_payoff() as f64).div(self.results.len() as f64));
+        println!("Player Payoff/Game: ${}", (self.get_player_payoff() as f64).div(self.results.len() as f64));
         self.sum_player_stats();
+        self.sum_limit_stats();
     }
 }
 
@@ -0,0 +1,50 @@
+//! Card notation for stacked scenarios. Cards as `Card` parses them, `?` for any card, `|` between hands:
+//! `"AS 10H | 6D ?"` is a player's ace ten against the dealer's six & an unknown hole card.
+//!
+//! As a shoe the groups are just dealt in order, so `"8S 8H | 6D 10C | 3H 10S"` deals the
+//! player a pair of eights, the dealer a six up & ten in the hole, then three & ten for the splits.
+
+use std::fmt;
+use std::str::FromStr;
+use crate::deck::Card;
+use crate::shoe::ScriptedShoe;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Script {
+    pub groups: Vec<Vec<Option<Card>>>, // In order, `None` any card
+}
+
+impl Script {
+    // Every card in the order written, groups run together
+    pub fn cards(&self) -> Vec<Option<Card>> {
+        self.groups.iter().flatten().copied().collect()
+    }
+
+    // Deals the cards in the order written, nothing after them
+    pub fn shoe(&self) -> ScriptedShoe {
+        ScriptedShoe::new(self.cards())
+    }
+}
+
+impl fmt::Display for Script {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let groups = self.groups.iter()
+            .map(|group| group.iter().map(|card| card.map_or("?".to_string(), |card| card.code())).collect::<Vec<_>>().join(" "))
+            .collect::<Vec<_>>();
+        write!(f, "{}", groups.join(" | "))
+    }
+}
+
+impl FromStr for Script {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let groups = text.split('|').map(|group| {
+            group.split_whitespace().map(|code| match code {
+                "?" => Ok(None),
+                code => code.parse::<Card>().map(Some),
+            }).collect::<Result<Vec<_>, _>>()
+        }).collect::<Result<Vec<_>, _>>()?;
+        Ok(Script { groups })
+    }
+}
@@ -0,0 +1,155 @@
+use std::ops::Div;
+use rand::Rng;
+use num_format::{Locale, ToFormattedString};
+use crate::bankroll::percentile;
+use crate::run_many::GamePool;
+use crate::Round;
+use crate::error::GameError;
+
+// How long a player sits at the table
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SessionLength {
+    Hands(u64),
+    Hours { hours: f64, hands_per_hour: u64 },
+}
+
+impl SessionLength {
+    pub fn hands(&self) -> u64 {
+        match *self {
+            SessionLength::Hands(hands) => hands,
+            SessionLength::Hours { hours, hands_per_hour } => (hours * hands_per_hour as f64).round() as u64,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionSettings {
+    pub length: SessionLength,
+    pub bankroll: u32,          // Money brought to the table
+    pub stop_win: Option<i64>,  // Leave once up this much
+    pub stop_loss: Option<i64>, // Leave once down this much
+}
+
+// Why a session ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionEnd {
+    Completed,
+    StopWin,
+    StopLoss,
+    Ruined,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct SessionReport {
+    pub sessions: u64,
+    pub results: Vec<i64>, // Net won/lost, one entry per session
+    pub hands: Vec<u64>,   // Rounds played, one entry per session
+    pub stop_wins: u64,
+    pub stop_losses: u64,
+    pub ruined: u64,
+}
+
+impl SessionReport {
+    pub fn record(&mut self, result: i64, hands: u64, end: SessionEnd) {
+        self.sessions += 1;
+        self.results.push(result);
+        self.hands.push(hands);
+        match end {
+            SessionEnd::Completed => {}
+            SessionEnd::StopWin => self.stop_wins += 1,
+            SessionEnd::StopLoss => self.stop_losses += 1,
+            SessionEnd::Ruined => self.ruined += 1,
+        }
+    }
+
+    pub fn winning_sessions(&self) -> f64 {
+        let wins = self.results.iter().filter(|result| **result > 0).count();
+        (wins as f64).div(self.sessions as f64)
+    }
+
+    pub fn mean_result(&self) -> f64 {
+        (self.results.iter().sum::<i64>() as f64).div(self.sessions as f64)
+    }
+
+    pub fn mean_hands(&self) -> f64 {
+        (self.hands.iter().sum::<u64>() as f64).div(self.sessions as f64)
+    }
+
+    // Session result at percentile p [0-100]
+    pub fn percentile(&self, p: f64) -> i64 {
+        percentile(&self.results, p)
+    }
+
+    pub fn sum_results(&self) {
+        let perc = |count: u64| 100_f64 * (count as f64).div(self.sessions as f64);
+
+        println!("\n -- Session Results --\n");
+        println!("Sessions: {}", self.sessions.to_formatted_string(&Locale::en));
+        println!("Winning Sessions: {}%", 100_f64 * self.winning_sessions());
+        println!("Average Session Result: ${}", self.mean_result());
+        println!("Average Hands/Session: {}", self.mean_hands());
+        println!("Session Results by Percentile");
+        for p in [5.0, 25.0, 50.0, 75.0, 95.0] {
+            println!(" - P{}: ${}", p, self.percentile(p).to_formatted_string(&Locale::en));
+        }
+        println!("Stop-Win Reached: {}%", perc(self.stop_wins));
+        println!("Stop-Loss Reached: {}%", perc(self.stop_losses));
+        println!("Session Bankroll Ruined: {}%", perc(self.ruined));
+    }
+}
+
+impl <R: Rng + Clone> GamePool <R> {
+    // Plays `sessions` independent sessions, each starting from the session bankroll on a fresh shoe.
+    // A session ends at its length, a stop-win/loss threshold, or when the bet can't be covered.
+    // Rounds sat out under table limits aren't hands, doubles & splits the bankroll can't cover are hit.
+    pub fn simulate_sessions(&mut self, sessions: u64, session: &SessionSettings, progress_bar: bool) -> Result<SessionReport, GameError> {
+        let start_bankroll = session.bankroll as i64;
+        let session_hands = session.length.hands();
+        let mut report = SessionReport::default();
+
+        // Progress in rounds, a session can end early
+        let mut bj = self.new_game(progress_bar.then_some(sessions * session_hands))?;
+        bj.bankroll_limited = true;
+
+        for _ in 0..sessions {
+            bj.reshuffle();
+            bj.reset_bankroll(start_bankroll);
+            let mut hands: u64 = 0;
+            let mut end = SessionEnd::Completed;
+            let mut sat_out = false;
+
+            while hands < session_hands {
+                match bj.play_round()? {
+                    Round::Played(_) => self.simulated_games += 1,
+                    // Base bet refused as well, see `simulate_bankroll`
+                    Round::Rejected if sat_out => break,
+                    Round::Rejected => {
+                        sat_out = true;
+                        continue;
+                    }
+                    Round::Ruined => {
+                        end = SessionEnd::Ruined;
+                        break;
+                    }
+                }
+                hands += 1;
+                sat_out = false;
+
+                let net = bj.bankroll - start_bankroll;
+                if session.stop_win.is_some_and(|stop_win| net >= stop_win) {
+                    end = SessionEnd::StopWin;
+                    break;
+                }
+                if session.stop_loss.is_some_and(|stop_loss| net <= -stop_loss) {
+                    end = SessionEnd::StopLoss;
+                    break;
+                }
+            }
+
+            report.record(bj.bankroll - start_bankroll, hands, end);
+        }
+        self.finish_game(&mut bj, progress_bar);
+
+        Ok(report)
+    }
+}
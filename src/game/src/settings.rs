@@ -0,0 +1,246 @@
+//! Builder for `GameSettings`. Defaults match `SimulationConfig::default`, the shoe is shuffled
+//! & cut on build and every problem `GameSettings::validate` finds comes back together.
+
+use std::marker::PhantomData;
+use std::sync::Arc;
+use rand::{Rng, SeedableRng};
+use crate::betting_strategy::ConstantBet;
+use crate::deck::MultiDeck;
+use crate::error::GameError;
+use crate::playing_strategy::{BasicStrategy, DealerPlay, HiLo, NoInsurance, StrategyFunc};
+use crate::shoe::{ShoeKind, CSM_RESERVOIR};
+use crate::shuffle::ShuffleProcedure;
+use crate::{GameSettings, LimitPolicy, TableRules, ValidSettings};
+
+#[derive(Clone)]
+pub struct GameSettingsBuilder<R> {
+    decks: u8,
+    cut_card: bool,
+    penetration: Option<f64>,
+    shoe: ShoeKind,
+    reservoir: usize,
+    shuffle: ShuffleProcedure,
+    max_splits: u8,
+    init_bet: u32,
+    bankroll: u32,
+    dealer_cutoff: u8,
+    dealer_strat: Arc<Box<dyn StrategyFunc>>,
+    player_strat: Arc<Box<dyn StrategyFunc>>,
+    betting_strat: Arc<Box<dyn StrategyFunc>>,
+    counting_strat: Arc<Box<dyn StrategyFunc>>,
+    insurance_strat: Arc<Box<dyn StrategyFunc>>,
+    allow_early_surrender: bool,
+    allow_late_surrender: bool,
+    rules: TableRules,
+    table_min: u32,
+    table_max: u32,
+    bet_increment: u32,
+    limit_policy: LimitPolicy,
+    seed: Option<u64>,
+    echo: bool,
+    rng: PhantomData<R>,
+}
+
+impl<R: Rng> GameSettings<R> {
+    // 6 decks w/ a cut card, basic strategy & a constant bet of 10
+    pub fn builder() -> GameSettingsBuilder<R> {
+        GameSettingsBuilder {
+            decks: 6,
+            cut_card: true,
+            penetration: None,
+            shoe: ShoeKind::Shuffled,
+            reservoir: CSM_RESERVOIR,
+            shuffle: ShuffleProcedure::default(),
+            max_splits: 3,
+            init_bet: 10,
+            bankroll: 10_000,
+            dealer_cutoff: 17,
+            dealer_strat: Arc::new(Box::new(DealerPlay)),
+            player_strat: Arc::new(Box::new(BasicStrategy)),
+            betting_strat: Arc::new(Box::new(ConstantBet)),
+            counting_strat: Arc::new(Box::new(HiLo)),
+            insurance_strat: Arc::new(Box::new(NoInsurance)),
+            allow_early_surrender: false,
+            allow_late_surrender: false,
+            rules: TableRules::default(),
+            table_min: 2,
+            table_max: 500,
+            bet_increment: 1,
+            limit_policy: LimitPolicy::Clamp,
+            seed: None,
+            echo: false,
+            rng: PhantomData,
+        }
+    }
+}
+
+impl<R: Rng + SeedableRng> GameSettingsBuilder<R> {
+    pub fn decks(mut self, decks: u8) -> Self {
+        self.decks = decks;
+        self
+    }
+
+    // Blank card reshuffles the shoe when drawn, both `contains_blank` flags follow it
+    // Only a hand shuffled shoe has one
+    pub fn cut_card(mut self, cut_card: bool) -> Self {
+        self.cut_card = cut_card;
+        self
+    }
+
+    // Share of the shoe dealt before the cut card, needs `cut_card`
+    pub fn penetration(mut self, penetration: f64) -> Self {
+        self.penetration = Some(penetration);
+        self
+    }
+
+    pub fn shoe(mut self, shoe: ShoeKind) -> Self {
+        self.shoe = shoe;
+        self
+    }
+
+    // Continuous shuffler only
+    pub fn reservoir(mut self, reservoir: usize) -> Self {
+        self.reservoir = reservoir;
+        self
+    }
+
+    // Previous shoe shuffled into the next, perfect if never set
+    pub fn shuffle(mut self, shuffle: ShuffleProcedure) -> Self {
+        self.shuffle = shuffle;
+        self
+    }
+
+    pub fn max_splits(mut self, max_splits: u8) -> Self {
+        self.max_splits = max_splits;
+        self
+    }
+
+    pub fn init_bet(mut self, init_bet: u32) -> Self {
+        self.init_bet = init_bet;
+        self
+    }
+
+    pub fn bankroll(mut self, bankroll: u32) -> Self {
+        self.bankroll = bankroll;
+        self
+    }
+
+    pub fn dealer_cutoff(mut self, dealer_cutoff: u8) -> Self {
+        self.dealer_cutoff = dealer_cutoff;
+        self
+    }
+
+    pub fn dealer_strat(mut self, strat: Arc<Box<dyn StrategyFunc>>) -> Self {
+        self.dealer_strat = strat;
+        self
+    }
+
+    pub fn player_strat(mut self, strat: Arc<Box<dyn StrategyFunc>>) -> Self {
+        self.player_strat = strat;
+        self
+    }
+
+    pub fn betting_strat(mut self, strat: Arc<Box<dyn StrategyFunc>>) -> Self {
+        self.betting_strat = strat;
+        self
+    }
+
+    pub fn counting_strat(mut self, strat: Arc<Box<dyn StrategyFunc>>) -> Self {
+        self.counting_strat = strat;
+        self
+    }
+
+    pub fn insurance_strat(mut self, strat: Arc<Box<dyn StrategyFunc>>) -> Self {
+        self.insurance_strat = strat;
+        self
+    }
+
+    pub fn early_surrender(mut self, allow: bool) -> Self {
+        self.allow_early_surrender = allow;
+        self
+    }
+
+    pub fn late_surrender(mut self, allow: bool) -> Self {
+        self.allow_late_surrender = allow;
+        self
+    }
+
+    pub fn rules(mut self, rules: TableRules) -> Self {
+        self.rules = rules;
+        self
+    }
+
+    pub fn table_limits(mut self, min: u32, max: u32) -> Self {
+        self.table_min = min;
+        self.table_max = max;
+        self
+    }
+
+    pub fn bet_increment(mut self, bet_increment: u32) -> Self {
+        self.bet_increment = bet_increment;
+        self
+    }
+
+    pub fn limit_policy(mut self, limit_policy: LimitPolicy) -> Self {
+        self.limit_policy = limit_policy;
+        self
+    }
+
+    // Random seed if never set, recorded in the settings either way
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    pub fn echo(mut self, echo: bool) -> Self {
+        self.echo = echo;
+        self
+    }
+
+    // Shuffled & cut shoe, settings shared as they can't change once checked
+    pub fn build(self) -> Result<Arc<ValidSettings<R>>, GameError> {
+        let mut problems = vec![];
+        if self.penetration.is_some() && !self.cut_card {
+            problems.push("penetration needs a cut card".to_string());
+        }
+
+        let cut_card = self.cut_card && self.shoe == ShoeKind::Shuffled;
+        let seed = self.seed.unwrap_or_else(|| rand::thread_rng().gen());
+        let mut rng = R::seed_from_u64(seed);
+        let mut deck = MultiDeck::new(self.decks, cut_card).with_kind(self.shoe).with_reservoir(self.reservoir).with_shuffle(self.shuffle);
+        deck.penetration = self.penetration;
+        deck.shuffle(&mut rng);
+        if cut_card && self.decks > 0 {
+            deck.insert_blank(&mut rng);
+        }
+
+        let settings = GameSettings {
+            deck,
+            contains_blank: cut_card,
+            max_splits: self.max_splits,
+            init_bet: self.init_bet,
+            bankroll: self.bankroll,
+            dealer_cutoff: self.dealer_cutoff,
+            dealer_strat: self.dealer_strat,
+            player_strat: self.player_strat,
+            betting_strat: self.betting_strat,
+            counting_strat: self.counting_strat,
+            insurance_strat: self.insurance_strat,
+            allow_early_surrender: self.allow_early_surrender,
+            allow_late_surrender: self.allow_late_surrender,
+            rules: self.rules,
+            table_min: self.table_min,
+            table_max: self.table_max,
+            bet_increment: self.bet_increment,
+            limit_policy: self.limit_policy,
+            rng,
+            seed: Some(seed),
+            echo: self.echo,
+        };
+        problems.extend(settings.problems());
+        match problems.is_empty() {
+            true => Ok(Arc::new(settings.validate()?)),
+            false => Err(GameError::InvalidSettings(problems)),
+        }
+    }
+}
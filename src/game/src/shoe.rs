@@ -0,0 +1,372 @@
+//! Where the cards come from. `Game` deals from any `Shoe`: the hand shuffled `MultiDeck`
+//! w/ its cut card, a continuous shuffling machine fed the discards after every round, or
+//! an infinite deck drawn w/ replacement. Tests stack a `ScriptedShoe` to deal exact cards.
+
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+use rand::{Rng, RngCore};
+use serde::{Deserialize, Serialize};
+use crate::betting_strategy::ConstantBet;
+use crate::deck::{Card, MultiDeck};
+use crate::error::GameError;
+use crate::playing_strategy::StrategyFunc;
+use crate::report::SimulationReport;
+use crate::run_many::GamePool;
+use crate::script::Script;
+use crate::GameSettings;
+
+pub const CSM_RESERVOIR: usize = 20; // Cards a machine holds ready to deal, returned discards go in behind them
+
+pub trait Shoe: Send + Sync {
+    // Next card, blank cut card included. None once empty
+    fn draw(&mut self, rng: &mut dyn RngCore) -> Option<Card>;
+
+    // Every card back in & shuffled, `played` in the order they came out since the last shuffle
+    fn reshuffle(&mut self, played: &[Card], rng: &mut dyn RngCore);
+
+    // Round's cards handed back once it's over
+    // True if they went back into play, counts start over
+    fn discard(&mut self, _cards: &[Card], _rng: &mut dyn RngCore) -> bool { false }
+
+    // Cards left to deal, next one last
+    fn cards(&self) -> &[Card];
+
+    // Replays only: the next cards set to a logged order
+    fn restack(&mut self, cards: Vec<Card>);
+
+    fn deck_count(&self) -> u8;
+
+    fn contains_blank(&self) -> bool { false }
+
+    fn kind(&self) -> ShoeKind;
+
+    fn box_clone(&self) -> Box<dyn Shoe>;
+}
+
+impl Clone for Box<dyn Shoe> {
+    fn clone(&self) -> Self {
+        self.box_clone()
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ShoeKind {
+    #[default]
+    Shuffled, // By hand once the cut card comes out
+    Csm,      // Continuous shuffling machine
+    Infinite, // Drawn w/ replacement
+}
+
+impl ShoeKind {
+    pub fn all() -> [ShoeKind; 3] {
+        [ShoeKind::Shuffled, ShoeKind::Csm, ShoeKind::Infinite]
+    }
+}
+
+impl fmt::Display for ShoeKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ShoeKind::Shuffled => write!(f, "Hand Shuffled"),
+            ShoeKind::Csm => write!(f, "Continuous Shuffler"),
+            ShoeKind::Infinite => write!(f, "Infinite Deck"),
+        }
+    }
+}
+
+impl MultiDeck {
+    // Shoe of the settings' kind, blank cut card only kept when hand shuffled
+    pub fn build_shoe(&self) -> Box<dyn Shoe> {
+        match self.kind {
+            ShoeKind::Shuffled => Box::new(self.clone()),
+            ShoeKind::Csm => Box::new(ContinuousShuffler::new(self)),
+            ShoeKind::Infinite => Box::new(InfiniteDeck::new(self)),
+        }
+    }
+}
+
+impl Shoe for MultiDeck {
+    fn draw(&mut self, _rng: &mut dyn RngCore) -> Option<Card> {
+        self.decks.draw()
+    }
+
+    // Discards w/ the stub on top through the shuffle procedure
+    // Fresh decks if it's perfect or cards are still out in hands
+    fn reshuffle(&mut self, played: &[Card], mut rng: &mut dyn RngCore) {
+        let mut stack = played.to_vec();
+        stack.extend(self.decks.cards.iter().filter(|card| !card.is_blank()));
+        match self.shuffle.is_perfect() || stack.len() != 52 * self.deck_count as usize {
+            true => {
+                self.decks = MultiDeck::new(self.deck_count, false).decks;
+                self.decks.shuffle_rng(&mut rng);
+            }
+            false => {
+                self.shuffle.apply(&mut stack, &mut rng);
+                self.decks.cards = stack;
+            }
+        }
+        if self.contains_blank {
+            self.insert_blank(&mut rng);
+        }
+    }
+
+    fn cards(&self) -> &[Card] {
+        &self.decks.cards
+    }
+
+    fn restack(&mut self, cards: Vec<Card>) {
+        self.decks.cards = cards;
+    }
+
+    fn deck_count(&self) -> u8 {
+        self.deck_count
+    }
+
+    fn contains_blank(&self) -> bool {
+        self.contains_blank
+    }
+
+    fn kind(&self) -> ShoeKind {
+        ShoeKind::Shuffled
+    }
+
+    fn box_clone(&self) -> Box<dyn Shoe> {
+        Box::new(self.clone())
+    }
+}
+
+// Discards go back in after every round, anywhere but the `reservoir` cards
+// already shuffled & waiting to be dealt
+#[derive(Debug, Clone)]
+pub struct ContinuousShuffler {
+    cards: Vec<Card>,
+    deck_count: u8,
+    reservoir: usize,
+}
+
+impl ContinuousShuffler {
+    pub fn new(deck: &MultiDeck) -> Self {
+        let cards = deck.decks.cards.iter().filter(|card| !card.is_blank()).copied().collect();
+        ContinuousShuffler { cards, deck_count: deck.deck_count, reservoir: deck.reservoir }
+    }
+}
+
+impl Shoe for ContinuousShuffler {
+    fn draw(&mut self, _rng: &mut dyn RngCore) -> Option<Card> {
+        self.cards.pop()
+    }
+
+    fn reshuffle(&mut self, _played: &[Card], mut rng: &mut dyn RngCore) {
+        let mut deck = MultiDeck::new(self.deck_count, false);
+        deck.shuffle(&mut rng);
+        self.cards = deck.decks.cards;
+    }
+
+    fn discard(&mut self, cards: &[Card], rng: &mut dyn RngCore) -> bool {
+        for card in cards.iter() {
+            let behind = self.cards.len().saturating_sub(self.reservoir);
+            self.cards.insert(rng.gen_range(0..=behind), *card);
+        }
+        true
+    }
+
+    fn cards(&self) -> &[Card] {
+        &self.cards
+    }
+
+    fn restack(&mut self, cards: Vec<Card>) {
+        self.cards = cards;
+    }
+
+    fn deck_count(&self) -> u8 {
+        self.deck_count
+    }
+
+    fn kind(&self) -> ShoeKind {
+        ShoeKind::Csm
+    }
+
+    fn box_clone(&self) -> Box<dyn Shoe> {
+        Box::new(self.clone())
+    }
+}
+
+// Every card equally likely on every draw, nothing for a count to track
+#[derive(Debug, Clone)]
+pub struct InfiniteDeck {
+    composition: Vec<Card>,
+    deck_count: u8,
+}
+
+impl InfiniteDeck {
+    pub fn new(deck: &MultiDeck) -> Self {
+        let composition = MultiDeck::new(deck.deck_count.min(1), false).decks.cards;
+        InfiniteDeck { composition, deck_count: deck.deck_count }
+    }
+}
+
+impl Shoe for InfiniteDeck {
+    fn draw(&mut self, rng: &mut dyn RngCore) -> Option<Card> {
+        match self.composition.is_empty() {
+            true => None,
+            false => Some(self.composition[rng.gen_range(0..self.composition.len())]),
+        }
+    }
+
+    fn reshuffle(&mut self, _played: &[Card], _rng: &mut dyn RngCore) {}
+
+    fn discard(&mut self, _cards: &[Card], _rng: &mut dyn RngCore) -> bool {
+        true
+    }
+
+    // Composition, no order to deal in
+    fn cards(&self) -> &[Card] {
+        &self.composition
+    }
+
+    fn restack(&mut self, _cards: Vec<Card>) {}
+
+    fn deck_count(&self) -> u8 {
+        self.deck_count
+    }
+
+    fn kind(&self) -> ShoeKind {
+        ShoeKind::Infinite
+    }
+
+    fn box_clone(&self) -> Box<dyn Shoe> {
+        Box::new(self.clone())
+    }
+}
+
+// Stacked for a scenario: the script dealt in order, `None` any card
+// Runs out after it unless given fresh decks to fall back on, see `Script`
+#[derive(Debug, Clone)]
+pub struct ScriptedShoe {
+    script: Vec<Option<Card>>, // Stacked on the next shuffle, once
+    cards: Vec<Card>,
+    fallback: Option<u8>,      // Decks shuffled in under the script & on every later shuffle
+}
+
+impl ScriptedShoe {
+    pub fn new(script: Vec<Option<Card>>) -> Self {
+        ScriptedShoe { script, cards: vec![], fallback: None }
+    }
+
+    pub fn with_fallback(mut self, decks: u8) -> Self {
+        self.fallback = Some(decks);
+        self
+    }
+}
+
+// Script notation, e.g. "As 10h | 6d ?"
+impl FromStr for ScriptedShoe {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        Ok(text.parse::<Script>()?.shoe())
+    }
+}
+
+impl Shoe for ScriptedShoe {
+    fn draw(&mut self, _rng: &mut dyn RngCore) -> Option<Card> {
+        self.cards.pop()
+    }
+
+    // Script on top of the fallback decks the first time, just the decks after
+    fn reshuffle(&mut self, _played: &[Card], mut rng: &mut dyn RngCore) {
+        self.cards = match self.fallback {
+            Some(decks) => {
+                let mut deck = MultiDeck::new(decks, false);
+                deck.shuffle(&mut rng);
+                deck.decks.cards
+            }
+            None => vec![],
+        };
+        // Any card: rank & suit as likely as from a fresh deck
+        let any = MultiDeck::new(1, false).decks.cards;
+        let stacked = self.script.drain(..).rev().map(|card| card.unwrap_or_else(|| any[rng.gen_range(0..any.len())])).collect::<Vec<_>>();
+        self.cards.extend(stacked);
+    }
+
+    fn cards(&self) -> &[Card] {
+        &self.cards
+    }
+
+    fn restack(&mut self, cards: Vec<Card>) {
+        self.cards = cards;
+    }
+
+    fn deck_count(&self) -> u8 {
+        self.fallback.unwrap_or(1).max(1)
+    }
+
+    // Dealt down to the last card like a hand shuffled shoe w/o a cut card
+    fn kind(&self) -> ShoeKind {
+        ShoeKind::Shuffled
+    }
+
+    fn box_clone(&self) -> Box<dyn Shoe> {
+        Box::new(self.clone())
+    }
+}
+
+// |-------------------------|
+// |     SHOE COMPARISON     |
+// |-------------------------|
+
+// Same rules & player dealt from each kind of shoe
+#[derive(Debug, Clone, Serialize)]
+pub struct ShoeComparison {
+    pub shoe: ShoeKind,
+    pub flat: SimulationReport,    // Constant bet
+    pub counter: SimulationReport, // Settings' betting strat, bets sized by the count
+}
+
+impl ShoeComparison {
+    // % of the initial bet kept from a flat bettor
+    pub fn house_edge(&self) -> f64 {
+        -self.flat.ev_percent
+    }
+
+    // % of the average initial bet the counter wins
+    pub fn counter_edge(&self) -> f64 {
+        self.counter.ev_percent
+    }
+}
+
+// `rounds` flat bet & counted under every `ShoeKind`, in `ShoeKind::all` order
+pub fn compare_shoes<R: Rng + Clone>(settings: &GameSettings<R>, rounds: u64, progress_bar: bool) -> Result<Vec<ShoeComparison>, GameError> {
+    ShoeKind::all().into_iter().map(|shoe| {
+        let run = |betting_strat: Arc<Box<dyn StrategyFunc>>| {
+            let mut shoe_settings = settings.clone();
+            shoe_settings.deck = settings.deck.clone().with_kind(shoe);
+            shoe_settings.betting_strat = betting_strat;
+
+            let mut pool = GamePool::new(Arc::new(shoe_settings.validate()?));
+            pool.run(rounds, progress_bar)?;
+            Ok::<_, GameError>(pool.report())
+        };
+        let flat = run(Arc::new(Box::new(ConstantBet)))?;
+        let counter = run(settings.betting_strat.clone())?;
+        Ok(ShoeComparison { shoe, flat, counter })
+    }).collect()
+}
+
+// Side by side edges, ± the 95% confidence half width
+pub fn print_shoe_comparison(comparisons: &[ShoeComparison]) {
+    println!("\n -- Shoe Comparison --\n");
+    if let Some(first) = comparisons.first() {
+        println!("Player Strat: {}", first.counter.settings.player_strat);
+        println!("Counter Betting: {}\n", first.counter.settings.betting_strat);
+    }
+    println!("{:<20} {:>20} {:>20}", "Shoe", "House Edge %", "Counter Edge %");
+    for comparison in comparisons {
+        println!("{:<20} {:>20} {:>20}",
+            comparison.shoe.to_string(),
+            format!("{:.3} +/- {:.3}", comparison.house_edge(), comparison.flat.ev_percent_ci95),
+            format!("{:.3} +/- {:.3}", comparison.counter_edge(), comparison.counter.ev_percent_ci95),
+        );
+    }
+}
@@ -0,0 +1,219 @@
+//! Shuffles as dealers do them, for clumping & shuffle tracking. A `ShuffleProcedure` is a list
+//! of steps applied to the previous shoe: the discards in the order they were played, the
+//! undealt stub on top. `OrderRetention` measures how much of that order survives.
+//!
+//! ```toml
+//! [shoe]
+//! shuffle = ["riffle", { strip = 5 }, "riffle", { plug = 3 }, "riffle", "cut"]
+//! ```
+
+use std::fmt;
+use std::str::FromStr;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+// Stacks run bottom to top, the last card is dealt first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ShuffleStep {
+    Riffle,     // Gilbert-Shannon-Reeds: binomial cut, cards dropped in proportion to what's left in each hand
+    Strip(u32), // Top pulled off in this many packets, packet order reversed
+    Plug(u32),  // This many chunks off the top pushed back in at random depths
+    Cut,        // Player's cut, somewhere in the middle half
+    Wash,       // Every order equally likely, nothing survives
+}
+
+impl ShuffleStep {
+    pub fn apply<T: Copy>(&self, stack: &mut Vec<T>, rng: &mut impl Rng) {
+        match self {
+            ShuffleStep::Riffle => riffle(stack, rng),
+            ShuffleStep::Strip(packets) => strip(stack, *packets as usize, rng),
+            ShuffleStep::Plug(plugs) => plug(stack, *plugs as usize, rng),
+            ShuffleStep::Cut => cut(stack, rng),
+            ShuffleStep::Wash => stack.shuffle(rng),
+        }
+    }
+}
+
+impl fmt::Display for ShuffleStep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ShuffleStep::Riffle => write!(f, "riffle"),
+            ShuffleStep::Strip(packets) => write!(f, "strip:{}", packets),
+            ShuffleStep::Plug(plugs) => write!(f, "plug:{}", plugs),
+            ShuffleStep::Cut => write!(f, "cut"),
+            ShuffleStep::Wash => write!(f, "wash"),
+        }
+    }
+}
+
+impl FromStr for ShuffleStep {
+    type Err = String;
+
+    // `riffle`, `strip:5`, `plug:3`, `cut` or `wash`
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let (name, count) = match text.trim().split_once(':') {
+            Some((name, count)) => (name, Some(count.parse::<u32>().map_err(|_| format!("'{}': expected a count after ':'", text))?)),
+            None => (text.trim(), None),
+        };
+        match (name, count) {
+            ("riffle", None) => Ok(ShuffleStep::Riffle),
+            ("strip", Some(packets)) => Ok(ShuffleStep::Strip(packets)),
+            ("plug", Some(plugs)) => Ok(ShuffleStep::Plug(plugs)),
+            ("cut", None) => Ok(ShuffleStep::Cut),
+            ("wash", None) => Ok(ShuffleStep::Wash),
+            _ => Err(format!("Unknown shuffle step '{}', expected riffle, strip:N, plug:N, cut or wash", text)),
+        }
+    }
+}
+
+// Steps in order. Empty: a perfect shuffle of fresh decks, nothing carried over
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ShuffleProcedure(pub Vec<ShuffleStep>);
+
+impl ShuffleProcedure {
+    pub fn is_perfect(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn apply<T: Copy>(&self, stack: &mut Vec<T>, rng: &mut impl Rng) {
+        for step in self.0.iter() {
+            step.apply(stack, rng);
+        }
+    }
+
+    pub fn problems(&self) -> Vec<String> {
+        self.0.iter()
+            .filter(|step| matches!(step, ShuffleStep::Strip(0)))
+            .map(|step| format!("shuffle step '{}' needs at least 1 packet", step))
+            .collect()
+    }
+}
+
+impl fmt::Display for ShuffleProcedure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.is_perfect() {
+            true => write!(f, "perfect"),
+            false => write!(f, "{}", self.0.iter().map(|step| step.to_string()).collect::<Vec<_>>().join(", ")),
+        }
+    }
+}
+
+impl FromStr for ShuffleProcedure {
+    type Err = String;
+
+    // Comma separated steps, `perfect` or nothing for none
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        match text.trim() {
+            "" | "perfect" => Ok(ShuffleProcedure::default()),
+            text => text.split(',').map(str::parse).collect::<Result<Vec<_>, _>>().map(ShuffleProcedure),
+        }
+    }
+}
+
+fn riffle<T: Copy>(stack: &mut Vec<T>, rng: &mut impl Rng) {
+    let split = (0..stack.len()).filter(|_| rng.gen_bool(0.5)).count();
+    let (mut left, mut right) = (split, stack.len() - split);
+    let mut riffled = Vec::with_capacity(stack.len());
+    // Dropped from the bottom of each hand
+    let (mut l, mut r) = (0, split);
+    while left + right > 0 {
+        match rng.gen_range(0..left + right) < left {
+            true => {
+                riffled.push(stack[l]);
+                l += 1;
+                left -= 1;
+            }
+            false => {
+                riffled.push(stack[r]);
+                r += 1;
+                right -= 1;
+            }
+        }
+    }
+    *stack = riffled;
+}
+
+fn strip<T: Copy>(stack: &mut Vec<T>, packets: usize, rng: &mut impl Rng) {
+    if packets < 2 || stack.len() < packets {
+        return;
+    }
+    let mut bounds = rand::seq::index::sample(rng, stack.len() - 1, packets - 1).into_iter().map(|at| at + 1).collect::<Vec<_>>();
+    bounds.sort_unstable();
+    bounds.insert(0, 0);
+    bounds.push(stack.len());
+    *stack = bounds.windows(2).rev().flat_map(|packet| stack[packet[0]..packet[1]].to_vec()).collect();
+}
+
+fn plug<T: Copy>(stack: &mut Vec<T>, plugs: usize, rng: &mut impl Rng) {
+    let len = stack.len();
+    for _ in 0..plugs {
+        if len < 8 {
+            return;
+        }
+        let size = rng.gen_range(1..=len / 8);
+        let chunk = stack.split_off(len - size);
+        let at = rng.gen_range(0..=stack.len());
+        stack.splice(at..at, chunk);
+    }
+}
+
+fn cut<T: Copy>(stack: &mut [T], rng: &mut impl Rng) {
+    let len = stack.len();
+    if len < 4 {
+        return;
+    }
+    stack.rotate_left(rng.gen_range(len / 4..=3 * len / 4));
+}
+
+// |-------------------------|
+// |     ORDER RETENTION     |
+// |-------------------------|
+
+// Averaged over shuffled stacks, `ShuffleStep::Wash` gives the baseline
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct OrderRetention {
+    pub cards: usize,
+    pub trials: u32,
+    pub rising_sequences: f64, // Runs of old neighbours in old order, about cards / 2 once random
+    pub pairs_kept: f64,       // Share of old neighbours still next to each other, about 1 / cards once random
+    pub displacement: f64,     // Mean distance moved as a share of the stack, about 1 / 3 once random
+}
+
+impl OrderRetention {
+    // `procedure` applied to `trials` ordered stacks of `cards`
+    pub fn measure(procedure: &ShuffleProcedure, cards: usize, trials: u32, rng: &mut impl Rng) -> Self {
+        let mut retention = OrderRetention { cards, trials, ..Default::default() };
+        for _ in 0..trials {
+            let mut stack = (0..cards).collect::<Vec<usize>>();
+            procedure.apply(&mut stack, rng);
+            let (rising, kept, moved) = order_kept(&stack);
+            retention.rising_sequences += rising as f64;
+            retention.pairs_kept += kept;
+            retention.displacement += moved;
+        }
+        let trials = trials.max(1) as f64;
+        retention.rising_sequences /= trials;
+        retention.pairs_kept /= trials;
+        retention.displacement /= trials;
+        retention
+    }
+}
+
+// Old position of the card now at each position
+fn order_kept(stack: &[usize]) -> (usize, f64, f64) {
+    let n = stack.len();
+    if n < 2 {
+        return (n, 1.0, 0.0);
+    }
+    let mut position = vec![0; n];
+    for (new, old) in stack.iter().enumerate() {
+        position[*old] = new;
+    }
+    let rising = 1 + (0..n - 1).filter(|old| position[old + 1] < position[*old]).count();
+    let kept = (0..n - 1).filter(|old| position[old + 1] == position[*old] + 1).count() as f64 / (n - 1) as f64;
+    let moved = position.iter().enumerate().map(|(old, new)| old.abs_diff(*new)).sum::<usize>() as f64 / (n * n) as f64;
+    (rising, kept, moved)
+}
@@ -0,0 +1,273 @@
+//! Grid experiments: every combination of the varied config values, simulated & reported side by side.
+//!
+//! ```toml
+//! preset = "vegas-strip"
+//! rounds = 500_000
+//! strategies = { counting = "hi-lo" }
+//!
+//! [[vary]]
+//! key = "shoe.penetration"
+//! values = [0.5, 0.65, 0.8]
+//!
+//! [[vary]]
+//! key = "strategies.betting"
+//! values = [{ name = "ramp", ramp = [[1, 1], [2, 2]] }, { name = "ramp", ramp = [[1, 1], [2, 4], [3, 8]] }]
+//! ```
+
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use indicatif::ProgressBar;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use crate::config::{ConfigError, SimulationConfig};
+use crate::error::GameError;
+use crate::registry::StrategyRegistry;
+use crate::report::{to_csv_line, SimulationReport};
+use crate::run_many::GamePool;
+use crate::ValidSettings;
+
+// One varied config key, dotted path into the config (e.g. shoe.penetration)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Axis {
+    pub key: String,
+    pub values: Vec<Value>,
+}
+
+impl Axis {
+    // `key=a,b,c`, each value JSON if it parses, text otherwise
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let (key, values) = spec.split_once('=').ok_or(format!("Expected key=value,..., found '{}'", spec))?;
+        let values = values.split(',')
+            .map(|value| serde_json::from_str(value).unwrap_or(Value::String(value.to_string())))
+            .collect();
+        Ok(Axis { key: key.trim().to_string(), values })
+    }
+}
+
+// Simulated grid point, params by axis key
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SweepRow {
+    pub params: Map<String, Value>,
+    pub report: SimulationReport,
+}
+
+pub struct Sweep {
+    pub base: Value, // Config as written, preset applied per grid point
+    pub axes: Vec<Axis>,
+}
+
+// Grid point ready to run
+struct Point<R: Rng> {
+    params: Map<String, Value>,
+    rounds: u64,
+    settings: Arc<ValidSettings<R>>,
+}
+
+impl Sweep {
+    pub fn new(base: &SimulationConfig) -> Self {
+        Sweep { base: serde_json::to_value(base).expect("Config serializable"), axes: vec![] }
+    }
+
+    // Config file w/ `[[vary]]` entries, JSON if the extension is .json, TOML otherwise
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path).map_err(|err| ConfigError::Io(format!("{}: {}", path.display(), err)))?;
+        let parse_error = |err: String| ConfigError::Parse(format!("{}: {}", path.display(), err));
+        let value: Value = match path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("json")) {
+            true => serde_json::from_str(&text).map_err(|err| parse_error(err.to_string()))?,
+            false => {
+                let table: toml::Table = toml::from_str(&text).map_err(|err| parse_error(err.to_string()))?;
+                serde_json::to_value(table).map_err(|err| parse_error(err.to_string()))?
+            }
+        };
+        Sweep::from_value(value)
+    }
+
+    pub fn from_value(mut value: Value) -> Result<Self, ConfigError> {
+        let Value::Object(config) = &mut value else {
+            return Err(ConfigError::Parse("Sweep config must be a table".to_string()));
+        };
+        let axes = match config.remove("vary") {
+            Some(axes) => serde_json::from_value(axes).map_err(|err| ConfigError::Parse(format!("vary: {}", err)))?,
+            None => vec![],
+        };
+        Ok(Sweep { base: value, axes })
+    }
+
+    pub fn vary(&mut self, axis: Axis) {
+        self.axes.push(axis);
+    }
+
+    // Same value at every grid point
+    pub fn set(&mut self, key: &str, value: Value) -> Result<(), ConfigError> {
+        set_path(&mut self.base, key, value).map_err(|err| ConfigError::Invalid(vec![err]))
+    }
+
+    pub fn point_count(&self) -> usize {
+        self.axes.iter().map(|axis| axis.values.len()).product()
+    }
+
+    // Every combination, first axis changing slowest
+    fn grid(&self) -> Vec<Map<String, Value>> {
+        let mut grid = vec![Map::new()];
+        for axis in self.axes.iter() {
+            grid = grid.into_iter().flat_map(|params| axis.values.iter().map(move |value| {
+                let mut params = params.clone();
+                params.insert(axis.key.clone(), value.clone());
+                params
+            })).collect();
+        }
+        grid
+    }
+
+    // Settings of every grid point, all problems reported together
+    // Points share the base seed so they're compared on the same cards
+    fn points<R: Rng + SeedableRng + Clone>(&self, registry: &StrategyRegistry) -> Result<Vec<Point<R>>, ConfigError> {
+        let mut base = self.base.clone();
+        if base.get("seed").is_none_or(Value::is_null) {
+            set_path(&mut base, "seed", Value::from(rand::thread_rng().gen::<u64>())).map_err(|err| ConfigError::Invalid(vec![err]))?;
+        }
+
+        let mut problems = vec![];
+        let mut add_problem = |problem: String| if !problems.contains(&problem) { problems.push(problem) };
+        for axis in self.axes.iter().filter(|axis| axis.values.is_empty()) {
+            add_problem(format!("vary {}: no values", axis.key));
+        }
+
+        let mut points = vec![];
+        for params in self.grid() {
+            let mut config = base.clone();
+            let label = params.iter().map(|(key, value)| format!("{}={}", key, cell(value))).collect::<Vec<_>>().join(", ");
+            for (key, value) in params.iter() {
+                if let Err(err) = set_path(&mut config, key, value.clone()) {
+                    add_problem(err);
+                }
+            }
+            let settings = SimulationConfig::from_value(config).and_then(|config| {
+                config.settings::<R>(registry).map(|settings| (config.rounds, settings))
+            });
+            match settings {
+                Ok((rounds, settings)) => points.push(Point { params, rounds, settings }),
+                Err(ConfigError::Invalid(errors)) => errors.into_iter().for_each(|err| add_problem(format!("[{}] {}", label, err))),
+                Err(err) => add_problem(err.to_string()), // Bad key or value, reported once for every point it breaks
+            }
+        }
+
+        match problems.is_empty() {
+            true => Ok(points),
+            false => Err(ConfigError::Invalid(problems)),
+        }
+    }
+
+    // Simulates every grid point, `jobs` at a time
+    // Nothing runs if any point is invalid
+    pub fn run<R>(&self, registry: &StrategyRegistry, jobs: usize, progress: bool) -> Result<Vec<SweepRow>, ConfigError>
+    where R: Rng + SeedableRng + Clone + Send + Sync {
+        let points = self.points::<R>(registry)?;
+        let reports = points.iter().map(|_| Mutex::new(None)).collect::<Vec<Mutex<Option<Result<SimulationReport, GameError>>>>>();
+        let next = AtomicUsize::new(0);
+        let bar = progress.then(|| ProgressBar::new(points.len() as u64));
+
+        thread::scope(|scope| {
+            for _ in 0..jobs.clamp(1, points.len().max(1)) {
+                scope.spawn(|| loop {
+                    let index = next.fetch_add(1, Ordering::Relaxed);
+                    let Some(point) = points.get(index) else { break };
+                    let mut pool = GamePool::new(point.settings.clone());
+                    let report = pool.run(point.rounds, false).map(|_| pool.report());
+                    *reports[index].lock().expect("Report slot poisoned") = Some(report);
+                    if let Some(bar) = bar.as_ref() {
+                        bar.inc(1);
+                    }
+                });
+            }
+        });
+        if let Some(bar) = bar {
+            bar.finish();
+        }
+
+        points.into_iter().zip(reports).map(|(point, report)| Ok(SweepRow {
+            params: point.params,
+            report: report.into_inner().expect("Report slot poisoned").expect("Every point run")?,
+        })).collect()
+    }
+
+    pub fn print_table(&self, rows: &[SweepRow]) {
+        let widths = self.axes.iter().map(|axis| {
+            rows.iter().map(|row| cell(&row.params[&axis.key]).len()).chain([axis.key.len()]).max().unwrap_or(0)
+        }).collect::<Vec<_>>();
+
+        println!("\n -- Sweep --\n");
+        for (axis, width) in self.axes.iter().zip(widths.iter()) {
+            print!("{:<width$} ", axis.key, width = width);
+        }
+        println!("{:>12} {:>10} {:>12} {:>10}", "Hands", "Edge %", "95% CI +/-", "EV/Hand");
+        for row in rows {
+            for (axis, width) in self.axes.iter().zip(widths.iter()) {
+                print!("{:<width$} ", cell(&row.params[&axis.key]), width = width);
+            }
+            println!("{:>12} {:>10.3} {:>12.3} {:>10.4}", row.report.hands, row.report.ev_percent, row.report.ev_percent_ci95, row.report.ev_per_hand);
+        }
+    }
+
+    // Tidy table, one row per grid point & one column per axis
+    pub fn write_csv(&self, rows: &[SweepRow], path: impl AsRef<Path>) -> io::Result<()> {
+        let mut file = io::BufWriter::new(fs::File::create(path)?);
+        let mut header = self.axes.iter().map(|axis| axis.key.clone()).collect::<Vec<_>>();
+        header.extend(["rounds", "hands", "ev_per_hand", "ev_std_error", "ev_percent", "ev_percent_ci95", "ev_percent_low", "ev_percent_high"].map(String::from));
+        writeln!(file, "{}", to_csv_line(&header))?;
+
+        for row in rows {
+            let report = &row.report;
+            let mut cells = self.axes.iter().map(|axis| cell(&row.params[&axis.key])).collect::<Vec<_>>();
+            cells.extend([
+                report.rounds.to_string(),
+                report.hands.to_string(),
+                report.ev_per_hand.to_string(),
+                report.ev_std_error.to_string(),
+                report.ev_percent.to_string(),
+                report.ev_percent_ci95.to_string(),
+                (report.ev_percent - report.ev_percent_ci95).to_string(),
+                (report.ev_percent + report.ev_percent_ci95).to_string(),
+            ]);
+            writeln!(file, "{}", to_csv_line(&cells))?;
+        }
+        file.flush()
+    }
+}
+
+// Text as is, anything else as compact JSON
+fn cell(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
+
+// Sets `shoe.penetration` style keys, missing tables created
+fn set_path(config: &mut Value, key: &str, value: Value) -> Result<(), String> {
+    let (parents, last) = match key.rsplit_once('.') {
+        Some((parents, last)) => (parents.split('.').collect::<Vec<_>>(), last),
+        None => (vec![], key),
+    };
+    let mut target = config;
+    for part in parents {
+        let Value::Object(table) = target else {
+            return Err(format!("vary {}: {} isn't a table", key, part));
+        };
+        target = table.entry(part).or_insert_with(|| Value::Object(Map::new()));
+    }
+    match target {
+        Value::Object(table) => {
+            table.insert(last.to_string(), value);
+            Ok(())
+        }
+        _ => Err(format!("vary {}: parent of {} isn't a table", key, last)),
+    }
+}
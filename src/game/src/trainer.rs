@@ -0,0 +1,347 @@
+//! Strategy drill: deal a decision, ask for the play, grade it against a reference strategy.
+//! Mistakes are priced by simulating both plays from the same spot on the same cards.
+
+use std::fmt;
+use std::sync::Arc;
+use rand::Rng;
+use crate::deck::{Card, Hand, HandState, MultiDeck};
+use crate::interactive::{choices, describe, SharedConsole};
+use crate::playing_strategy::{PlayerDecision, StratReturn, StrategyFunc};
+use crate::error::GameError;
+use crate::{Game, GameView, ValidSettings};
+
+const EV_BET: u32 = 100; // Simulated bet, fine enough for 6:5 naturals
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DealMode {
+    Random,
+    Hard, // Mostly spots players get wrong: soft hands, pairs, stiffs & doubles
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HandCategory {
+    Hard,
+    Soft,
+    Pair,
+}
+
+impl fmt::Display for HandCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            HandCategory::Hard => "Hard",
+            HandCategory::Soft => "Soft",
+            HandCategory::Pair => "Pairs",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+// Two card hand facing an upcard
+#[derive(Debug, Clone, PartialEq)]
+pub struct Spot {
+    pub hand: Hand,
+    pub upcard: Card,
+}
+
+impl Spot {
+    pub fn new(first: Card, second: Card, upcard: Card) -> Self {
+        let mut hand = Hand::new(EV_BET);
+        hand.add_card(first);
+        hand.add_card(second);
+        hand.set_state(HandState::Playing);
+        Spot { hand, upcard }
+    }
+
+    pub fn category(&self) -> HandCategory {
+        if self.hand.contains_pair() {
+            HandCategory::Pair
+        } else if self.hand.contains_soft_ace() {
+            HandCategory::Soft
+        } else {
+            HandCategory::Hard
+        }
+    }
+
+    // Share of dealt spots kept in `DealMode::Hard`
+    fn difficulty(&self) -> f64 {
+        let value = self.hand.value();
+        let upcard = self.upcard.value();
+        match self.category() {
+            HandCategory::Pair | HandCategory::Soft if value < 20 => 1.0,
+            HandCategory::Hard if (9..=11).contains(&value) => 1.0,
+            HandCategory::Hard if (12..=16).contains(&value) => if upcard >= 7 || upcard <= 3 { 1.0 } else { 0.5 },
+            _ => 0.1,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct CategoryStats {
+    pub asked: u32,
+    pub correct: u32,
+    pub ev_lost: f64, // Summed over mistakes, share of the bet
+}
+
+impl CategoryStats {
+    pub fn accuracy(&self) -> f64 {
+        100.0 * self.correct as f64 / self.asked.max(1) as f64
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TrainerStats {
+    pub hard: CategoryStats,
+    pub soft: CategoryStats,
+    pub pairs: CategoryStats,
+}
+
+impl TrainerStats {
+    pub fn category(&self, category: HandCategory) -> &CategoryStats {
+        match category {
+            HandCategory::Hard => &self.hard,
+            HandCategory::Soft => &self.soft,
+            HandCategory::Pair => &self.pairs,
+        }
+    }
+
+    fn category_mut(&mut self, category: HandCategory) -> &mut CategoryStats {
+        match category {
+            HandCategory::Hard => &mut self.hard,
+            HandCategory::Soft => &mut self.soft,
+            HandCategory::Pair => &mut self.pairs,
+        }
+    }
+
+    pub fn total(&self) -> CategoryStats {
+        [self.hard, self.soft, self.pairs].iter().fold(CategoryStats::default(), |total, stats| CategoryStats {
+            asked: total.asked + stats.asked,
+            correct: total.correct + stats.correct,
+            ev_lost: total.ev_lost + stats.ev_lost,
+        })
+    }
+}
+
+// Answer checked against the reference
+#[derive(Debug, Clone, PartialEq)]
+pub struct Grade {
+    pub answer: PlayerDecision,
+    pub reference: PlayerDecision,
+    pub answer_ev: f64, // Share of the bet, nothing simulated & 0 when correct
+    pub reference_ev: f64,
+}
+
+impl Grade {
+    pub fn correct(&self) -> bool {
+        self.answer == self.reference
+    }
+
+    // Expected loss of the answer vs the reference play, share of the bet
+    pub fn cost(&self) -> f64 {
+        self.reference_ev - self.answer_ev
+    }
+}
+
+// Given play on the spot's first decision, reference strategy after that
+struct Forced {
+    decision: PlayerDecision,
+    rest: Arc<Box<dyn StrategyFunc>>,
+}
+
+impl StrategyFunc for Forced {
+    fn get_decision(&self, state: &GameView) -> Result<StratReturn, GameError> {
+        match state.player_hand {
+            Some(hand) if hand.cards.len() == 2 && !hand.split_child => Ok(StratReturn::Play(self.decision.clone())),
+            _ => self.rest.get_decision(state),
+        }
+    }
+
+    fn to_string(&self) -> String {
+        format!("{:?} then {}", self.decision, self.rest.to_string())
+    }
+
+    fn surrenders_early(&self) -> bool {
+        self.decision == PlayerDecision::EarlySurrender || self.rest.surrenders_early()
+    }
+
+    fn surrenders_late(&self) -> bool {
+        self.decision == PlayerDecision::LateSurrender || self.rest.surrenders_late()
+    }
+}
+
+pub struct Trainer<R: Rng + Clone> {
+    game: Game<R>, // Table rules & the cards EVs are simulated on
+    reference: Arc<Box<dyn StrategyFunc>>,
+    pub mode: DealMode,
+    pub ev_trials: u32, // Rounds simulated per play when pricing a mistake
+    pub stats: TrainerStats,
+}
+
+impl<R: Rng + Clone> Trainer<R> {
+    // Settings' player strategy is the reference
+    pub fn new(settings: Arc<ValidSettings<R>>, mode: DealMode, ev_trials: u32) -> Result<Self, GameError> {
+        let reference = settings.player_strat.clone();
+        Ok(Trainer { game: Game::from_settings(settings), reference, mode, ev_trials, stats: TrainerStats::default() })
+    }
+
+    pub fn reference_name(&self) -> String {
+        self.reference.to_string()
+    }
+
+    // Random two cards & upcard, naturals skipped as there's nothing to decide
+    pub fn deal_spot(&mut self) -> Spot {
+        loop {
+            let mut deck = MultiDeck::new(1, false);
+            deck.shuffle(&mut self.game.rng);
+            let mut draw = || deck.draw().expect("Full deck");
+            let spot = Spot::new(draw(), draw(), draw());
+            if spot.hand.value() == 21 {
+                continue;
+            }
+            if self.mode == DealMode::Random || self.game.rng.gen::<f64>() < spot.difficulty() {
+                return spot;
+            }
+        }
+    }
+
+    // Table set to the spot, hole card unknown, for `Game::view` of what a strategy sees
+    fn seat(&mut self, spot: &Spot) {
+        self.game.reset_hands();
+        self.game.set_dealer_hand(Hand::from_cards(vec![spot.upcard], EV_BET, false, false, false));
+    }
+
+    // Plays the table allows here, in prompt order
+    pub fn legal_plays(&mut self, spot: &Spot) -> Vec<PlayerDecision> {
+        self.seat(spot);
+        choices(&self.game.view(Some(&spot.hand)), &spot.hand).into_iter().map(|choice| choice.decision).collect()
+    }
+
+    // Reference play, illegal doubles & splits hit like the engine does
+    // Surrender graded as whichever kind the table offers
+    pub fn reference_play(&mut self, spot: &Spot) -> Result<PlayerDecision, GameError> {
+        self.seat(spot);
+        let state = self.game.view(Some(&spot.hand));
+        let decision = match self.reference.get_decision(&state)? {
+            StratReturn::Play(decision) => decision,
+            _ => return Err(GameError::wrong_decision(self.reference.as_ref().as_ref(), "Play")),
+        };
+        Ok(match decision {
+            PlayerDecision::Double if !state.can_double(&spot.hand) => PlayerDecision::Hit,
+            PlayerDecision::Split if !state.can_split(&spot.hand) => PlayerDecision::Hit,
+            PlayerDecision::EarlySurrender | PlayerDecision::LateSurrender => choices(&state, &spot.hand).into_iter()
+                .map(|choice| choice.decision)
+                .find(|decision| matches!(decision, PlayerDecision::EarlySurrender | PlayerDecision::LateSurrender))
+                .unwrap_or(decision),
+            decision => decision,
+        })
+    }
+
+    // Mean result of `decision` then the reference, share of the bet
+    // Same shuffle for every play so differences aren't card luck
+    pub fn play_ev(&mut self, spot: &Spot, decision: PlayerDecision) -> Result<f64, GameError> {
+        let rng = self.game.rng.clone();
+        self.game.player.playing_strat = Arc::new(Box::new(Forced { decision, rest: self.reference.clone() }));
+        self.game.reshuffle();
+        let total = self.play_trials(spot);
+
+        // Reference back in the seat either way
+        self.game.rng = rng;
+        self.game.player.playing_strat = self.reference.clone();
+        Ok(total? as f64 / (self.ev_trials.max(1) as f64 * EV_BET as f64))
+    }
+
+    fn play_trials(&mut self, spot: &Spot) -> Result<i64, GameError> {
+        let mut total = 0;
+        for _ in 0..self.ev_trials {
+            // Known no natural under a peek, the player wouldn't be asked otherwise
+            let mut dealer = Hand::from_cards(vec![spot.upcard], EV_BET, false, false, false);
+            loop {
+                dealer.add_card(self.game.draw()?);
+                if !(self.game.rules.dealer_peeks && dealer.value() == 21) {
+                    break;
+                }
+                dealer.cards.pop();
+            }
+            dealer.set_state(HandState::Playing);
+            self.game.set_dealer_hand(dealer);
+            self.game.set_player_hands(vec![spot.hand.clone()]);
+
+            total += self.game.play_hand()?.iter().map(|(winner, state)| state.payoff(winner)).sum::<i64>();
+            self.game.reset_hands();
+        }
+        Ok(total)
+    }
+
+    // Checks the answer & records it, mistakes priced by simulation
+    pub fn grade(&mut self, spot: &Spot, answer: PlayerDecision) -> Result<Grade, GameError> {
+        let reference = self.reference_play(spot)?;
+        let (answer_ev, reference_ev) = match answer == reference {
+            true => (0.0, 0.0),
+            false => (self.play_ev(spot, answer.clone())?, self.play_ev(spot, reference.clone())?),
+        };
+        let grade = Grade { answer, reference, answer_ev, reference_ev };
+
+        let stats = self.stats.category_mut(spot.category());
+        stats.asked += 1;
+        if grade.correct() {
+            stats.correct += 1;
+        } else {
+            stats.ev_lost += grade.cost().max(0.0);
+        }
+        Ok(grade)
+    }
+
+    // Asks spots until the player quits, input ends or `rounds` are done
+    pub fn drill(&mut self, console: &SharedConsole, rounds: Option<u64>) -> Result<(), GameError> {
+        let mut console = console.lock().expect("Console poisoned");
+        console.say(&format!("Reference: {}. Type the play, q to quit", self.reference_name()));
+
+        let mut asked = 0;
+        while rounds.is_none_or(|rounds| asked < rounds) {
+            let spot = self.deal_spot();
+            self.seat(&spot);
+            let choices = choices(&self.game.view(Some(&spot.hand)), &spot.hand);
+            let prompt = choices.iter().map(|choice| choice.label).collect::<Vec<_>>().join(", ");
+
+            let (upcard, hand) = (console.cards(&spot.upcard), console.cards(&spot.hand));
+            console.say(&format!("\nDealer shows {}   Your hand {} ({})", upcard, hand, describe(&spot.hand)));
+            let answer = loop {
+                let Some(answer) = console.ask(&prompt) else { break None };
+                if answer == "q" || answer == "quit" {
+                    break None;
+                }
+                match choices.iter().find(|choice| answer == choice.key || answer == choice.word) {
+                    Some(choice) => break Some(choice.decision.clone()),
+                    None => console.say(&format!("'{}' isn't allowed here, choose {}", answer, prompt)),
+                }
+            };
+            let Some(answer) = answer else { break };
+            asked += 1;
+
+            let grade = self.grade(&spot, answer)?;
+            if grade.correct() {
+                console.say("Correct");
+                continue;
+            }
+            let cost = 100.0 * grade.cost();
+            let verdict = match cost {
+                cost if cost.abs() < 0.5 => "a close call".to_string(),
+                cost => format!("costs about {:.1}% of the bet", cost),
+            };
+            console.say(&format!("Reference plays {:?}. {:?} {} (EV {:+.1}% vs {:+.1}%)",
+                grade.reference, grade.answer, verdict, 100.0 * grade.answer_ev, 100.0 * grade.reference_ev));
+        }
+
+        console.say(&self.stats_table());
+        Ok(())
+    }
+
+    pub fn stats_table(&self) -> String {
+        let mut table = format!("\n{:<8} {:>8} {:>10} {:>12}", "Hands", "Asked", "Correct %", "EV Lost %");
+        let rows = [HandCategory::Hard, HandCategory::Soft, HandCategory::Pair]
+            .map(|category| (category.to_string(), *self.stats.category(category)));
+        for (name, stats) in rows.into_iter().chain([("Total".to_string(), self.stats.total())]) {
+            table += &format!("\n{:<8} {:>8} {:>10.1} {:>12.1}", name, stats.asked, stats.accuracy(), 100.0 * stats.ev_lost);
+        }
+        table
+    }
+}
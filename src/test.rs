@@ -1,47 +1,77 @@
-use rand::Rng;
+use rand::prelude::*;
+use rand_chacha::ChaCha8Rng;
 use std::sync::Arc;
 
-use crate::*;
+use game::{
+    deck::{Card, Hand, MultiDeck, Rank, Suit},
+    playing_strategy::{StrategyFunc, BasicStrategy, DealerPlay, MimicDealer, HiLo, KnockOut, OmegaTwo, NoInsurance},
+    betting_strategy::{ConstantBet, Martingale, KellyCriterion},
+    run_many::GamePool,
+    EndState, Game, GameSettings, LimitPolicy, TableRules,
+};
 
 #[cfg(test)]
 mod tests {
-    use game::{playing_strategy::{PlayerDecision, update_knock_out, omega_2, StrategyFunc, CountFunc, InsuranceFunc, card_counter_insurance}, Winner, deck::HandState, betting_strategy::{BettingFunc, self}};
+    use game::{playing_strategy::PlayerDecision, Winner, LimitStats, BetRecord, deck::HandState};
+    use game::betting_strategy::{Paroli, OneThreeTwoSix, DAlembert, Fibonacci, Labouchere, OscarsGrind, BetRamp};
+    use game::bankroll::{simulate_betting_strats, compare_bankrolls};
+    use game::session::{SessionLength, SessionSettings};
+    use game::report::SimulationReport;
+    use game::event_log::{logged_settings, read_events, rounds, replay, GameEvent, StateSummary};
+    use game::observer::{GameObserver, Seat};
+    use game::registry::{StrategyError, StrategyKind, StrategyRegistry};
+    use game::config::{ConfigError, SimulationConfig, TableConfig};
+    use game::presets::presets;
+    use game::sweep::{Axis, Sweep, SweepRow};
+    use game::interactive::{play_session, Console, HumanPlayer, SessionResult};
+    use game::trainer::{DealMode, HandCategory, Spot, Trainer};
+    use game::count_drill::{card_count, true_count, CountDrill, DrillSettings};
+    use game::error::GameError;
+    use game::fast::{card_idx, idx_value, FastGame, FastHand, PlayTable};
+    use game::shoe::{compare_shoes, ContinuousShuffler, InfiniteDeck, ScriptedShoe, Shoe, ShoeKind};
+    use game::shuffle::{OrderRetention, ShuffleProcedure, ShuffleStep};
+    use game::script::Script;
+    use game::playing_strategy::{FullBasicStrategy, StratReturn};
+    use game::{BlackjackPayout, DoubleRule, GameView, Round};
+    use std::sync::Mutex;
 
     use super::*;
+
+    type Strat = Arc<Box<dyn StrategyFunc>>;
     
 
     // Test Helpers
     fn standard_game (
-        player_strat: Option<Arc<PlayingStrat>>, 
-        dealer_strategy: Option<Arc<PlayingStrat>>, 
-        betting_strat: Option<Arc<BettingFunc>>, 
-        counting_strat: Option<Arc<CountFunc>>,
-        insurance_strat: Option<Arc<InsuranceFunc>>,
+        player_strat: Option<Strat>, 
+        dealer_strategy: Option<Strat>, 
+        betting_strat: Option<Strat>, 
+        counting_strat: Option<Strat>,
+        insurance_strat: Option<Strat>,
     ) -> Game<ChaCha8Rng> {
 
-        let player_strat = match player_strat {
+        let player_strat: Strat = match player_strat {
             Some(strat) => strat,
-            None => Arc::new(PlayingStrat::Player(Box::new(basic_strategy)))
+            None => Arc::new(Box::new(BasicStrategy))
         };
 
-        let dealer_strat: Arc<PlayingStrat> = match dealer_strategy {
+        let dealer_strat: Strat = match dealer_strategy {
             Some(strat) => strat,
-            None => Arc::new(PlayingStrat::Dealer(Box::new(dealer_hard_17)))
+            None => Arc::new(Box::new(DealerPlay))
         };
 
-        let betting_strat: Arc<BettingFunc> = match betting_strat {
+        let betting_strat: Strat = match betting_strat {
             Some(strat) => strat,
-            None => Arc::new(Box::new(constant_bet))
+            None => Arc::new(Box::new(ConstantBet))
         };
 
-        let counting_strat: Arc<CountFunc> = match counting_strat {
+        let counting_strat: Strat = match counting_strat {
             Some(strat) => strat,
-            None => Arc::new(Box::new(update_hi_lo)),
+            None => Arc::new(Box::new(HiLo)),
         };
 
-        let insurance_strat: Arc<InsuranceFunc> = match insurance_strat {
+        let insurance_strat: Strat = match insurance_strat {
             Some(strat) => strat,
-            None => Arc::new(Box::new(no_insurance)),
+            None => Arc::new(Box::new(NoInsurance)),
         };
         
         let mut rng = ChaCha8Rng::seed_from_u64(2);
@@ -52,8 +82,9 @@ mod tests {
         let settings = GameSettings {
             deck,
             max_splits: 3,
-            contains_blank: true,
+            contains_blank: false,
             init_bet: 10,
+            bankroll: 1000,
             dealer_cutoff: 17,
             dealer_strat,
             player_strat,
@@ -62,13 +93,60 @@ mod tests {
             insurance_strat,
             allow_early_surrender: false,
             allow_late_surrender: false,    
+            rules: TableRules::default(),
+            table_min: 2,
+            table_max: 500,
+            bet_increment: 1,
+            limit_policy: LimitPolicy::Clamp,
             echo: false,
             rng,
+            seed: Some(2),
         };
 
-        Game::from_settings(Arc::new(settings))
+        Game::from_settings(Arc::new(settings.validate().unwrap()))
     }
     
+    // Seeded settings w/ basic strategy & constant bet, override fields as needed
+    fn standard_settings() -> GameSettings<ChaCha8Rng> {
+        let mut rng = ChaCha8Rng::seed_from_u64(2);
+        let mut deck = MultiDeck::new(6, false);
+        deck.shuffle(&mut rng);
+
+        GameSettings {
+            deck,
+            max_splits: 3,
+            contains_blank: false,
+            init_bet: 10,
+            bankroll: 1000,
+            dealer_cutoff: 17,
+            dealer_strat: Arc::new(Box::new(DealerPlay)),
+            player_strat: Arc::new(Box::new(BasicStrategy)),
+            betting_strat: Arc::new(Box::new(ConstantBet)),
+            counting_strat: Arc::new(Box::new(HiLo)),
+            insurance_strat: Arc::new(Box::new(NoInsurance)),
+            allow_early_surrender: false,
+            allow_late_surrender: false,
+            rules: TableRules::default(),
+            table_min: 2,
+            table_max: 500,
+            bet_increment: 1,
+            limit_policy: LimitPolicy::Clamp,
+            echo: false,
+            rng,
+            seed: Some(2),
+        }
+    }
+
+    // One round dealt exactly from `script`, results & net won
+    fn play_script(settings: GameSettings<ChaCha8Rng>, script: &str) -> (Vec<(Winner, EndState)>, i64) {
+        let bankroll = settings.bankroll as i64;
+        let mut game = Game::from_settings(Arc::new(settings.validate().unwrap()));
+        game.set_shoe(Box::new(script.parse::<Script>().unwrap().shoe()));
+        let Round::Played(results) = game.play_round().unwrap() else { panic!("Round not played") };
+        assert!(game.shoe().cards().is_empty(), "'{}' not dealt out", script);
+        (results, game.bankroll - bankroll)
+    }
+
     fn set_hands <R: Rng + Clone>(
         game: &mut Game<R>,
         dealer_hand: Hand,
@@ -80,6 +158,14 @@ mod tests {
         
     }
 
+    // Counting strategies read the last played card from the game state
+    fn count_cards <R: Rng + Clone>(game: &mut Game<R>, cards: &[Card]) {
+        for card in cards {
+            game.played_cards.push(*card);
+            game.update_count().unwrap();
+        }
+    }
+
     fn test_decision(dealer_hand: Hand, player_hands: Vec<Hand>, expected_decision: PlayerDecision) -> Arc<Game<ChaCha8Rng>> {
         let mut rng = ChaCha8Rng::seed_from_u64(2);
         let mut deck = MultiDeck::new(6, false);
@@ -89,31 +175,38 @@ mod tests {
         let settings = GameSettings {
             deck,
             max_splits: 3,
-            contains_blank: true,
+            contains_blank: false,
             init_bet: 10,
+            bankroll: 1000,
             dealer_cutoff: 17,
-            dealer_strat: Arc::new(PlayingStrat::Dealer(Box::new(dealer_hard_17))),
-            player_strat: Arc::new(PlayingStrat::Player(Box::new(basic_strategy))),
-            betting_strat: Arc::new(Box::new(constant_bet)),
-            counting_strat: Arc::new(Box::new(update_hi_lo)),
-            insurance_strat: Arc::new(Box::new(no_insurance)),
+            dealer_strat: Arc::new(Box::new(DealerPlay)),
+            player_strat: Arc::new(Box::new(BasicStrategy)),
+            betting_strat: Arc::new(Box::new(ConstantBet)),
+            counting_strat: Arc::new(Box::new(HiLo)),
+            insurance_strat: Arc::new(Box::new(NoInsurance)),
             allow_early_surrender: false,
             allow_late_surrender: false,
+            rules: TableRules::default(),
+            table_min: 2,
+            table_max: 500,
+            bet_increment: 1,
+            limit_policy: LimitPolicy::Clamp,
             echo: false,
             rng,
+            seed: Some(2),
         };
 
-        let mut test_game = Game::from_settings(Arc::new(settings));
+        let mut test_game = Game::from_settings(Arc::new(settings.validate().unwrap()));
 
         set_hands(&mut test_game, dealer_hand, player_hands.clone());
 
-        let state = test_game.get_state(&player_hands[0]);
+        let state = test_game.view(Some(&player_hands[0]));
 
-        let decision = test_game.player.decide_play(state);
+        let decision = test_game.player.decide_play(&state).unwrap();
 
         assert_eq!(decision, expected_decision);
 
-        test_game.play_hand();
+        test_game.play_hand().unwrap();
 
         Arc::new(test_game).clone()
     }
@@ -136,19 +229,26 @@ mod tests {
             max_splits: 3,
             contains_blank: false,
             init_bet: 10,
+            bankroll: 1000,
             dealer_cutoff: 17,
-            dealer_strat: Arc::new(PlayingStrat::Dealer(Box::new(dealer_hard_17))),
-            player_strat: Arc::new(PlayingStrat::Player(Box::new(basic_strategy))),
-            betting_strat: Arc::new(Box::new(constant_bet)),
-            counting_strat: Arc::new(Box::new(update_hi_lo)),
-            insurance_strat: Arc::new(Box::new(no_insurance)),
+            dealer_strat: Arc::new(Box::new(DealerPlay)),
+            player_strat: Arc::new(Box::new(BasicStrategy)),
+            betting_strat: Arc::new(Box::new(ConstantBet)),
+            counting_strat: Arc::new(Box::new(HiLo)),
+            insurance_strat: Arc::new(Box::new(NoInsurance)),
             allow_early_surrender: false,
             allow_late_surrender: false,
+            rules: TableRules::default(),
+            table_min: 2,
+            table_max: 500,
+            bet_increment: 1,
+            limit_policy: LimitPolicy::Clamp,
             echo: false,
             rng,
+            seed: Some(2),
         };
 
-        let bj = Game::from_settings(Arc::new(settings));
+        let bj = Game::from_settings(Arc::new(settings.validate().unwrap()));
 
         assert!(bj.played_cards.is_empty());
         assert!(bj.dealer.hand.is_none());
@@ -168,24 +268,31 @@ mod tests {
         let settings = GameSettings {
             deck,
             max_splits: 3,
-            contains_blank: true,
+            contains_blank: false,
             init_bet: 10,
+            bankroll: 1000,
             dealer_cutoff: 17,
-            dealer_strat: Arc::new(PlayingStrat::Dealer(Box::new(dealer_hard_17))),
-            player_strat: Arc::new(PlayingStrat::Player(Box::new(basic_strategy))),
-            betting_strat: Arc::new(Box::new(constant_bet)),
-            counting_strat: Arc::new(Box::new(update_hi_lo)),
-            insurance_strat: Arc::new(Box::new(no_insurance)),
+            dealer_strat: Arc::new(Box::new(DealerPlay)),
+            player_strat: Arc::new(Box::new(BasicStrategy)),
+            betting_strat: Arc::new(Box::new(ConstantBet)),
+            counting_strat: Arc::new(Box::new(HiLo)),
+            insurance_strat: Arc::new(Box::new(NoInsurance)),
             allow_early_surrender: false,
             allow_late_surrender: false,
+            rules: TableRules::default(),
+            table_min: 2,
+            table_max: 500,
+            bet_increment: 1,
+            limit_policy: LimitPolicy::Clamp,
             echo: false,
             rng,
+            seed: Some(2),
         };
 
-        let bet = settings.init_bet.clone();
-        let mut bj = Game::from_settings(Arc::new(settings));
+        let bet = settings.init_bet;
+        let mut bj = Game::from_settings(Arc::new(settings.validate().unwrap()));
 
-        bj.deal(bet);
+        bj.deal(bet).unwrap();
 
         assert!(bj.dealer.hand.is_some());
         assert!(bj.get_dealer_upcard().is_some());
@@ -201,7 +308,24 @@ mod tests {
 
 
     }
-    
+
+    #[test]
+    /// Soft aces count down to 1 one at a time, only as far as needed
+    fn test_soft_aces() {
+        let value = |codes: &[&str]| {
+            let mut hand = Hand::new(10);
+            for code in codes {
+                hand.add_card(Card::from_code(code).unwrap());
+            }
+            (hand.value(), hand.contains_soft_ace())
+        };
+        assert_eq!(value(&["AS", "AH"]), (12, true));
+        assert_eq!(value(&["AS", "AH", "9D"]), (21, true));
+        assert_eq!(value(&["AS", "AH", "AD", "8C"]), (21, true));
+        assert_eq!(value(&["AS", "5H", "AD"]), (17, true));
+        assert_eq!(value(&["AS", "AH", "10D"]), (12, false));
+    }
+
     #[test]
     fn test_blank() {
 
@@ -213,25 +337,137 @@ mod tests {
         let settings = GameSettings {
             deck,
             max_splits: 3,
-            contains_blank: true,
+            contains_blank: false,
             init_bet: 10,
+            bankroll: 1000,
             dealer_cutoff: 17,
-            dealer_strat: Arc::new(PlayingStrat::Dealer(Box::new(dealer_hard_17))),
-            player_strat: Arc::new(PlayingStrat::Player(Box::new(basic_strategy))),
-            betting_strat: Arc::new(Box::new(constant_bet)),
-            counting_strat: Arc::new(Box::new(update_hi_lo)),
-            insurance_strat: Arc::new(Box::new(no_insurance)),
+            dealer_strat: Arc::new(Box::new(DealerPlay)),
+            player_strat: Arc::new(Box::new(BasicStrategy)),
+            betting_strat: Arc::new(Box::new(ConstantBet)),
+            counting_strat: Arc::new(Box::new(HiLo)),
+            insurance_strat: Arc::new(Box::new(NoInsurance)),
             allow_early_surrender: false,
             allow_late_surrender: false,
+            rules: TableRules::default(),
+            table_min: 2,
+            table_max: 500,
+            bet_increment: 1,
+            limit_policy: LimitPolicy::Clamp,
             echo: false,
             rng,
+            seed: Some(2),
         };
 
-        let mut test_pool = GamePool::new(Arc::new(settings));
+        let mut test_pool = GamePool::new(Arc::new(settings.validate().unwrap()));
+
+        test_pool.simulate(100, false).unwrap();
+
 
-        test_pool.simulate(100);
+    }
 
+    #[test]
+    /// Random cut card stays inside a single deck, at most half way in
+    fn test_cut_card_small_shoe() {
+        for seed in 0..20 {
+            let mut rng = ChaCha8Rng::seed_from_u64(seed);
+            let mut deck = MultiDeck::new(1, true);
+            deck.shuffle(&mut rng);
+            deck.insert_blank(&mut rng);
+            let blank = deck.decks.cards.iter().position(|card| card.is_blank()).unwrap();
+            assert!(blank <= 26, "Cut card at {}", blank);
+        }
+    }
 
+    #[test]
+    // Errors reported, nothing panics
+    fn test_game_errors() {
+        // Impossible settings caught before a game is built, all together
+        let settings = GameSettings { deck: MultiDeck::new(0, false), dealer_cutoff: 22, table_min: 600, ..standard_settings() };
+        let Err(GameError::InvalidSettings(problems)) = settings.validate() else { panic!("Settings invalid") };
+        assert_eq!(problems.len(), 3);
+
+        // Nothing dealt yet, no such hands
+        let mut game = Game::from_settings(Arc::new(standard_settings().validate().unwrap()));
+        assert_eq!(game.play_hand().unwrap_err(), GameError::NotDealt);
+        assert_eq!(game.hit_player(5).unwrap_err(), GameError::NoSuchHand(5));
+        game.deal(10).unwrap();
+        game.set_player_hands(vec!["10H 6D".parse().unwrap()]);
+        assert_eq!(game.split_hand(0).unwrap_err(), GameError::NotAPair(0));
+
+        // Strategy in the wrong slot
+        let settings = GameSettings { player_strat: Arc::new(Box::new(HiLo)), ..standard_settings() };
+        let mut pool = GamePool::new(Arc::new(settings.validate().unwrap()));
+        let err = pool.run(10, false).unwrap_err();
+        assert_eq!(err, GameError::WrongDecision { strategy: "Hi Lo".to_string(), expected: "Play" });
+        assert!(err.to_string().contains("Hi Lo"));
+
+        // Nothing left to draw
+        let mut game = Game::from_settings(Arc::new(standard_settings().validate().unwrap()));
+        game.scripted_shoes.extend([vec![], vec![]]);
+        game.reshuffle();
+        assert_eq!(game.deal(10).unwrap_err(), GameError::EmptyShoe);
+    }
+
+    #[test]
+    // Defaults checked on build, contradictions reported together
+    fn test_settings_builder() {
+        let settings = GameSettings::<ChaCha8Rng>::builder().seed(2).build().unwrap();
+        assert_eq!((settings.deck.deck_count, settings.init_bet, settings.dealer_cutoff), (6, 10, 17));
+        assert!(settings.contains_blank && settings.deck.contains_blank);
+        assert_eq!(settings.deck.decks.cards.iter().filter(|card| card.is_blank()).count(), 1);
+        assert_eq!(settings.seed, Some(2));
+        Game::from_settings(settings);
+
+        // Cut card drives both flags
+        let settings = GameSettings::<ChaCha8Rng>::builder().cut_card(false).build().unwrap();
+        assert!(!settings.contains_blank && !settings.deck.contains_blank);
+
+        let settings = GameSettings::<ChaCha8Rng>::builder()
+            .decks(0)
+            .dealer_cutoff(22)
+            .cut_card(false)
+            .penetration(0.75)
+            .late_surrender(true)
+            .build();
+        let Err(GameError::InvalidSettings(problems)) = settings else { panic!("Settings invalid") };
+        assert_eq!(problems.len(), 4);
+        assert!(problems.iter().any(|problem| problem.contains("'Basic Strategy' never surrenders")));
+
+        // Surrender needs a strategy that takes it
+        let settings = GameSettings::<ChaCha8Rng>::builder()
+            .player_strat(Arc::new(Box::new(FullBasicStrategy)))
+            .late_surrender(true)
+            .build();
+        assert!(settings.is_ok());
+
+        // Each kind checked on its own, full basic only surrenders late
+        let settings = GameSettings::<ChaCha8Rng>::builder()
+            .player_strat(Arc::new(Box::new(FullBasicStrategy)))
+            .early_surrender(true)
+            .build();
+        let Err(GameError::InvalidSettings(problems)) = settings else { panic!("Full basic never surrenders early") };
+        assert_eq!(problems, vec!["early surrender allowed but player strategy 'Full Basic Strategy' never surrenders early".to_string()]);
+
+        // Hand built settings checked the same way
+        let settings = GameSettings { contains_blank: true, ..standard_settings() };
+        let Err(GameError::InvalidSettings(problems)) = settings.validate() else { panic!("Blank flags disagree") };
+        assert_eq!(problems, vec!["contains_blank (true) disagrees w/ the deck's (false)".to_string()]);
+
+        // Betting strategy's own unit & limits checked against init_bet & the table
+        let kelly = || Arc::new(Box::new(KellyCriterion::new(1.0, 10, 500, 5)) as Box<dyn StrategyFunc>);
+        assert!(GameSettings::<ChaCha8Rng>::builder().betting_strat(kelly()).build().is_ok());
+        let settings = GameSettings::<ChaCha8Rng>::builder()
+            .betting_strat(kelly())
+            .init_bet(12)
+            .table_limits(25, 200)
+            .bet_increment(10)
+            .build();
+        let Err(GameError::InvalidSettings(problems)) = settings else { panic!("Kelly disagrees w/ the table") };
+        assert_eq!(problems, vec![
+            "Kelly Criterion unit (5) is not a multiple of bet_increment (10)".to_string(),
+            "init_bet (12) is not a multiple of the Kelly Criterion unit (5)".to_string(),
+            "Kelly Criterion bets 10-500, outside the table's 25-200".to_string(),
+        ]);
     }
 
     #[test]
@@ -245,27 +481,34 @@ mod tests {
         let settings = GameSettings {
             deck,
             max_splits: 3,
-            contains_blank: true,
+            contains_blank: false,
             init_bet: 10,
+            bankroll: 1000,
             dealer_cutoff: 17,
-            dealer_strat: Arc::new(PlayingStrat::Dealer(Box::new(dealer_hard_17))),
-            player_strat: Arc::new(PlayingStrat::Player(Box::new(basic_strategy))),
-            betting_strat: Arc::new(Box::new(constant_bet)),
-            counting_strat: Arc::new(Box::new(update_hi_lo)),
-            insurance_strat: Arc::new(Box::new(no_insurance)),
+            dealer_strat: Arc::new(Box::new(DealerPlay)),
+            player_strat: Arc::new(Box::new(BasicStrategy)),
+            betting_strat: Arc::new(Box::new(ConstantBet)),
+            counting_strat: Arc::new(Box::new(HiLo)),
+            insurance_strat: Arc::new(Box::new(NoInsurance)),
             allow_early_surrender: false,
             allow_late_surrender: false,
+            rules: TableRules::default(),
+            table_min: 2,
+            table_max: 500,
+            bet_increment: 1,
+            limit_policy: LimitPolicy::Clamp,
             echo: false,
             rng,
+            seed: Some(2),
         };
 
-        let bet = settings.init_bet.clone();
-        let mut bj = Game::from_settings(Arc::new(settings));
+        let bet = settings.init_bet;
+        let mut bj = Game::from_settings(Arc::new(settings.validate().unwrap()));
 
-        bj.deal(bet);
+        bj.deal(bet).unwrap();
 
-        let res: Vec<(game::Winner, EndState)> = bj.play_hand();
-        assert_eq!(res.is_empty(), false);
+        let res: Vec<(game::Winner, EndState)> = bj.play_hand().unwrap();
+        assert!(!res.is_empty());
         let player_values: Vec<u8> = bj.player.hands.iter().map(|hand| hand.value()).collect();
 
         println!("\nRESULTS");
@@ -355,6 +598,59 @@ mod tests {
         test_decision(dealer_hand, player_hands, expected_decision);
     }
 
+    #[test]
+    /// Soft ace of a split pair counts 11 again in its own hand
+    fn test_split_aces() {
+        let card = |code| Card::from_code(code).unwrap();
+        let mut pair = Hand::new(10);
+        pair.add_card(card("AS"));
+        pair.add_card(card("AH"));
+        assert_eq!(pair.value(), 12);
+
+        let mut test_game = standard_game(None, None, None, None, None);
+        set_hands(&mut test_game, Hand::from_cards(vec![card("10D"), card("7C")], 10, false, false, false), vec![pair]);
+        test_game.split_hand(0).unwrap();
+        assert_eq!(test_game.player.hands.len(), 2);
+        assert!(test_game.player.hands.iter().all(|hand| hand.cards[0].value() == 11));
+    }
+
+    #[test]
+    // Split hands can be identical, actions go to the hand at the given index
+    fn test_split_identical_hands() {
+        let eights = || Hand { split_child: true, .."8H 8S".parse().unwrap() };
+        let dealer_hand = "6D 10C".parse::<Hand>().unwrap();
+
+        let mut game = Game::from_settings(Arc::new(standard_settings().validate().unwrap()));
+        set_hands(&mut game, dealer_hand.clone(), vec![eights(), eights()]);
+        game.hit_player(1).unwrap();
+        assert_eq!((game.player.hands[0].cards.len(), game.player.hands[1].cards.len()), (2, 3));
+        game.stand_player(1).unwrap();
+        assert_eq!((game.player.hands[0].state, game.player.hands[1].state), (HandState::Playing, HandState::Finished));
+
+        set_hands(&mut game, dealer_hand, vec![eights(), eights(), eights()]);
+        game.split_hand(1).unwrap();
+        assert_eq!(game.player.hands.len(), 4);
+        assert_eq!((&game.player.hands[0], &game.player.hands[3]), (&eights(), &eights()));
+        assert_eq!((game.player.hands[1].cards[0], &game.player.hands[2].cards[..]), ("8H".parse().unwrap(), &["8S".parse().unwrap()][..]));
+        assert_eq!(game.player.hands[1].cards.len(), 2);
+
+        // 8-8 -> 8-8 / 8-8, both split again, every hand 18 & the dealer busts
+        let mut game = Game::from_settings(Arc::new(standard_settings().validate().unwrap()));
+        game.set_shoe(Box::new("8S 8H | 6D 10C | 8D 10S 10H 8C 10D JC KS".parse::<ScriptedShoe>().unwrap()));
+        game.deal(10).unwrap();
+        let hand_results = game.play_hand().unwrap();
+
+        assert_eq!(game.player.hands.len(), 4);
+        assert!(game.player.hands.iter().all(|hand| hand.value() == 18 && hand.split_child));
+        // Left to right, each hand played out before the next gets its second card
+        let hands = game.player.hands.iter().map(|hand| hand.cards.clone()).collect::<Vec<_>>();
+        let expected = ["8S 10S", "8D 10H", "8H 10D", "8C JC"].map(|hand| hand.parse::<Hand>().unwrap().cards);
+        assert_eq!(hands, expected);
+        assert!(hand_results.iter().all(|(winner, _)| *winner == Winner::Player));
+        assert_eq!(game.bankroll, 1040);
+        assert_eq!(game.played_cards.len(), 11);
+    }
+
     #[test]
     fn test_double_down() {
         // Expected Behavior: Double 
@@ -396,22 +692,22 @@ mod tests {
         let expected_decision = PlayerDecision::Double;
 
         
-        let dealer_strat = Some(Arc::new(PlayingStrat::Dealer(Box::new(dealer_hard_17))));
-        let player_strat=  Some(Arc::new(PlayingStrat::Player(Box::new(basic_strategy))));
-        let betting_strat: Option<Arc<BettingFunc>> = Some(Arc::new(Box::new(constant_bet)));
+        let dealer_strat: Option<Strat> = Some(Arc::new(Box::new(DealerPlay)));
+        let player_strat: Option<Strat> = Some(Arc::new(Box::new(BasicStrategy)));
+        let betting_strat: Option<Strat> = Some(Arc::new(Box::new(ConstantBet)));
             
         // Create Game
         let mut test_game = standard_game(player_strat, dealer_strat, betting_strat, None, None);
 
         set_hands(&mut test_game, dealer_hand, player_hands.clone());
 
-        let state = test_game.get_state(&player_hands[0]);
+        let state = test_game.view(Some(&player_hands[0]));
 
-        let decision = test_game.player.decide_play(state);
+        let decision = test_game.player.decide_play(&state).unwrap();
 
         assert_eq!(decision, expected_decision);
 
-        test_game.double_hand(&player_hands[0]);
+        test_game.double_hand(0).unwrap();
 
         let expected_num_cards = 3;
         assert_eq!(test_game.player.hands[0].cards.len(), expected_num_cards);
@@ -433,23 +729,30 @@ mod tests {
         let settings = GameSettings {
             deck,
             max_splits: 3,
-            contains_blank: true,
+            contains_blank: false,
             init_bet: 10,
+            bankroll: 1000,
             dealer_cutoff: 17,
-            dealer_strat: Arc::new(PlayingStrat::Dealer(Box::new(dealer_hard_17))),
-            player_strat: Arc::new(PlayingStrat::Player(Box::new(basic_strategy))),
-            betting_strat: Arc::new(Box::new(constant_bet)),
-            counting_strat: Arc::new(Box::new(update_hi_lo)),
-            insurance_strat: Arc::new(Box::new(no_insurance)),
+            dealer_strat: Arc::new(Box::new(DealerPlay)),
+            player_strat: Arc::new(Box::new(BasicStrategy)),
+            betting_strat: Arc::new(Box::new(ConstantBet)),
+            counting_strat: Arc::new(Box::new(HiLo)),
+            insurance_strat: Arc::new(Box::new(NoInsurance)),
             allow_early_surrender: false,
             allow_late_surrender: false,
+            rules: TableRules::default(),
+            table_min: 2,
+            table_max: 500,
+            bet_increment: 1,
+            limit_policy: LimitPolicy::Clamp,
             echo: false,
             rng,
+            seed: Some(2),
         };
 
-        let mut test_pool = GamePool::new(Arc::new(settings));
+        let mut test_pool = GamePool::new(Arc::new(settings.validate().unwrap()));
 
-        test_pool.simulate(10000);
+        test_pool.simulate(10000, false).unwrap();
 
         println!("Results: {:?} ", test_pool.results)
 
@@ -465,6 +768,7 @@ mod tests {
     /// 1 - Player Only => Player
     /// 2 - Dealer Only => Dealer
     /// 3 - Both => Tie
+    /// 4 - Player natural vs a drawn dealer 21 => Player
     fn test_naturals() {
         // --- Player Natural ---
         // Ace & Six
@@ -507,26 +811,33 @@ mod tests {
         let settings = GameSettings {
             deck,
             max_splits: 3,
-            contains_blank: true,
+            contains_blank: false,
             init_bet: 10,
+            bankroll: 1000,
             dealer_cutoff: 17,
-            dealer_strat: Arc::new(PlayingStrat::Dealer(Box::new(dealer_hard_17))),
-            player_strat: Arc::new(PlayingStrat::Player(Box::new(basic_strategy))),
-            betting_strat: Arc::new(Box::new(constant_bet)),
-            counting_strat: Arc::new(Box::new(update_hi_lo)),
-            insurance_strat: Arc::new(Box::new(no_insurance)),
+            dealer_strat: Arc::new(Box::new(DealerPlay)),
+            player_strat: Arc::new(Box::new(BasicStrategy)),
+            betting_strat: Arc::new(Box::new(ConstantBet)),
+            counting_strat: Arc::new(Box::new(HiLo)),
+            insurance_strat: Arc::new(Box::new(NoInsurance)),
             allow_early_surrender: false,
             allow_late_surrender: false,
+            rules: TableRules::default(),
+            table_min: 2,
+            table_max: 500,
+            bet_increment: 1,
+            limit_policy: LimitPolicy::Clamp,
             echo: false,
             rng,
+            seed: Some(2),
         };
 
-        let mut test_game = Game::from_settings(Arc::new(settings));
+        let mut test_game = Game::from_settings(Arc::new(settings.validate().unwrap()));
 
         set_hands(&mut test_game, dealer_hand, player_hands);
 
         
-        let player_natural_outcome = test_game.play_hand();
+        let player_natural_outcome = test_game.play_hand().unwrap();
         let state = player_natural_outcome.first().unwrap().1.clone();
         let expected_winner = Winner::Player;
         let real_winner = player_natural_outcome.first().unwrap().0.clone();
@@ -570,7 +881,7 @@ mod tests {
         let player_hands = vec![Hand::from_cards(player_cards, 1, false, false, false)];
 
         set_hands(&mut test_game, dealer_hand, player_hands);
-        let player_natural_outcome = test_game.play_hand();
+        let player_natural_outcome = test_game.play_hand().unwrap();
         let state = player_natural_outcome.first().unwrap().1.clone();
         let expected_winner = Winner::Dealer;
         let real_winner = player_natural_outcome.first().unwrap().0.clone();
@@ -614,7 +925,7 @@ mod tests {
         let player_hands = vec![Hand::from_cards(player_cards, 1, false, false, false)];
 
         set_hands(&mut test_game, dealer_hand, player_hands);
-        let player_natural_outcome = test_game.play_hand();
+        let player_natural_outcome = test_game.play_hand().unwrap();
         let state = player_natural_outcome.first().unwrap().1.clone();
         let expected_winner = Winner::Tie;
         let real_winner = player_natural_outcome.first().unwrap().0.clone();
@@ -622,9 +933,142 @@ mod tests {
         assert!(state.d_natural);
         assert!(state.p_natural);
 
+        // Player Natural vs Dealer 21 drawn to
+        test_game.reset_hands();
+        let card = |code| Card::from_code(code).unwrap();
+        let dealer_hand = Hand::from_cards(vec![card("6D"), card("5C"), card("10C")], 10, false, false, false);
+        let player_hands = vec![Hand::from_cards(vec![card("AS"), card("10H")], 1, false, false, false)];
+
+        set_hands(&mut test_game, dealer_hand, player_hands);
+        let player_natural_outcome = test_game.play_hand().unwrap();
+        let (real_winner, state) = player_natural_outcome.first().unwrap().clone();
+        assert_eq!(real_winner, Winner::Player);
+        assert!(state.p_natural);
+        assert!(!state.d_natural);
+    }
+
+    #[test]
+    /// Bankroll credited/debited by each settled hand
+    /// Natural pays 3:2, loss costs the bet
+    fn test_bankroll_settlement() {
+        let mut test_game = standard_game(None, None, None, None, None);
+        let start = test_game.bankroll;
+
+        // Player natural vs dealer 17
+        let dealer_hand = Hand::from_cards(vec![Card::from_rank(Rank::Six), Card::from_rank(Rank::Ace)], 10, false, false, false);
+        let player_hands = vec![Hand::from_cards(vec![Card::from_rank(Rank::Ace), Card::from_rank(Rank::King)], 10, false, false, false)];
+        set_hands(&mut test_game, dealer_hand, player_hands);
+        let outcome = test_game.play_hand().unwrap();
+        assert_eq!(outcome[0].1.payoff(&outcome[0].0), 15);
+        assert_eq!(test_game.bankroll, start + 15);
+        test_game.reset_hands();
+
+        // Player 17 stands vs dealer 20
+        let dealer_hand = Hand::from_cards(vec![Card::from_rank(Rank::King), Card::from_rank(Rank::Queen)], 10, false, false, false);
+        let player_hands = vec![Hand::from_cards(vec![Card::from_rank(Rank::King), Card::from_rank(Rank::Seven)], 10, false, false, false)];
+        set_hands(&mut test_game, dealer_hand, player_hands);
+        let outcome = test_game.play_hand().unwrap();
+        assert_eq!(outcome[0].0, Winner::Dealer);
+        assert_eq!(test_game.bankroll, start + 5);
+
+        // Doubled & surrendered hands
+        let doubled = EndState { hand_bet: 10, p_doubled: true, ..Default::default() };
+        assert_eq!(doubled.payoff(&Winner::Player), 20);
+        assert_eq!(doubled.payoff(&Winner::Dealer), -20);
+        let surrendered = EndState { hand_bet: 10, p_surrender_late: true, ..Default::default() };
+        assert_eq!(surrendered.payoff(&Winner::Dealer), -5);
+    }
+
+    #[test]
+    /// Martingale on a short bankroll is ruined when it can't cover the next bet
+    fn test_risk_of_ruin() {
+        let mut rng = ChaCha8Rng::seed_from_u64(2);
+        let mut deck = MultiDeck::new(6, false);
+        deck.shuffle(&mut rng);
+
+        let settings = GameSettings {
+            deck,
+            max_splits: 3,
+            contains_blank: false,
+            init_bet: 10,
+            bankroll: 100,
+            dealer_cutoff: 17,
+            dealer_strat: Arc::new(Box::new(DealerPlay)),
+            player_strat: Arc::new(Box::new(BasicStrategy)),
+            betting_strat: Arc::new(Box::new(Martingale)),
+            counting_strat: Arc::new(Box::new(HiLo)),
+            insurance_strat: Arc::new(Box::new(NoInsurance)),
+            allow_early_surrender: false,
+            allow_late_surrender: false,
+            rules: TableRules::default(),
+            table_min: 2,
+            table_max: 500,
+            bet_increment: 1,
+            limit_policy: LimitPolicy::Clamp,
+            echo: false,
+            rng,
+            seed: Some(2),
+        };
+
+        let mut test_pool = GamePool::new(Arc::new(settings.validate().unwrap()));
+        let report = test_pool.simulate_bankroll(50, 5_000, false).unwrap();
+
+        assert_eq!(report.trajectories, 50);
+        assert_eq!(report.final_bankrolls.len(), 50);
+        assert!(report.ruined > 0);
+        assert_eq!(report.hands_to_ruin.len() as u64, report.ruined);
+        assert!(report.median_hands_to_ruin().is_some());
+        assert!(report.risk_of_ruin() > 0.0 && report.risk_of_ruin() <= 1.0);
+
+        // Ruin always happens before the hand limit
+        report.hands_to_ruin.iter().for_each(|hands| assert!(*hands < 5_000));
     }
 
 
+    #[test]
+    /// Rounds sat out under table limits aren't hands, a base bet the table refuses ends the run
+    fn test_bankroll_rejected_rounds() {
+        let settings = GameSettings {
+            bankroll: 1_000_000,
+            betting_strat: Arc::new(Box::new(Martingale)),
+            table_max: 40,
+            limit_policy: LimitPolicy::Reject,
+            ..standard_settings()
+        };
+        let mut test_pool = GamePool::new(Arc::new(settings.clone().validate().unwrap()));
+        let report = test_pool.simulate_bankroll(10, 200, false).unwrap();
+        assert!(report.limit_stats.rejected > 0);
+        assert_eq!(report.ruined, 0);
+        assert_eq!(test_pool.simulated_games, 10 * 200);
+
+        let mut test_pool = GamePool::new(Arc::new(GameSettings { table_min: 20, ..settings }.validate().unwrap()));
+        let report = test_pool.simulate_bankroll(3, 200, false).unwrap();
+        assert_eq!(test_pool.simulated_games, 0);
+        assert_eq!(report.final_bankrolls, vec![1_000_000; 3]);
+    }
+
+    #[test]
+    /// Bankroll runs hit doubles & splits the bankroll can't cover
+    fn test_uncovered_raises() {
+        let play = |script: &str, bankroll: u32, limited: bool| {
+            let mut game = Game::from_settings(Arc::new(GameSettings { bankroll, ..standard_settings() }.validate().unwrap()));
+            game.bankroll_limited = limited;
+            game.set_shoe(Box::new(script.parse::<Script>().unwrap().shoe()));
+            let Round::Played(results) = game.play_round().unwrap() else { panic!("Round not played") };
+            (results.len(), game.bankroll - bankroll as i64)
+        };
+
+        // 11 doubled only w/ 20 to cover it
+        let double = "6S 5H | 6D 10C | 9C | 10H";
+        assert_eq!(play(double, 15, false), (1, 20));
+        assert_eq!(play(double, 15, true), (1, 10));
+        assert_eq!(play(double, 20, true), (1, 20));
+
+        // 8s split only w/ 20 to cover both hands
+        assert_eq!(play("8S 8H | 10D 7C | 2C", 15, true), (1, 10));
+        assert_eq!(play("8S 8H | 10D 7C | 10S | 10H", 20, true), (2, 20));
+    }
+
 // |-------------------------|
 // |   Betting Strat Tests   |
 // |-------------------------|
@@ -641,20 +1085,27 @@ mod tests {
         let settings = GameSettings {
             deck,
             max_splits: 3,
-            contains_blank: true,
+            contains_blank: false,
             init_bet: 10,
+            bankroll: 1000,
             dealer_cutoff: 17,
-            dealer_strat: Arc::new(PlayingStrat::Dealer(Box::new(dealer_hard_17))),
-            player_strat: Arc::new(PlayingStrat::Player(Box::new(basic_strategy))),
-            betting_strat: Arc::new(Box::new(martingale)),
-            counting_strat: Arc::new(Box::new(update_hi_lo)),
-            insurance_strat: Arc::new(Box::new(no_insurance)),
+            dealer_strat: Arc::new(Box::new(DealerPlay)),
+            player_strat: Arc::new(Box::new(BasicStrategy)),
+            betting_strat: Arc::new(Box::new(Martingale)),
+            counting_strat: Arc::new(Box::new(HiLo)),
+            insurance_strat: Arc::new(Box::new(NoInsurance)),
             allow_early_surrender: false,
             allow_late_surrender: false,
+            rules: TableRules::default(),
+            table_min: 2,
+            table_max: 500,
+            bet_increment: 1,
+            limit_policy: LimitPolicy::Clamp,
             echo: false,
             rng,
+            seed: Some(2),
         };
-        let settings = Arc::new(settings);
+        let settings = Arc::new(settings.validate().unwrap());
         let mut test_game = Game::from_settings(settings.clone());
 
         
@@ -691,7 +1142,7 @@ mod tests {
         // First Game
         set_hands(&mut test_game, dealer_hand.clone(), player_hands.clone());
         assert_eq!(test_game.init_bet, settings.init_bet); // First game should bet init bet
-        let outcome = test_game.play_hand(); 
+        let outcome = test_game.play_hand().unwrap(); 
         assert_eq!(outcome[0].0, Winner::Dealer); // Dealer should win
         assert_eq!(test_game.last_winner, Winner::Dealer);
 
@@ -699,37 +1150,1368 @@ mod tests {
         println!("--- SECOND GAME ---");
         assert_eq!(test_game.last_bet, settings.clone().init_bet);
         
-        let state = test_game.get_state(&player_hands[0]);
-        let bet = (settings.clone().betting_strat)(state);
+        let state = test_game.view(Some(&player_hands[0]));
+        let bet = test_game.player.decide_bet(&state).unwrap();
 
         let expected_bet = settings.init_bet * 2;
         assert_eq!(bet, expected_bet);
         
     }
-    
+
+    #[test]
+    /// Ensures Kelly bets scale w/ the true count and respect limits & units
+    fn test_kelly_criterion() {
+        let kelly = KellyCriterion::new(1.0, 10, 500, 5);
+        let betting_strat: Option<Strat> = Some(Arc::new(Box::new(KellyCriterion::new(1.0, 10, 500, 5))));
+        let mut test_game = standard_game(None, None, betting_strat, None, None);
+        test_game.bankroll = 10_000;
+
+        // No advantage at TC 0 (-0.5% edge) => Minimum bet
+        test_game.true_count = 0.0;
+        let bet = test_game.player.decide_bet(&test_game.view(None)).unwrap();
+        assert_eq!(bet, 10);
+
+        // TC 3 => 1% edge: 10,000 * 0.01 / 1.33 = 75.19 => 75
+        test_game.true_count = 3.0;
+        let bet = test_game.player.decide_bet(&test_game.view(None)).unwrap();
+        assert_eq!(bet, 75);
+        assert_eq!(bet % 5, 0);
+
+        // Half Kelly bets half as much, rounded down to unit
+        let half_kelly = KellyCriterion::new(0.5, 10, 500, 5);
+        assert_eq!(half_kelly.bet_size(10_000, 3.0), 35);
+
+        // Huge count => Table max
+        assert_eq!(kelly.bet_size(10_000, 50.0), 500);
+
+        // Broke player => Minimum bet
+        assert_eq!(kelly.bet_size(0, 10.0), 10);
+
+        // Table limits win over Kelly's own, same as the table's legal bet
+        test_game.table_min = 25;
+        test_game.table_max = 200;
+        test_game.bet_increment = 25;
+        test_game.true_count = 0.0;
+        assert_eq!(test_game.player.decide_bet(&test_game.view(None)).unwrap(), 25);
+        // TC 4 => 110 by Kelly's unit, 100 by the table's increment
+        test_game.true_count = 4.0;
+        assert_eq!(test_game.player.decide_bet(&test_game.view(None)).unwrap(), 100);
+        test_game.true_count = 50.0;
+        assert_eq!(test_game.player.decide_bet(&test_game.view(None)).unwrap(), 200);
+    }
+        #[test]
+    /// Table limits clamp or reject bets and count every change
+    fn test_table_limits() {
+        let mut test_game = standard_game(None, None, None, None, None);
+        test_game.table_min = 5;
+        test_game.table_max = 100;
+        test_game.bet_increment = 5;
+
+        // Clamp
+        assert_eq!(test_game.place_bet(25), Some(25));
+        assert_eq!(test_game.place_bet(1_000), Some(100));
+        assert_eq!(test_game.place_bet(2), Some(5));
+        assert_eq!(test_game.place_bet(27), Some(25));
+        assert_eq!(test_game.limit_stats, LimitStats { clamped_min: 1, clamped_max: 1, rounded: 1, rejected: 0 });
+
+        // Reject resets the betting progression
+        test_game.limit_policy = LimitPolicy::Reject;
+        test_game.last_bet = 160;
+        test_game.last_winner = Winner::Dealer;
+        assert_eq!(test_game.place_bet(320), None);
+        assert_eq!(test_game.limit_stats.rejected, 1);
+        assert_eq!(test_game.last_winner, Winner::None);
+        assert_eq!(test_game.player.decide_bet(&test_game.view(None)).unwrap(), 10);
+
+        // No multiple of the increment fits, table max still holds
+        let settings = GameSettings::<ChaCha8Rng>::builder().table_limits(5, 7).bet_increment(10).build();
+        let Err(GameError::InvalidSettings(problems)) = settings else { panic!("No legal bet") };
+        assert_eq!(problems, vec!["no bet from table_min (5) to table_max (7) is a multiple of bet_increment (10)".to_string()]);
+        assert!(GameSettings::<ChaCha8Rng>::builder().table_limits(5, 10).bet_increment(10).build().is_ok());
+        test_game.limit_policy = LimitPolicy::Clamp;
+        (test_game.table_min, test_game.table_max, test_game.bet_increment) = (5, 7, 10);
+        assert!([1, 6, 100].iter().all(|bet| test_game.place_bet(*bet).unwrap() <= 7));
+    }
+
+    #[test]
+    /// Low table max breaks the Martingale progression
+    fn test_martingale_table_max() {
+        let mut rng = ChaCha8Rng::seed_from_u64(2);
+        let mut deck = MultiDeck::new(6, false);
+        deck.shuffle(&mut rng);
+
+        let settings = GameSettings {
+            deck,
+            max_splits: 3,
+            contains_blank: false,
+            init_bet: 10,
+            bankroll: 100_000,
+            dealer_cutoff: 17,
+            dealer_strat: Arc::new(Box::new(DealerPlay)),
+            player_strat: Arc::new(Box::new(BasicStrategy)),
+            betting_strat: Arc::new(Box::new(Martingale)),
+            counting_strat: Arc::new(Box::new(HiLo)),
+            insurance_strat: Arc::new(Box::new(NoInsurance)),
+            allow_early_surrender: false,
+            allow_late_surrender: false,
+            rules: TableRules::default(),
+            table_min: 10,
+            table_max: 40,
+            bet_increment: 1,
+            limit_policy: LimitPolicy::Clamp,
+            echo: false,
+            rng,
+            seed: Some(2),
+        };
+
+        let mut test_pool = GamePool::new(Arc::new(settings.validate().unwrap()));
+        test_pool.simulate(2_000, false).unwrap();
+
+        assert!(test_pool.limit_stats.clamped_max > 0);
+        assert_eq!(test_pool.limit_stats.rejected, 0);
+        assert!(test_pool.results.iter().all(|(_, state)| state.hand_bet <= 40));
+    }
+
+    fn bet_record(bet: u32, winner: Winner, payoff: i64) -> BetRecord {
+        BetRecord { bet, winner, payoff }
+    }
+
+    // Next bet w/ the given betting strat & round history
+    fn next_bet(betting_strat: Strat, history: Vec<BetRecord>) -> u32 {
+        let mut test_game = standard_game(None, None, Some(betting_strat), None, None);
+        if let Some(last) = history.last() {
+            test_game.last_bet = last.bet;
+            test_game.last_winner = last.winner.clone();
+        }
+        test_game.bet_history = history;
+        test_game.player.decide_bet(&test_game.view(None)).unwrap()
+    }
+
+    #[test]
+    /// Positive progressions step up on consecutive wins & reset on a loss
+    fn test_streak_progressions() {
+        let win = |bet| bet_record(bet, Winner::Player, bet as i64);
+        let loss = |bet| bet_record(bet, Winner::Dealer, -(bet as i64));
+        let tie = |bet| bet_record(bet, Winner::Tie, 0);
+
+        // Paroli: 1, 2, 4 units then bank
+        assert_eq!(next_bet(Arc::new(Box::new(Paroli)), vec![]), 10);
+        assert_eq!(next_bet(Arc::new(Box::new(Paroli)), vec![win(10)]), 20);
+        assert_eq!(next_bet(Arc::new(Box::new(Paroli)), vec![win(10), tie(20)]), 20);
+        assert_eq!(next_bet(Arc::new(Box::new(Paroli)), vec![win(10), win(20)]), 40);
+        assert_eq!(next_bet(Arc::new(Box::new(Paroli)), vec![win(10), win(20), win(40)]), 10);
+        assert_eq!(next_bet(Arc::new(Box::new(Paroli)), vec![win(10), loss(20)]), 10);
+
+        // 1-3-2-6
+        let history = [win(10), win(30), win(20), win(60)];
+        let expected = [10, 30, 20, 60, 10];
+        for (i, bet) in expected.iter().enumerate() {
+            assert_eq!(next_bet(Arc::new(Box::new(OneThreeTwoSix)), history[..i].to_vec()), *bet);
+        }
+
+        // Unit multiples saturate instead of wrapping on huge init bets
+        let saturating: [(Strat, Vec<BetRecord>); 2] = [
+            (Arc::new(Box::new(Paroli)), vec![win(10), win(20)]),
+            (Arc::new(Box::new(OneThreeTwoSix)), vec![win(10)]),
+        ];
+        for (betting_strat, history) in saturating {
+            let mut test_game = standard_game(None, None, Some(betting_strat), None, None);
+            test_game.init_bet = u32::MAX / 2;
+            test_game.bet_history = history;
+            assert_eq!(test_game.player.decide_bet(&test_game.view(None)).unwrap(), u32::MAX);
+        }
+
+        // D'Alembert
+        assert_eq!(next_bet(Arc::new(Box::new(DAlembert)), vec![loss(30)]), 40);
+        assert_eq!(next_bet(Arc::new(Box::new(DAlembert)), vec![win(30)]), 20);
+        assert_eq!(next_bet(Arc::new(Box::new(DAlembert)), vec![win(10)]), 10);
+    }
+
+    #[test]
+    /// Progressions w/ longer memory replay their line from the history
+    fn test_line_progressions() {
+        let win = |bet| bet_record(bet, Winner::Player, bet as i64);
+        let loss = |bet| bet_record(bet, Winner::Dealer, -(bet as i64));
+
+        // Fibonacci: 1, 1, 2, 3 units on losses, two steps back on a win
+        assert_eq!(next_bet(Arc::new(Box::new(Fibonacci)), vec![loss(10), loss(10), loss(20)]), 30);
+        assert_eq!(next_bet(Arc::new(Box::new(Fibonacci)), vec![loss(10), loss(10), loss(20), win(30)]), 10);
+        assert_eq!(Fibonacci.history_needed(10, &[loss(10), win(10)]), 0);
+
+        // Labouchere 1-2-3: bet first + last
+        let labouchere = || -> Strat { Arc::new(Box::new(Labouchere::default())) };
+        assert_eq!(next_bet(labouchere(), vec![]), 40);
+        assert_eq!(next_bet(labouchere(), vec![loss(40)]), 50); // 1-2-3-4
+        assert_eq!(next_bet(labouchere(), vec![loss(40), win(50)]), 50); // 2-3
+        assert_eq!(next_bet(labouchere(), vec![win(40)]), 20); // 2
+        assert_eq!(next_bet(labouchere(), vec![win(40), win(20)]), 40); // Cleared, new line
+        assert_eq!(Labouchere::default().history_needed(10, &[win(40), win(20)]), 0);
+        assert_eq!(Labouchere::default().history_needed(10, &[loss(40), win(50)]), 2);
+
+        // Oscar's Grind: +1 unit per series
+        assert_eq!(next_bet(Arc::new(Box::new(OscarsGrind)), vec![loss(10)]), 10);
+        assert_eq!(next_bet(Arc::new(Box::new(OscarsGrind)), vec![loss(10), loss(10), win(10)]), 20);
+        assert_eq!(next_bet(Arc::new(Box::new(OscarsGrind)), vec![loss(10), win(10)]), 10);
+        assert_eq!(next_bet(Arc::new(Box::new(OscarsGrind)), vec![loss(10), loss(10), win(10), win(20)]), 10);
+        assert_eq!(OscarsGrind.history_needed(10, &[loss(10), loss(10), win(10), win(20)]), 0);
+    }
+
+    #[test]
+    /// Game keeps only the history the betting strat needs
+    fn test_bet_history_trim() {
+        let betting_strat: Option<Strat> = Some(Arc::new(Box::new(Labouchere::default())));
+        let mut test_game = standard_game(None, None, betting_strat, None, None);
+        for _ in 0..200 {
+            let bet = test_game.player.decide_bet(&test_game.view(None)).unwrap();
+            test_game.deal(bet).unwrap();
+            test_game.play_hand().unwrap();
+            test_game.reset_hands();
+
+            let needed = Labouchere::default().history_needed(10, &test_game.bet_history);
+            assert_eq!(needed, test_game.bet_history.len());
+        }
+    }
+
+    #[test]
+    /// Each betting strat gets its own bankroll report
+    fn test_compare_betting_strats() {
+        let mut rng = ChaCha8Rng::seed_from_u64(2);
+        let mut deck = MultiDeck::new(6, false);
+        deck.shuffle(&mut rng);
+
+        let settings = GameSettings {
+            deck,
+            max_splits: 3,
+            contains_blank: false,
+            init_bet: 10,
+            bankroll: 500,
+            dealer_cutoff: 17,
+            dealer_strat: Arc::new(Box::new(DealerPlay)),
+            player_strat: Arc::new(Box::new(BasicStrategy)),
+            betting_strat: Arc::new(Box::new(ConstantBet)),
+            counting_strat: Arc::new(Box::new(HiLo)),
+            insurance_strat: Arc::new(Box::new(NoInsurance)),
+            allow_early_surrender: false,
+            allow_late_surrender: false,
+            rules: TableRules::default(),
+            table_min: 2,
+            table_max: 500,
+            bet_increment: 1,
+            limit_policy: LimitPolicy::Clamp,
+            echo: false,
+            rng,
+            seed: Some(2),
+        };
+
+        let strats: Vec<Strat> = vec![
+            Arc::new(Box::new(ConstantBet)),
+            Arc::new(Box::new(Paroli)),
+            Arc::new(Box::new(Labouchere::default())),
+            Arc::new(Box::new(OscarsGrind)),
+        ];
+        let reports = simulate_betting_strats(&settings, strats, 10, 200, false).unwrap();
+        compare_bankrolls(&reports);
+
+        assert_eq!(reports.len(), 4);
+        assert_eq!(reports[1].0, "Paroli");
+        reports.iter().for_each(|(_, report)| {
+            assert_eq!(report.trajectories, 10);
+            assert!(report.percentile(5.0) <= report.percentile(95.0));
+        });
+    }
+
+    #[test]
+    /// Sessions end at their length or a stop-win/loss threshold
+    fn test_sessions() {
+        let hours = SessionLength::Hours { hours: 1.5, hands_per_hour: 60 };
+        assert_eq!(hours.hands(), 90);
+
+        let session = SessionSettings {
+            length: SessionLength::Hands(100),
+            bankroll: 200,
+            stop_win: Some(50),
+            stop_loss: Some(100),
+        };
+        let mut test_pool = GamePool::new(Arc::new(standard_settings().validate().unwrap()));
+        let report = test_pool.simulate_sessions(50, &session, false).unwrap();
+        report.sum_results();
+
+        assert_eq!(report.sessions, 50);
+        assert_eq!(report.results.len(), 50);
+        assert!(report.stop_wins > 0);
+        assert!(report.stop_wins + report.stop_losses + report.ruined <= 50);
+        report.results.iter().zip(report.hands.iter()).for_each(|(result, hands)| {
+            assert!(*hands <= 100);
+            // Left early only after hitting a threshold
+            if *hands < 100 {
+                assert!(*result >= 50 || *result <= -100);
+            }
+        });
+        assert!(report.percentile(5.0) <= report.percentile(50.0));
+        assert!((0.0..=1.0).contains(&report.winning_sessions()));
+
+        // No double or split past the session bankroll
+        let session = SessionSettings { length: SessionLength::Hands(500), bankroll: 40, stop_win: None, stop_loss: None };
+        let report = test_pool.simulate_sessions(50, &session, false).unwrap();
+        assert!(report.ruined > 0);
+        assert!(report.results.iter().all(|result| *result >= -40));
+
+        // Rounds sat out under table limits aren't hands
+        let settings = GameSettings {
+            betting_strat: Arc::new(Box::new(Martingale)),
+            table_max: 40,
+            limit_policy: LimitPolicy::Reject,
+            ..standard_settings()
+        };
+        let session = SessionSettings { length: SessionLength::Hands(100), bankroll: 1_000_000, stop_win: None, stop_loss: None };
+        let mut test_pool = GamePool::new(Arc::new(settings.clone().validate().unwrap()));
+        let report = test_pool.simulate_sessions(10, &session, false).unwrap();
+        assert_eq!(report.hands, vec![100; 10]);
+        assert_eq!(test_pool.simulated_games, 10 * 100);
+
+        // Base bet refused, sessions end w/o a hand
+        let mut test_pool = GamePool::new(Arc::new(GameSettings { table_min: 20, ..settings }.validate().unwrap()));
+        let report = test_pool.simulate_sessions(3, &session, false).unwrap();
+        assert_eq!(report.hands, vec![0; 3]);
+    }
+
+
 // |-------------------------|
-// |  Insurance Strat Tests  |
+// |      Report Tests       |
 // |-------------------------|
 
     #[test]
-    fn test_no_insurance() {
-        let insurance_strat:Option<Arc<InsuranceFunc>> = Some(Arc::new(Box::new(no_insurance)));
-        let game = standard_game(None, None, None, None, insurance_strat);
-        let decision = game.player.decide_insurance(game.get_state(&Hand::new(10)));
-        let expected_decision = false;
-        assert_eq!(expected_decision, decision);
+    /// Reports written as JSON & CSV load back unchanged
+    fn test_report_round_trip() {
+        let settings = GameSettings {
+            betting_strat: Arc::new(Box::new(Labouchere::default())), // Name w/ commas
+            ..standard_settings()
+        };
+        let mut test_pool = GamePool::new(Arc::new(settings.validate().unwrap()));
+        test_pool.simulate(500, false).unwrap();
+
+        let report = test_pool.report();
+        assert_eq!(report.rounds, 500);
+        assert_eq!(report.hands, test_pool.results.len() as u64);
+        assert_eq!(report.payoff, test_pool.get_player_payoff());
+        assert_eq!(report.settings.seed, Some(2));
+        assert_eq!(report.settings.betting_strat, "Labouchere [1, 2, 3]");
+        assert_eq!(report.outcomes.player_wins + report.outcomes.dealer_wins + report.outcomes.ties, report.hands);
+        assert!(report.ev_std_error > 0.0);
+
+        let dir = std::env::temp_dir().join(format!("bj_report_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        // JSON
+        let json_path = dir.join("report.json");
+        test_pool.write_json(&json_path).unwrap();
+        assert_eq!(SimulationReport::load_json(&json_path).unwrap(), report);
+
+        // CSV, one row per run
+        let csv_path = dir.join("report.csv");
+        let _ = std::fs::remove_file(&csv_path);
+        report.append_csv(&csv_path).unwrap();
+        report.append_csv(&csv_path).unwrap();
+        // Strategy params don't add columns
+        let mut with_spec = report.clone();
+        with_spec.settings.betting_spec = Some(serde_json::from_str(r#"{"name": "labouchere", "line": [1, 2, 3]}"#).unwrap());
+        with_spec.append_csv(&csv_path).unwrap();
+        let rows = SimulationReport::load_csv(&csv_path).unwrap();
+        assert_eq!(rows, vec![report.clone(), report.clone(), with_spec]);
+
+        // Penetration recorded, None for a random cut card
+        assert_eq!(report.settings.penetration, None);
+        let settings = GameSettings::<ChaCha8Rng>::builder().penetration(0.75).seed(2).build().unwrap();
+        let mut test_pool = GamePool::new(settings);
+        test_pool.simulate(10, false).unwrap();
+        let cut = test_pool.report();
+        assert_eq!(cut.settings.penetration, Some(0.75));
+        cut.append_csv(&csv_path).unwrap();
+        assert_eq!(SimulationReport::load_csv(&csv_path).unwrap().last(), Some(&cut));
+
+        // Other columns refused, file left as it was
+        let old_path = dir.join("old.csv");
+        std::fs::write(&old_path, "hands,ev\n10,0.5\n").unwrap();
+        let err = report.append_csv(&old_path).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+        assert!(err.to_string().contains("extra [ev]"), "{}", err);
+        assert_eq!(std::fs::read_to_string(&old_path).unwrap(), "hands,ev\n10,0.5\n");
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_event_log_replay() {
+        let path = std::env::temp_dir().join(format!("bj_events_{}.jsonl", std::process::id()));
+        let mut test_pool = GamePool::new(Arc::new(standard_settings().validate().unwrap()));
+        test_pool.log_events(&path).unwrap();
+        test_pool.simulate(300, false).unwrap();
+        test_pool.close_event_log().unwrap();
+
+        let events = read_events(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(events.first(), Some(GameEvent::Start { .. })));
+        assert_eq!(rounds(&events).len(), 300);
+        // Shoe in play logged first, reshuffles after
+        assert!(events.iter().filter(|event| matches!(event, GameEvent::Shuffle { .. })).count() > 1);
+
+        // Every round settled against the running bankroll
+        let last_bankroll = events.iter().rev().find_map(|event| match event {
+            GameEvent::Settle { bankroll, .. } => Some(*bankroll),
+            _ => None,
+        });
+        assert_eq!(last_bankroll, Some(1000 + test_pool.get_player_payoff()));
+
+        // Same strategies replay the log exactly
+        let same = replay(events.clone(), Arc::new(standard_settings().validate().unwrap())).unwrap();
+        assert_eq!(same.first_mismatch(), None);
+
+        // Different playing strat found at its first decision
+        let changed = GameSettings {
+            player_strat: Arc::new(Box::new(MimicDealer)),
+            ..standard_settings()
+        };
+        let mismatch = replay(events, Arc::new(changed.validate().unwrap())).unwrap().first_mismatch().expect("Decisions differ");
+        assert!(matches!(mismatch.logged, Some(GameEvent::Decision { .. })));
+    }
+
+
+    #[test]
+    /// Logs record the strategies' params, so parameterised strategies replay as run
+    fn test_logged_strategy_specs() {
+        let registry = StrategyRegistry::default();
+        let config = SimulationConfig::from_toml(r#"
+            seed = 4
+            [strategies]
+            betting = { name = "kelly", fraction = 0.25, min_bet = 20 }
+        "#).unwrap();
+        let settings = config.settings::<ChaCha8Rng>(&registry).unwrap();
+
+        let path = std::env::temp_dir().join(format!("bj_spec_events_{}.jsonl", std::process::id()));
+        let mut test_pool = GamePool::new(settings.clone());
+        test_pool.log_events(&path).unwrap();
+        test_pool.simulate(200, false).unwrap();
+        test_pool.close_event_log().unwrap();
+        let events = read_events(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let summary = logged_settings(&events).unwrap();
+        assert_eq!(summary.betting_strat, "Fractional Kelly (0.25x)");
+        assert_eq!(summary.betting_spec.as_ref(), Some(&config.strategies.betting));
+        assert_eq!(summary.player_spec, Some("basic".into()));
+        // Display name alone can't find the params
+        assert!(registry.get(StrategyKind::Betting, &summary.betting_strat).is_err());
+
+        let betting_strat = registry.build(StrategyKind::Betting, summary.betting_spec.as_ref().unwrap()).unwrap();
+        assert_eq!(betting_strat.bet_limits(), Some((20, 500)));
+        let rebuilt = GameSettings { betting_strat, ..GameSettings::clone(&settings) };
+        assert_eq!(replay(events, Arc::new(rebuilt.validate().unwrap())).unwrap().first_mismatch(), None);
+    }
+
+    #[derive(Default)]
+    struct TallyObserver {
+        shuffles: u64,
+        deals: u64,
+        player_decisions: u64,
+        dealer_decisions: u64,
+        draws: u64,
+        hands_settled: u64,
+        payoff: i64,
+        rounds: u64,
+        last_bankroll: i64,
+        shoes_ended: u64,
+    }
+
+    impl GameObserver for TallyObserver {
+        fn on_shuffle(&mut self, _shoe: &[Card]) { self.shuffles += 1 }
+        fn on_deal(&mut self, _player: &Hand, _dealer: &Hand) { self.deals += 1 }
+        fn on_decision(&mut self, seat: Seat, _hand: &Hand, _decision: &PlayerDecision, _state: &StateSummary) {
+            match seat {
+                Seat::Player => self.player_decisions += 1,
+                Seat::Dealer => self.dealer_decisions += 1,
+            }
+        }
+        fn on_draw(&mut self, _card: &Card) { self.draws += 1 }
+        fn on_hand_settled(&mut self, _hand: &Hand, _dealer: &Hand, _winner: &Winner, payoff: i64) {
+            self.hands_settled += 1;
+            self.payoff += payoff;
+        }
+        fn on_round_end(&mut self, bankroll: i64) {
+            self.rounds += 1;
+            self.last_bankroll = bankroll;
+        }
+        fn on_shoe_end(&mut self, _played: &[Card]) { self.shoes_ended += 1 }
+    }
+
+    #[test]
+    fn test_observers() {
+        let tally = Arc::new(Mutex::new(TallyObserver::default()));
+        let mut test_pool = GamePool::new(Arc::new(standard_settings().validate().unwrap()));
+        test_pool.add_observer(Box::new(tally.clone()));
+        test_pool.simulate(400, false).unwrap();
+        // Observers handed back to the pool
+        assert_eq!(test_pool.observers.len(), 1);
+
+        let tally = tally.lock().unwrap();
+        assert_eq!(tally.rounds, 400);
+        assert_eq!(tally.deals, 400);
+        assert_eq!(tally.hands_settled, test_pool.results.len() as u64);
+        assert_eq!(tally.payoff, test_pool.get_player_payoff());
+        assert_eq!(tally.last_bankroll, 1000 + test_pool.get_player_payoff());
+        assert!(tally.player_decisions >= tally.hands_settled);
+        assert!(tally.dealer_decisions >= tally.rounds);
+        assert!(tally.draws >= 4 * tally.rounds);
+        // Every reshuffle follows an exhausted shoe
+        assert!(tally.shoes_ended > 0);
+        assert_eq!(tally.shuffles, tally.shoes_ended);
+
+        // Unobserved run plays the same games
+        let mut unobserved = GamePool::new(Arc::new(standard_settings().validate().unwrap()));
+        unobserved.simulate(400, false).unwrap();
+        assert_eq!(unobserved.get_player_payoff(), test_pool.get_player_payoff());
+    }
+
+    #[test]
+    fn test_strategy_registry() {
+        let mut registry = StrategyRegistry::default();
+
+        // Registered name or display name, either case
+        assert_eq!(registry.get(StrategyKind::Playing, "basic").unwrap().to_string(), "Basic Strategy");
+        assert_eq!(registry.get(StrategyKind::Betting, "Oscar's Grind").unwrap().to_string(), "Oscar's Grind");
+        assert_eq!(registry.get(StrategyKind::Counting, "HI-LO").unwrap().to_string(), "Hi Lo");
+
+        // Names only found under their own kind
+        let err = registry.get(StrategyKind::Betting, "basic").err().expect("Not a betting strat");
+        assert_eq!(err.kind, StrategyKind::Betting);
+        assert!(err.known.contains(&"martingale".to_string()));
+
+        // Custom strategies registered next to the built ins
+        registry.register(StrategyKind::Betting, "quarter-kelly", || Box::new(KellyCriterion { fraction: 0.25, ..Default::default() }));
+        assert_eq!(registry.get(StrategyKind::Betting, "quarter-kelly").unwrap().to_string(), "Fractional Kelly (0.25x)");
+        assert_eq!(registry.names(StrategyKind::Betting).last().unwrap(), "quarter-kelly");
+    }
+
+    #[test]
+    /// Params no game could be played with are refused when the strategy is built
+    fn test_strategy_param_problems() {
+        let registry = StrategyRegistry::default();
+        let build = |spec: &str| registry.build(StrategyKind::Betting, &serde_json::from_str(spec).unwrap());
+
+        for spec in [
+            r#"{"name": "kelly", "min_bet": 600}"#,
+            r#"{"name": "kelly", "unit": 0}"#,
+            r#"{"name": "kelly", "variance": 0.0}"#,
+            r#"{"name": "kelly", "fraction": -0.5}"#,
+            r#"{"name": "kelly", "fraction": 1.5}"#,
+            r#"{"name": "labouchere", "line": []}"#,
+            r#"{"name": "labouchere", "line": [1, 0, 2]}"#,
+        ] {
+            let err = build(spec).err().expect(spec);
+            assert!(matches!(err, StrategyError::InvalidParams { kind: StrategyKind::Betting, .. }), "{}", spec);
+        }
+        let Err(StrategyError::InvalidParams { message, .. }) = build(r#"{"name": "kelly", "min_bet": 600, "unit": 0}"#) else { panic!() };
+        assert_eq!(message, "min_bet (600) is above max_bet (500); unit must be positive");
+
+        assert!(build(r#"{"name": "kelly", "min_bet": 25, "fraction": 0.25}"#).is_ok());
+        assert!(build(r#"{"name": "labouchere", "line": [2]}"#).is_ok());
+
+        // Strategies built in code are checked w/ the rest of the settings
+        let err = GameSettings::<ChaCha8Rng>::builder()
+            .betting_strat(Arc::new(Box::new(KellyCriterion::new(1.0, 50, 20, 5))))
+            .build()
+            .err().expect("Kelly limits backwards");
+        let GameError::InvalidSettings(problems) = err else { panic!("Expected invalid settings") };
+        assert_eq!(problems, vec!["Kelly Criterion: min_bet (50) is above max_bet (20)"]);
+    }
+
+    #[test]
+    fn test_simulation_config() {
+        let registry = StrategyRegistry::default();
+        let config = SimulationConfig::from_toml(r#"
+            rounds = 500
+            seed = 11
+
+            [shoe]
+            decks = 2
+            penetration = 0.5
+
+            [table]
+            limit_policy = "reject"
+
+            [strategies]
+            player = "mimic-dealer"
+            betting = { name = "kelly", fraction = 0.5 }
+            counting = { name = "ramp" }
+        "#).unwrap();
+        assert_eq!(config.rules.dealer_cutoff, 17); // Missing sections take defaults
+        assert_eq!(config.table.limit_policy, LimitPolicy::Reject);
+
+        // Same config back out of either format
+        assert_eq!(SimulationConfig::from_toml(&config.to_toml()).unwrap(), config);
+        assert_eq!(SimulationConfig::from_json(&serde_json::to_string(&config).unwrap()).unwrap(), config);
+
+        // Every problem reported at once, strategies included
+        let err = config.settings::<ChaCha8Rng>(&registry).err().expect("Ramp isn't a counting strat");
+        let ConfigError::Invalid(problems) = err else { panic!("Expected invalid config") };
+        assert_eq!(problems.len(), 1);
+        assert!(problems[0].starts_with("strategies.counting: Unknown counting strategy 'ramp'"));
+
+        let mut config = config;
+        config.strategies.counting = "hi-lo".into();
+        let settings = config.settings::<ChaCha8Rng>(&registry).unwrap();
+        assert_eq!(settings.player_strat.to_string(), "Mimic Dealer");
+        assert_eq!(settings.betting_strat.to_string(), "Fractional Kelly (0.5x)");
+        assert_eq!(settings.seed, Some(11));
+        // Half of the 104 cards behind the cut card
+        let blank = settings.deck.decks.cards.iter().position(|card| card.rank == Rank::Blank);
+        assert_eq!(blank, Some(52));
+
+        // Cut card reshuffles instead of running the shoe dry
+        let mut pool = GamePool::new(settings);
+        pool.simulate(config.rounds, false).unwrap();
+
+        let mut bad = SimulationConfig { rounds: 0, ..Default::default() };
+        bad.shoe.penetration = Some(1.5);
+        bad.table.min = 1_000;
+        bad.strategies.betting = serde_json::from_str(r#"{"name": "martingale", "unit": 5}"#).unwrap();
+        let err = bad.settings::<ChaCha8Rng>(&registry).err().expect("Invalid config");
+        let message = err.to_string();
+        assert!(message.starts_with("4 problems:"));
+        assert!(message.contains("shoe.penetration must be in (0, 1], found 1.5"));
+        assert!(message.contains("table.min (1000) is above table.max (500)"));
+        assert!(message.contains("betting strategy 'martingale': takes no parameters, found 'unit'"));
+
+        // Settings problems found on build named by config key too
+        let bad = SimulationConfig { table: TableConfig { increment: 0, ..Default::default() }, ..Default::default() };
+        let Err(ConfigError::Invalid(problems)) = bad.settings::<ChaCha8Rng>(&registry) else { panic!("Expected invalid config") };
+        assert_eq!(problems, vec!["table.increment must be positive".to_string()]);
+
+        // Whole setting names only
+        let err = GameError::InvalidSettings(vec!["table_min (5) is above table_max (2)".to_string()]);
+        let err = err.rename_settings(&[("table_min", "--table-min"), ("table", "?"), ("max", "?")]);
+        assert_eq!(err, GameError::InvalidSettings(vec!["--table-min (5) is above table_max (2)".to_string()]));
+
+        // Typos caught while parsing
+        assert!(matches!(SimulationConfig::from_toml("[shoe]\ndeck = 2"), Err(ConfigError::Parse(_))));
+    }
+
+// |-------------------------|
+// |    Table Rule Tests     |
+// |-------------------------|
+
+    #[test]
+    fn test_table_rules() {
+        let cards = |codes: &str| codes.split(' ').map(|code| code.parse::<Card>().unwrap()).collect::<Vec<_>>();
+        let hand = |codes: &str| codes.parse::<Hand>().unwrap();
+
+        // Aces drop to 1 one at a time
+        assert_eq!(hand("AS AH").value(), 12);
+        assert_eq!(hand("AS AH 9D").value(), 21);
+        assert_eq!(hand("AS 6H KD").value(), 17);
+
+        // Dealer hits soft 17 only under H17
+        let h17 = TableRules { hit_soft_17: true, ..TableRules::default() };
+        let dealer_decision = |dealer: Hand, rules: TableRules| {
+            let state = GameView::new(10, 1000, &[], dealer.cards.first().copied(), None, None, Some(&dealer), 17, false, Winner::None, 0, 0.0, false, false);
+            match DealerPlay.get_decision(&state.with_rules(rules)).unwrap() {
+                StratReturn::Play(decision) => decision,
+                _ => unreachable!("Dealer plays"),
+            }
+        };
+        assert_eq!(dealer_decision(hand("AS 6H"), TableRules::default()), PlayerDecision::Stand);
+        assert_eq!(dealer_decision(hand("AS 6H"), h17), PlayerDecision::Hit);
+        assert_eq!(dealer_decision(hand("AS 6H KD"), h17), PlayerDecision::Stand);
+
+        // Doubles & splits within the table's rules
+        let strict = TableRules { double_after_split: false, double_on: DoubleRule::TenToEleven, split_aces_one_card: true, ..TableRules::default() };
+        let state = GameView::new(10, 1000, &[], None, None, None, None, 17, false, Winner::None, 0, 0.0, false, false).with_rules(strict);
+        assert!(state.can_double(&hand("5S 6H")));
+        assert!(!state.can_double(&hand("5S 4H")));
+        assert!(!state.can_double(&hand("5S 2H 4D")));
+        assert!(!state.can_double(&Hand::from_cards(cards("5S 6H"), 10, false, false, true)));
+        assert!(state.can_split(&hand("8S 8H")));
+        assert!(!state.can_split(&Hand::from_cards(cards("AS AH"), 10, false, false, true)));
+
+        let play = |player_strat: Strat, rules: TableRules, dealer: &str, player: &str| {
+            let settings = GameSettings { player_strat, rules, ..standard_settings() };
+            let mut game = Game::from_settings(Arc::new(settings.validate().unwrap()));
+            set_hands(&mut game, Hand::from_cards(cards(dealer), 10, false, false, false), vec![Hand::from_cards(cards(player), 10, false, false, false)]);
+            let (winner, state) = game.play_hand().unwrap().remove(0);
+            state.payoff(&winner)
+        };
+
+        // Peeked natural ends the round before the double, w/o a hole card the double is lost too
+        // Basic strategy always doubles 11, full basic doesn't against a possible natural
+        let basic: Strat = Arc::new(Box::new(BasicStrategy));
+        let full_basic: Strat = Arc::new(Box::new(FullBasicStrategy));
+        let peek = TableRules { dealer_peeks: true, ..TableRules::default() };
+        assert_eq!(play(basic.clone(), peek, "KS AH", "5S 6H"), -10);
+        assert_eq!(play(basic.clone(), TableRules::default(), "KS AH", "5S 6H"), -20);
+        assert_eq!(play(full_basic.clone(), TableRules::default(), "KS AH", "5S 6H"), -10);
+
+        // Natural pays by the table & beats a dealer 21 that isn't one
+        let six_to_five = TableRules { blackjack_pays: BlackjackPayout::SixToFive, ..TableRules::default() };
+        assert_eq!(play(full_basic.clone(), six_to_five, "9S 8H", "AS KH"), 12);
+        assert_eq!(play(full_basic, TableRules::default(), "7S 7H 7D", "AS KH"), 15);
+    }
+
+    #[test]
+    // Full basic strategy under each preset lands near its published house edge
+    // Allowed miss: the run's own 95% interval plus 0.15% for strategy & cut card differences
+    fn test_preset_house_edges() {
+        let registry = StrategyRegistry::default();
+        for preset in presets() {
+            let config = SimulationConfig { rounds: 2_000_000, seed: Some(2), ..preset.config() };
+            let mut pool = GamePool::new(config.settings::<ChaCha8Rng>(&registry).unwrap());
+            pool.run(config.rounds, false).unwrap();
+            let report = pool.report();
+
+            // Published edges are per initial bet, a round's splits & doubles included
+            let house_edge = -report.ev_round_percent;
+            let tolerance = report.ev_round_percent_ci95 + 0.05;
+            assert!((house_edge - preset.house_edge).abs() <= tolerance,
+                "{}: simulated edge {:.3}% vs published {:.2}% (+/- {:.3}%)", preset.name, house_edge, preset.house_edge, tolerance);
+        }
+    }
+
+    #[test]
+    fn test_sweep() {
+        let registry = StrategyRegistry::default();
+        let mut sweep = Sweep::from_value(serde_json::json!({
+            "rounds": 2_000,
+            "seed": 5,
+            "vary": [{ "key": "shoe.decks", "values": [2, 6] }],
+        })).unwrap();
+        sweep.vary(Axis::parse("rules.blackjack_pays=3:2,6:5").unwrap());
+        assert_eq!(Axis::parse("shoe.penetration=0.5,0.8").unwrap().values, vec![serde_json::json!(0.5), serde_json::json!(0.8)]);
+        assert!(Axis::parse("shoe.decks").is_err());
+        assert_eq!(sweep.point_count(), 4);
+
+        // First axis slowest, same results however many run at once
+        let rows = sweep.run::<ChaCha8Rng>(&registry, 1, false).unwrap();
+        let order = rows.iter().map(|row| (row.params["shoe.decks"].as_u64().unwrap(), row.report.settings.rules.blackjack_pays)).collect::<Vec<_>>();
+        assert_eq!(order, vec![
+            (2, BlackjackPayout::ThreeToTwo), (2, BlackjackPayout::SixToFive),
+            (6, BlackjackPayout::ThreeToTwo), (6, BlackjackPayout::SixToFive),
+        ]);
+        let results = |rows: &[SweepRow]| rows.iter().map(|row| (row.params.clone(), row.report.hands, row.report.payoff)).collect::<Vec<_>>();
+        assert_eq!(results(&sweep.run::<ChaCha8Rng>(&registry, 3, false).unwrap()), results(&rows));
+        // Shared seed, only the payout differs: 6:5 never pays more
+        assert_eq!(rows[0].report.hands, rows[1].report.hands);
+        assert!(rows[1].report.ev_per_hand < rows[0].report.ev_per_hand);
+
+        let path = std::env::temp_dir().join(format!("bj_sweep_{}.csv", std::process::id()));
+        sweep.write_csv(&rows, &path).unwrap();
+        let csv = std::fs::read_to_string(&path).unwrap();
+        let mut lines = csv.lines();
+        assert!(lines.next().unwrap().starts_with("shoe.decks,rules.blackjack_pays,rounds,hands,ev_per_hand"));
+        assert!(lines.next().unwrap().starts_with("2,3:2,2000,"));
+        assert_eq!(lines.count(), 3);
+        std::fs::remove_file(&path).unwrap();
+
+        // Nothing runs if any point is invalid
+        sweep.vary(Axis::parse("shoe.deck=1").unwrap());
+        sweep.vary(Axis::parse("shoe.penetration=0.5,1.5").unwrap());
+        let Err(ConfigError::Invalid(problems)) = sweep.run::<ChaCha8Rng>(&registry, 1, false) else { panic!("Expected invalid sweep") };
+        assert_eq!(problems.len(), 1);
+        assert!(problems[0].contains("unknown field `deck`"));
+    }
+
+// |-------------------------|
+// |     Fast Path Tests     |
+// |-------------------------|
+
+    #[test]
+    // Every 2 & 3 card hand valued as the rich hand values it
+    fn test_fast_hand() {
+        let ranks = Rank::iterator().copied().collect::<Vec<_>>();
+        for &a in ranks.iter() {
+            for &b in ranks.iter() {
+                for third in std::iter::once(None).chain(ranks.iter().copied().map(Some)) {
+                    let mut cards = vec![Card::from_rank(a), Card::from_rank(b)];
+                    cards.extend(third.map(Card::from_rank));
+                    let mut hand = Hand::new(10);
+                    cards.iter().for_each(|card| hand.add_card(*card));
+
+                    let fast = FastHand::from(&hand);
+                    assert_eq!(fast.value(), hand.value(), "{:?}", cards);
+                    assert_eq!(fast.soft(), hand.contains_soft_ace(), "{:?}", cards);
+                    assert_eq!(fast.is_pair(), hand.contains_pair(), "{:?}", cards);
+                }
+            }
+        }
+        assert_eq!(card_idx(Card::from_rank(Rank::King)), 13);
+        assert_eq!(idx_value(13), 10);
+    }
+
+    #[test]
+    // Table lookups match asking the strategy, first two cards against every upcard
+    fn test_fast_play_table() {
+        let rules = TableRules { dealer_peeks: true, hit_soft_17: true, ..TableRules::default() };
+        let settings = GameSettings { player_strat: Arc::new(Box::new(FullBasicStrategy)), allow_late_surrender: true, rules, ..standard_settings() };
+        let table = PlayTable::new(&settings).unwrap();
+        for &first in Rank::iterator() {
+            for &second in Rank::iterator() {
+                let hand = Hand::from_cards(vec![Card::from_rank(first), Card::from_rank(second)], 10, false, false, false);
+                for &up in Rank::iterator() {
+                    let upcard = Card::from_rank(up);
+                    let dealer = Hand::from_cards(vec![upcard], 10, false, false, false);
+                    let state = GameView::new(10, 1000, &[], Some(upcard), Some(upcard.get_dealer_str()), Some(&hand), Some(&dealer), 17, true, Winner::None, 0, 0.0, false, true)
+                        .with_rules(rules);
+                    let StratReturn::Play(expected) = FullBasicStrategy.get_decision(&state).unwrap() else { unreachable!("Playing strat") };
+                    assert_eq!(table.get(&FastHand::from(&hand), upcard.rank.value(false), true).unwrap(), &expected, "{:?} {:?} vs {:?}", first, second, up);
+                }
+            }
+        }
+
+        // Nothing to look up over 21
+        let busted = "10S 6H 9D".parse::<Hand>().unwrap();
+        assert_eq!(table.get(&FastHand::from(&busted), 10, true), Err(GameError::Busted(25)));
+    }
+
+    #[test]
+    // Same edges as the rich engine under every preset, see `test_preset_house_edges`
+    fn test_fast_game() {
+        let registry = StrategyRegistry::default();
+        for preset in presets() {
+            let config = SimulationConfig { seed: Some(2), ..preset.config() };
+            let mut game = FastGame::new(config.settings::<ChaCha8Rng>(&registry).unwrap()).unwrap();
+            game.run(2_000_000).unwrap();
+            let report = game.report();
+            assert_eq!(report.rounds, 2_000_000);
+            assert!(report.hands > report.rounds);
+
+            let house_edge = -report.ev_round_percent;
+            let tolerance = report.ev_round_percent_ci95 + 0.05;
+            assert!((house_edge - preset.house_edge).abs() <= tolerance,
+                "{}: fast edge {:.3}% vs published {:.2}% (+/- {:.3}%)", preset.name, house_edge, preset.house_edge, tolerance);
+        }
+
+        // Seeded runs repeat
+        let settings = Arc::new(standard_settings().validate().unwrap());
+        let runs = [0, 1].map(|_| {
+            let mut game = FastGame::new(settings.clone()).unwrap();
+            game.run(10_000).unwrap();
+            game.report().payoff
+        });
+        assert_eq!(runs[0], runs[1]);
+
+        // Bet refused by the table limits, every round sat out
+        let mut game = FastGame::new(Arc::new(GameSettings { table_max: 5, limit_policy: LimitPolicy::Reject, ..standard_settings() }.validate().unwrap())).unwrap();
+        game.run(100).unwrap();
+        assert_eq!((game.report().hands, game.limit_stats.rejected), (0, 100));
+
+        // Only strategies the tables can hold: flat bets & decisions blind to the count
+        let martingale = GameSettings { betting_strat: Arc::new(Box::new(Martingale)), ..standard_settings() };
+        let Err(GameError::InvalidSettings(problems)) = FastGame::new(Arc::new(martingale.validate().unwrap())) else { panic!("Martingale bets vary") };
+        assert_eq!(problems, vec!["fast engine bets init_bet flat, found 'Martingale'".to_string()]);
+        let queued = GameSettings { player_strat: QueuedDecisions::strat(&[]), ..standard_settings() };
+        let Err(GameError::InvalidSettings(problems)) = FastGame::new(Arc::new(queued.validate().unwrap())) else { panic!("Queued decisions aren't by shape") };
+        assert!(problems[0].contains("'Queued Decisions' may read the count"), "{:?}", problems);
+        let registry = StrategyRegistry::default();
+        assert!(registry.names(StrategyKind::Playing).iter().all(|name| registry.get(StrategyKind::Playing, name).unwrap().count_independent()));
+    }
+
+// |-------------------------|
+// |        Shoe Tests       |
+// |-------------------------|
+
+    #[test]
+    fn test_continuous_shuffler() {
+        let mut rng = ChaCha8Rng::seed_from_u64(2);
+        let mut shoe = ContinuousShuffler::new(&MultiDeck::new(1, true).with_reservoir(20));
+        shoe.reshuffle(&[], &mut rng);
+        assert_eq!(shoe.cards().len(), 52);
+
+        // Discards back in behind the reservoir, never dealt straight back out
+        let round = (0..10).map(|_| shoe.draw(&mut rng).unwrap()).collect::<Vec<_>>();
+        assert!(shoe.discard(&round, &mut rng));
+        assert_eq!(shoe.cards().len(), 52);
+        let next = (0..20).map(|_| shoe.draw(&mut rng).unwrap()).collect::<Vec<_>>();
+        assert!(next.iter().all(|card| !round.contains(card)));
+
+        // Count starts over every round, nothing left out of the machine
+        let settings = GameSettings::<ChaCha8Rng>::builder().shoe(ShoeKind::Csm).seed(2).build().unwrap();
+        assert!(!settings.contains_blank);
+        let mut game = Game::from_settings(settings);
+        for _ in 0..50 {
+            game.play_round().unwrap();
+            assert_eq!((game.running_count, game.played_cards.len()), (0, 0));
+            assert_eq!(game.shoe().cards().len(), 6 * 52);
+        }
+
+        let settings = GameSettings::<ChaCha8Rng>::builder().decks(1).shoe(ShoeKind::Csm).reservoir(52).build();
+        let Err(GameError::InvalidSettings(problems)) = settings else { panic!("Reservoir holds the whole shoe") };
+        assert_eq!(problems, vec!["reservoir (52) must leave room for discards in a 52 card shoe".to_string()]);
+    }
+
+    #[test]
+    fn test_infinite_deck() {
+        let mut rng = ChaCha8Rng::seed_from_u64(2);
+        let mut shoe = InfiniteDeck::new(&MultiDeck::new(6, false));
+        let cards = (0..5_200).map(|_| shoe.draw(&mut rng).unwrap()).collect::<Vec<_>>();
+        // Drawn w/ replacement, every rank about as often as in a deck
+        let aces = cards.iter().filter(|card| card.rank == Rank::Ace).count();
+        assert!((300..500).contains(&aces), "{} aces in 5200 cards", aces);
+        assert_eq!(shoe.cards().len(), 52);
+
+        // Fast engine & replays need a hand shuffled shoe
+        let settings = GameSettings::<ChaCha8Rng>::builder().shoe(ShoeKind::Infinite).build().unwrap();
+        assert!(FastGame::new(settings.clone()).is_err());
+        assert!(replay(vec![], settings).is_err());
+    }
+
+    #[test]
+    // Counting only pays when cards stay out of play until the shuffle
+    fn test_compare_shoes() {
+        let settings = GameSettings::<ChaCha8Rng>::builder()
+            .betting_strat(Arc::new(Box::new(BetRamp::default())))
+            .seed(2)
+            .build()
+            .unwrap();
+        let comparisons = compare_shoes(&settings, 5_000, false).unwrap();
+        assert_eq!(comparisons.iter().map(|comparison| comparison.shoe).collect::<Vec<_>>(), ShoeKind::all());
+
+        for comparison in comparisons.iter() {
+            assert_eq!(comparison.flat.settings.shoe, comparison.shoe);
+            match comparison.shoe {
+                // Count is back to 0 before every bet, the counter flat bets too
+                ShoeKind::Csm | ShoeKind::Infinite => {
+                    assert_eq!(comparison.counter.total_bet, comparison.flat.total_bet);
+                    assert!((comparison.counter_edge() + comparison.house_edge()).abs() < 1e-9);
+                }
+                ShoeKind::Shuffled => assert!(comparison.counter.total_bet > comparison.flat.total_bet),
+            }
+        }
+    }
+
+    #[test]
+    fn test_shuffle_procedure() {
+        let procedure = "riffle, strip:5, plug:3, riffle, cut".parse::<ShuffleProcedure>().unwrap();
+        assert_eq!(procedure.0, vec![ShuffleStep::Riffle, ShuffleStep::Strip(5), ShuffleStep::Plug(3), ShuffleStep::Riffle, ShuffleStep::Cut]);
+        assert_eq!(procedure.to_string().parse::<ShuffleProcedure>().unwrap(), procedure);
+        assert!("perfect".parse::<ShuffleProcedure>().unwrap().is_perfect());
+        assert!("riffle, shuffle".parse::<ShuffleProcedure>().is_err());
+        assert!("strip:x".parse::<ShuffleProcedure>().is_err());
+
+        // Same cards back out, whatever the steps
+        let mut rng = ChaCha8Rng::seed_from_u64(2);
+        let mut stack = (0..312).collect::<Vec<usize>>();
+        procedure.apply(&mut stack, &mut rng);
+        let mut sorted = stack.clone();
+        sorted.sort_unstable();
+        assert_eq!(sorted, (0..312).collect::<Vec<usize>>());
+
+        // One riffle interleaves 2 packets, a wash leaves about half the cards starting a new run
+        let riffle = OrderRetention::measure(&ShuffleProcedure(vec![ShuffleStep::Riffle]), 312, 100, &mut rng);
+        assert!(riffle.rising_sequences <= 2.0);
+        let wash = OrderRetention::measure(&ShuffleProcedure(vec![ShuffleStep::Wash]), 312, 100, &mut rng);
+        assert!((wash.rising_sequences - 156.5).abs() < 5.0, "{:?}", wash);
+        assert!(wash.pairs_kept < 0.01);
+        let measured = OrderRetention::measure(&procedure, 312, 100, &mut rng);
+        assert!(measured.rising_sequences > riffle.rising_sequences && measured.rising_sequences < wash.rising_sequences);
+
+        let settings = GameSettings::<ChaCha8Rng>::builder().shuffle(ShuffleProcedure(vec![ShuffleStep::Strip(0)])).build();
+        let Err(GameError::InvalidSettings(problems)) = settings else { panic!("Strip w/o packets") };
+        assert_eq!(problems, vec!["shuffle step 'strip:0' needs at least 1 packet".to_string()]);
+    }
+
+    #[test]
+    // Next shoe is the last one's discards put through the procedure
+    fn test_game_shuffle_procedure() {
+        let settings = GameSettings::<ChaCha8Rng>::builder()
+            .decks(2)
+            .shuffle(ShuffleProcedure(vec![ShuffleStep::Riffle]))
+            .seed(2)
+            .build()
+            .unwrap();
+        assert!(FastGame::new(settings.clone()).is_err());
+
+        let mut game = Game::from_settings(settings);
+        let mut ended = 0;
+        while ended < 3 {
+            game.play_round().unwrap();
+            if game.played_cards.is_empty() {
+                ended += 1;
+                let mut cards = game.shoe().cards().iter().filter(|card| !card.is_blank()).map(|card| card.to_string()).collect::<Vec<_>>();
+                let mut fresh = MultiDeck::new(2, false).decks.cards.iter().map(|card| card.to_string()).collect::<Vec<_>>();
+                cards.sort();
+                fresh.sort();
+                assert_eq!(cards, fresh);
+                assert!(game.shoe().contains_blank());
+            }
+        }
+    }
+
+// |-------------------------|
+// |   Scripted Shoe Tests   |
+// |-------------------------|
+
+    #[test]
+    fn test_card_notation() {
+        let king = "Kh".parse::<Card>().unwrap();
+        assert_eq!((king.rank, king.suit), (Rank::King, Suit::Hearts));
+        assert_eq!("10s".parse::<Card>().unwrap(), "TS".parse::<Card>().unwrap());
+        assert_eq!("q\u{2666}".parse::<Card>().unwrap().code(), "QD");
+        assert_eq!("a".parse::<Rank>().unwrap(), Rank::Ace);
+        for text in ["K", "1h", "11h", "Kz", "X", ""] {
+            assert!(text.parse::<Card>().is_err(), "'{}' parsed", text);
+        }
+        // Every code back to the card it came from, X for the blank in logged shoes
+        for card in MultiDeck::new(1, true).decks.cards.iter() {
+            assert_eq!(Card::from_code(&card.code()), Some(*card));
+        }
+        assert!(Card::from_code("X").unwrap().is_blank());
+
+        // Real rank shown, not the value
+        assert_eq!(king.to_string(), "[K \u{2665}]");
+        assert_eq!(format!("{:#}", king), "[K h]");
+        let hand = "As 7d".parse::<Hand>().unwrap();
+        assert_eq!((hand.value(), hand.contains_soft_ace()), (18, true));
+        assert_eq!(format!("{:#}", hand), "[A s][7 d]");
+        assert_eq!("As Ad".parse::<Hand>().unwrap().value(), 12);
+        assert!("As 7x".parse::<Hand>().is_err());
+
+        let mut rng = ChaCha8Rng::seed_from_u64(2);
+        let mut shoe = "As 10h | 6d".parse::<ScriptedShoe>().unwrap();
+        shoe.reshuffle(&[], &mut rng);
+        assert_eq!(shoe.cards().iter().rev().map(|card| card.code()).collect::<Vec<_>>(), vec!["AS", "10H", "6D"]);
+    }
+
+    #[test]
+    fn test_script_notation() {
+        let script = "AS 10H | 6D ?".parse::<Script>().unwrap();
+        assert_eq!(script.groups, vec![
+            vec![Some("AS".parse().unwrap()), Some("10H".parse().unwrap())],
+            vec![Some("6D".parse().unwrap()), None],
+        ]);
+        assert_eq!(script.to_string(), "AS 10H | 6D ?");
+        assert_eq!(script.cards().len(), 4);
+        assert!("AS ZH".parse::<Script>().is_err());
+        assert!("AS X".parse::<Script>().is_err());
+
+        // Cards as written, `?` drawn from the shoe
+        let mut game = Game::from_settings(Arc::new(standard_settings().validate().unwrap()));
+        game.set_table(&"8S 8H | AS 5C | 6D ?".parse().unwrap()).unwrap();
+        assert_eq!(game.player.hands.iter().map(|hand| hand.value()).collect::<Vec<_>>(), vec![16, 16]);
+        assert!(game.player.hands[0].contains_pair());
+        let dealer = game.dealer.hand.as_ref().unwrap();
+        assert_eq!((dealer.cards.len(), dealer.cards[0].code()), (2, "6D".to_string()));
+        assert_eq!(game.played_cards.len(), 1);
+    }
+
+    #[test]
+    fn test_scripted_shoe() {
+        let mut rng = ChaCha8Rng::seed_from_u64(2);
+        let mut shoe = "AS 10H | 6D ?".parse::<Script>().unwrap().shoe();
+        shoe.reshuffle(&[], &mut rng);
+        let dealt = (0..3).map(|_| shoe.draw(&mut rng).unwrap().code()).collect::<Vec<_>>();
+        assert_eq!(dealt, vec!["AS", "10H", "6D"]);
+        assert!(shoe.draw(&mut rng).is_some());
+        assert!(shoe.draw(&mut rng).is_none());
+
+        // Out of cards w/o a fallback, the script isn't dealt twice
+        let mut game = Game::from_settings(Arc::new(standard_settings().validate().unwrap()));
+        game.set_shoe(Box::new("AS 10H 6D".parse::<Script>().unwrap().shoe()));
+        assert_eq!(game.deal(10).unwrap_err(), GameError::EmptyShoe);
+
+        // Fresh decks once it runs out
+        let mut game = Game::from_settings(Arc::new(standard_settings().validate().unwrap()));
+        game.set_shoe(Box::new("AS 10H | 6D 10C".parse::<Script>().unwrap().shoe().with_fallback(6)));
+        assert_eq!(game.shoe().cards().len(), 6 * 52 + 4);
+        for _ in 0..500 {
+            game.play_round().unwrap();
+        }
+    }
+
+    #[test]
+    fn test_scripted_naturals() {
+        // 3:2 & 6:5, dealer still draws to 16
+        let (results, won) = play_script(standard_settings(), "AS 10H | 6D 10C | 2C");
+        assert!(results[0].1.p_natural);
+        assert_eq!(won, 15);
+        let rules = TableRules { blackjack_pays: BlackjackPayout::SixToFive, ..TableRules::default() };
+        assert_eq!(play_script(GameSettings { rules, ..standard_settings() }, "AS 10H | 6D 10C | 2C").1, 12);
+
+        // Ace & ten after a split is only 21
+        let (results, won) = play_script(standard_settings(), "AS AH | 10D 10C | 10S | 9H");
+        assert_eq!(results.iter().map(|(winner, _)| winner.clone()).collect::<Vec<_>>(), vec![Winner::Player, Winner::Tie]);
+        assert!(!results[0].1.p_natural);
+        assert_eq!(won, 10);
     }
 
-    fn test() {
-        let insurance_strat:Option<Arc<InsuranceFunc>> = Some(Arc::new(Box::new(card_counter_insurance)));
+    #[test]
+    fn test_scripted_doubles() {
+        // 11 doubled for one card, dealer busts
+        let (results, won) = play_script(standard_settings(), "5S 6H | 6D 10C | 10S | 9H");
+        assert!(results[0].1.p_doubled);
+        assert_eq!(won, 20);
+
+        // 9 only hit where doubles are 10 & 11 only
+        let rules = TableRules { double_on: DoubleRule::TenToEleven, ..TableRules::default() };
+        let (results, won) = play_script(GameSettings { rules, ..standard_settings() }, "5S 4H | 6D 10C | 10S | 5H");
+        assert!(!results[0].1.p_doubled);
+        assert_eq!(won, -10);
+
+        // No peek: a dealer natural takes the double too, peeking ends the round first
+        let (results, won) = play_script(standard_settings(), "5S 6H | 10D AC | 9S");
+        assert!(results[0].1.p_doubled && results[0].1.d_natural);
+        assert_eq!(won, -20);
+        let rules = TableRules { dealer_peeks: true, ..TableRules::default() };
+        assert_eq!(play_script(GameSettings { rules, ..standard_settings() }, "5S 6H | 10D AC").1, -10);
+    }
+
+    #[test]
+    fn test_scripted_splits() {
+        // Left hand doubled after the split, right stands on 18
+        let script = "8S 8H | 6D 10C | 3H 10D | 10S | 9C";
+        let (results, won) = play_script(standard_settings(), script);
+        assert_eq!(results.iter().map(|(_, state)| state.p_doubled).collect::<Vec<_>>(), vec![true, false]);
+        assert_eq!(won, 30);
+
+        // No double after split, 11 hit instead
+        let rules = TableRules { double_after_split: false, ..TableRules::default() };
+        let (results, won) = play_script(GameSettings { rules, ..standard_settings() }, script);
+        assert!(results.iter().all(|(_, state)| !state.p_doubled));
+        assert_eq!(won, 20);
+
+        // Splits used up, the second pair is hit
+        let (results, won) = play_script(GameSettings { max_splits: 1, ..standard_settings() }, "8S 8H | 6D 10C | 8D 2C | 10S | 9H");
+        assert_eq!(results.len(), 2);
+        assert_eq!(won, 20);
+
+        // Split aces get one card each, soft 13 left as is
+        let rules = TableRules { split_aces_one_card: true, ..TableRules::default() };
+        let (results, won) = play_script(GameSettings { rules, ..standard_settings() }, "AS AH | 6D 10C | 2S 3H | 10C");
+        assert_eq!(results.len(), 2);
+        assert!(results.iter().all(|(_, state)| state.d_bust));
+        assert_eq!(won, 20);
+    }
+
+    // Answers w/ the queued decisions in order, whatever the table allows, then stands
+    struct QueuedDecisions(Mutex<Vec<PlayerDecision>>);
+
+    impl QueuedDecisions {
+        fn strat(decisions: &[PlayerDecision]) -> Strat {
+            Arc::new(Box::new(QueuedDecisions(Mutex::new(decisions.iter().rev().cloned().collect()))))
+        }
+    }
+
+    impl StrategyFunc for QueuedDecisions {
+        fn get_decision(&self, _state: &GameView) -> Result<StratReturn, GameError> {
+            Ok(StratReturn::Play(self.0.lock().unwrap().pop().unwrap_or(PlayerDecision::Stand)))
+        }
+
+        fn to_string(&self) -> String {
+            "Queued Decisions".to_string()
+        }
+
+        fn surrenders_early(&self) -> bool { true }
+        fn surrenders_late(&self) -> bool { true }
+    }
+
+    #[test]
+    /// Strategies asked before the deal answer w/ an error, not a panic
+    fn test_strategy_not_dealt() {
+        let state = GameView::new(10, 1000, &[], None, None, None, None, 17, false, Winner::None, 0, 0.0, false, false);
+        let registry = StrategyRegistry::default();
+        for kind in [StrategyKind::Playing, StrategyKind::Dealer, StrategyKind::Counting] {
+            for name in registry.names(kind) {
+                let strat = registry.get(kind, &name).unwrap();
+                assert_eq!(strat.get_decision(&state).err(), Some(GameError::NotDealt), "{}", name);
+            }
+        }
+    }
+
+    #[test]
+    /// Surrender only on the initial two cards of a table allowing it, hit otherwise
+    fn test_scripted_surrender() {
+        let late = |decisions: &[PlayerDecision]| GameSettings {
+            player_strat: QueuedDecisions::strat(decisions),
+            allow_late_surrender: true,
+            ..standard_settings()
+        };
+
+        // Initial two cards, half the bet back
+        let (results, won) = play_script(late(&[PlayerDecision::LateSurrender]), "10S 6H | 10D 7C");
+        assert!(results[0].1.p_surrender_late);
+        assert_eq!(won, -5);
+
+        // Table w/o surrender, 16 hit & bust
+        let settings = GameSettings { allow_late_surrender: false, ..late(&[PlayerDecision::LateSurrender]) };
+        let (results, won) = play_script(settings, "10S 6H | 10D 7C | 9C");
+        assert!(!results[0].1.p_surrender_late && results[0].1.p_bust);
+        assert_eq!(won, -10);
+        let (results, _) = play_script(late(&[PlayerDecision::EarlySurrender]), "10S 6H | 10D 7C | 9C");
+        assert!(!results[0].1.p_surrender_early && results[0].1.p_bust);
+
+        // Third card, hit again
+        let (results, won) = play_script(late(&[PlayerDecision::Hit, PlayerDecision::LateSurrender]), "10S 2H | 10D 7C | 4C | 10H");
+        assert!(!results[0].1.p_surrender_late && results[0].1.p_bust);
+        assert_eq!(won, -10);
+
+        // Split hand, hit again: left busts, right stands on 18 against 17
+        let decisions = [PlayerDecision::Split, PlayerDecision::LateSurrender];
+        let (results, won) = play_script(late(&decisions), "8S 8H | 10D 7C | 8D | 10C | 10S");
+        assert!(results.iter().all(|(_, state)| !state.p_surrender_late));
+        assert_eq!(results.iter().map(|(winner, _)| winner.clone()).collect::<Vec<_>>(), vec![Winner::Dealer, Winner::Player]);
+        assert_eq!(won, 0);
+    }
+
+    #[test]
+    fn test_scripted_soft_17() {
+        let script = "10S 8H | AD 6C | 2C";
+        let (_, won) = play_script(standard_settings(), "10S 8H | AD 6C");
+        assert_eq!(won, 10);
+        let rules = TableRules { hit_soft_17: true, ..TableRules::default() };
+        let (results, won) = play_script(GameSettings { rules, ..standard_settings() }, script);
+        assert_eq!(results[0].0, Winner::Dealer);
+        assert_eq!(won, -10);
+    }
+
+// |-------------------------|
+// |    Human Player Tests   |
+// |-------------------------|
+
+    // Console output kept for the test to read
+    #[derive(Clone, Default)]
+    struct SharedOutput(Arc<Mutex<Vec<u8>>>);
+
+    impl std::io::Write for SharedOutput {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+        fn flush(&mut self) -> std::io::Result<()> { Ok(()) }
+    }
+
+    #[test]
+    fn test_human_player() {
+        // Player 10 6, dealer 17, then a 4 for the player's hit
+        let play = |input: &'static str| {
+            let output = SharedOutput::default();
+            let console = Console::new(input.as_bytes(), output.clone()).with_count(true).shared();
+            let settings = GameSettings { player_strat: Arc::new(Box::new(HumanPlayer::new(console.clone()))), ..standard_settings() };
+            let mut game = Game::from_settings(Arc::new(settings.validate().unwrap()));
+            game.set_shoe(Box::new("10H 6D | 10S 7C | 4S".parse::<ScriptedShoe>().unwrap()));
+
+            let result = play_session(&mut game, &console).unwrap();
+            let text = String::from_utf8(output.0.lock().unwrap().clone()).unwrap();
+            (result, text)
+        };
+
+        // Only legal choices offered, anything else asked again
+        let (result, text) = play("25\np\nh\ns\nq\n");
+        assert_eq!(result, SessionResult { rounds: 1, start_bankroll: 1000, bankroll: 1025 });
+        assert!(text.contains("[h]it, [s]tand, [d]ouble >"));
+        assert!(text.contains("'p' isn't allowed here"));
+        assert!(text.contains("Your hand [10 ♥][6 ♦][4 ♠] (20): win +25"));
+        assert!(text.contains("Running count"));
+
+        // Input ends mid round: hand stands & the session ends
+        let (result, text) = play("\n");
+        assert_eq!(result, SessionResult { rounds: 1, start_bankroll: 1000, bankroll: 990 });
+        assert!(text.contains("(16): lose -10"));
+    }
+
+    #[test]
+    fn test_trainer() {
+        let card = |code: &str| code.parse::<Card>().unwrap();
+        let settings = GameSettings { rules: TableRules { dealer_peeks: true, ..TableRules::default() }, ..standard_settings() };
+        let mut trainer = Trainer::new(Arc::new(settings.validate().unwrap()), DealMode::Random, 3_000).unwrap();
+
+        let eights = Spot::new(card("8H"), card("8D"), card("10S"));
+        let soft = Spot::new(card("AH"), card("6D"), card("5S"));
+        let eleven = Spot::new(card("5H"), card("6D"), card("6S"));
+        assert_eq!(eights.category(), HandCategory::Pair);
+        assert_eq!(soft.category(), HandCategory::Soft);
+        assert_eq!(eleven.category(), HandCategory::Hard);
+        assert_eq!(trainer.legal_plays(&eleven), vec![PlayerDecision::Hit, PlayerDecision::Stand, PlayerDecision::Double]);
+        assert!(trainer.legal_plays(&eights).contains(&PlayerDecision::Split));
+
+        // Hit pair plays on as a total instead of busting
+        assert!(trainer.play_ev(&eights, PlayerDecision::Hit).unwrap() > -0.7);
+
+        // Mistake priced, right answer free
+        let grade = trainer.grade(&eleven, PlayerDecision::Stand).unwrap();
+        assert!(!grade.correct());
+        assert_eq!(grade.reference, PlayerDecision::Double);
+        assert!(grade.cost() > 0.3, "Standing on 11 vs 6 costs {}", grade.cost());
+        let grade = trainer.grade(&eleven, PlayerDecision::Double).unwrap();
+        assert!(grade.correct());
+        assert_eq!(grade.cost(), 0.0);
+        assert_eq!(trainer.grade(&eights, PlayerDecision::Split).unwrap().reference, PlayerDecision::Split);
+        assert_eq!(trainer.stats.hard.asked, 2);
+        assert_eq!(trainer.stats.hard.accuracy(), 50.0);
+        assert_eq!(trainer.stats.total().correct, 2);
+
+        // Never a natural to decide on
+        trainer.mode = DealMode::Hard;
+        assert!((0..200).all(|_| trainer.deal_spot().hand.value() != 21));
+
+        // Drill stops at the rounds asked for
+        let output = SharedOutput::default();
+        let console = Console::new("s\nh\nd\n".as_bytes(), output.clone()).shared();
+        let before = trainer.stats.total().asked;
+        trainer.drill(&console, Some(2)).unwrap();
+        assert_eq!(trainer.stats.total().asked, before + 2);
+        let text = String::from_utf8(output.0.lock().unwrap().clone()).unwrap();
+        assert!(text.contains("Reference: Basic Strategy"));
+        assert!(text.contains("Total"));
+    }
+
+    #[test]
+    fn test_count_drill() {
+        let card = |code: &str| code.parse::<Card>().unwrap();
+        assert_eq!(card_count(&HiLo, card("5H")).unwrap(), 1);
+        assert_eq!(card_count(&HiLo, card("KS")).unwrap(), -1);
+        assert_eq!(card_count(&KnockOut, card("7D")).unwrap(), 1);
+        assert_eq!(true_count(6, 156), 2.0);
+
+        let drill = DrillSettings { pace: std::time::Duration::ZERO, checkpoint_every: 10, ..Default::default() };
+        let run = |answers: String, table: bool, cards: Option<usize>| {
+            let console = Console::new(std::io::Cursor::new(answers), std::io::sink()).shared();
+            let mut count_drill = CountDrill::new(Arc::new(standard_settings().validate().unwrap()), DrillSettings { cards, ..drill.clone() });
+            match table {
+                true => count_drill.table_drill(&console).unwrap(),
+                false => count_drill.flip_drill(&console).unwrap(),
+            }
+        };
+
+        // Shoe shuffled from the settings' rng
+        let mut shoe = MultiDeck::new(6, false);
+        shoe.shuffle(&mut standard_settings().rng);
+        let running = (0..10).map(|_| card_count(&HiLo, shoe.draw().unwrap()).unwrap() as i32).sum::<i32>();
+        let score = run(format!("{}\n{}\n", running, true_count(running, 302) + 2.0), false, Some(10));
+        assert_eq!((score.checkpoints, score.running_correct, score.true_correct), (1, 1, 0));
+        assert_eq!(score.cards, 10);
+
+        // First round played, checked against the engine's own count
+        let mut game = Game::from_settings(Arc::new(standard_settings().validate().unwrap()));
+        game.deal(game.init_bet).unwrap();
+        game.play_hand().unwrap();
+        let answer = format!("{}\n{}\n", game.running_count, true_count(game.running_count, 312 - game.played_cards.len()));
+        let score = run(answer, true, Some(1));
+        assert_eq!((score.checkpoints, score.running_correct, score.true_correct), (1, 1, 1));
+        assert_eq!(score.cards, game.played_cards.len() as u64);
+
+        // Quitting ends the drill without a score
+        assert_eq!(run("q\n".to_string(), false, Some(10)).checkpoints, 0);
+    }
+
+// |-------------------------|
+// |  Insurance Strat Tests  |
+// |-------------------------|
+
+    #[test]
+    fn test_no_insurance() {
+        let insurance_strat: Option<Strat> = Some(Arc::new(Box::new(NoInsurance)));
         let game = standard_game(None, None, None, None, insurance_strat);
-        let decision = game.player.decide_insurance(game.get_state(&Hand::new(10)));
+        let decision = game.player.decide_insurance(&game.view(Some(&Hand::new(10)))).unwrap();
         let expected_decision = false;
         assert_eq!(expected_decision, decision);
     }
 
 
-
 // |-------------------------|
 // |   Card Counting Tests   |
 // |-------------------------|
@@ -748,18 +2530,25 @@ mod tests {
             max_splits: 3,
             contains_blank: true,
             init_bet: 10,
+            bankroll: 1000,
             dealer_cutoff: 17,
-            dealer_strat: Arc::new(PlayingStrat::Dealer(Box::new(dealer_hard_17))),
-            player_strat: Arc::new(PlayingStrat::Player(Box::new(basic_strategy))),
-            betting_strat: Arc::new(Box::new(constant_bet)),
-            counting_strat: Arc::new(Box::new(update_hi_lo)),
-            insurance_strat: Arc::new(Box::new(no_insurance)),
+            dealer_strat: Arc::new(Box::new(DealerPlay)),
+            player_strat: Arc::new(Box::new(BasicStrategy)),
+            betting_strat: Arc::new(Box::new(ConstantBet)),
+            counting_strat: Arc::new(Box::new(HiLo)),
+            insurance_strat: Arc::new(Box::new(NoInsurance)),
             allow_early_surrender: false,
             allow_late_surrender: false,
+            rules: TableRules::default(),
+            table_min: 2,
+            table_max: 500,
+            bet_increment: 1,
+            limit_policy: LimitPolicy::Clamp,
             echo: false,
             rng,
+            seed: Some(2),
         };
-        let settings = Arc::new(settings);
+        let settings = Arc::new(settings.validate().unwrap());
         let mut test_game = Game::from_settings(settings.clone());
 
         
@@ -778,7 +2567,7 @@ mod tests {
         ];
 
         // Test Counts
-        high_cards.iter().for_each(|card| test_game.update_count(card));
+        count_cards(&mut test_game, &high_cards);
         let expected_running = -2;
         let expected_true = expected_running as f64/deck_count as f64;
         assert_eq!(expected_running, test_game.running_count);
@@ -800,7 +2589,7 @@ mod tests {
         ];
 
         // Test Counts
-        low_cards.iter().for_each(|card| test_game.update_count(card));
+        count_cards(&mut test_game, &low_cards);
         let expected_running = 0;
         assert_eq!(expected_running, test_game.running_count);
 
@@ -824,7 +2613,7 @@ mod tests {
         ];
 
         // Test Counts
-        neutral_cards.iter().for_each(|card| test_game.update_count(card));
+        count_cards(&mut test_game, &neutral_cards);
         let expected_running = 0;
         assert_eq!(expected_running, test_game.running_count);
         
@@ -844,18 +2633,25 @@ mod tests {
             max_splits: 3,
             contains_blank: true,
             init_bet: 10,
+            bankroll: 1000,
             dealer_cutoff: 17,
-            dealer_strat: Arc::new(PlayingStrat::Dealer(Box::new(dealer_hard_17))),
-            player_strat: Arc::new(PlayingStrat::Player(Box::new(basic_strategy))),
-            betting_strat: Arc::new(Box::new(constant_bet)),
-            counting_strat: Arc::new(Box::new(update_knock_out)),
-            insurance_strat: Arc::new(Box::new(no_insurance)),
+            dealer_strat: Arc::new(Box::new(DealerPlay)),
+            player_strat: Arc::new(Box::new(BasicStrategy)),
+            betting_strat: Arc::new(Box::new(ConstantBet)),
+            counting_strat: Arc::new(Box::new(KnockOut)),
+            insurance_strat: Arc::new(Box::new(NoInsurance)),
             allow_early_surrender: false,
             allow_late_surrender: false,
+            rules: TableRules::default(),
+            table_min: 2,
+            table_max: 500,
+            bet_increment: 1,
+            limit_policy: LimitPolicy::Clamp,
             echo: false,
             rng,
+            seed: Some(2),
         };
-        let settings = Arc::new(settings);
+        let settings = Arc::new(settings.validate().unwrap());
         let mut test_game = Game::from_settings(settings.clone());
 
         
@@ -889,7 +2685,7 @@ mod tests {
         ];
 
         // Test Counts
-        high_cards.iter().for_each(|card| test_game.update_count(card));
+        count_cards(&mut test_game, &high_cards);
         let expected_running = -(high_cards.len() as i32);
         let expected_true = expected_running as f64/deck_count as f64;
         assert_eq!(expected_running, test_game.running_count);
@@ -931,7 +2727,7 @@ mod tests {
         ];
 
         // Test Counts
-        low_cards.iter().for_each(|card| test_game.update_count(card));
+        count_cards(&mut test_game, &low_cards);
         let expected_running = expected_running + low_cards.len() as i32;
         assert_eq!(expected_running, test_game.running_count);
 
@@ -950,11 +2746,24 @@ mod tests {
         ];
 
         // Test Counts
-        neutral_cards.iter().for_each(|card| test_game.update_count(card));
+        count_cards(&mut test_game, &neutral_cards);
         // Running count shouldn't change b/c neutral cards
         assert_eq!(expected_running, test_game.running_count);
     }
 
+    // Counted card by card as drawn, same as recounting everything played since the shuffle
+    #[test]
+    fn test_incremental_count() {
+        for counting_strat in [Arc::new(Box::new(HiLo) as Box<dyn StrategyFunc>), Arc::new(Box::new(OmegaTwo))] {
+            let mut test_game = standard_game(None, None, None, Some(counting_strat.clone()), None);
+            for _ in 0..200 {
+                test_game.play_round().unwrap();
+                let recount = test_game.played_cards.iter().map(|card| card_count(counting_strat.as_ref().as_ref(), *card).unwrap() as i32).sum::<i32>();
+                assert_eq!(test_game.running_count, recount);
+            }
+        }
+    }
+
     #[test]
     fn test_omega2_count(){
         let deck_count = 4;
@@ -969,18 +2778,25 @@ mod tests {
             max_splits: 3,
             contains_blank: true,
             init_bet: 10,
+            bankroll: 1000,
             dealer_cutoff: 17,
-            dealer_strat: Arc::new(PlayingStrat::Dealer(Box::new(dealer_hard_17))),
-            player_strat: Arc::new(PlayingStrat::Player(Box::new(basic_strategy))),
-            betting_strat: Arc::new(Box::new(constant_bet)),
-            counting_strat: Arc::new(Box::new(omega_2)),
-            insurance_strat: Arc::new(Box::new(no_insurance)),
+            dealer_strat: Arc::new(Box::new(DealerPlay)),
+            player_strat: Arc::new(Box::new(BasicStrategy)),
+            betting_strat: Arc::new(Box::new(ConstantBet)),
+            counting_strat: Arc::new(Box::new(OmegaTwo)),
+            insurance_strat: Arc::new(Box::new(NoInsurance)),
             allow_early_surrender: false,
             allow_late_surrender: false,
+            rules: TableRules::default(),
+            table_min: 2,
+            table_max: 500,
+            bet_increment: 1,
+            limit_policy: LimitPolicy::Clamp,
             echo: false,
             rng,
+            seed: Some(2),
         };
-        let settings = Arc::new(settings);
+        let settings = Arc::new(settings.validate().unwrap());
         let mut test_game = Game::from_settings(settings.clone());
 
         
@@ -1010,7 +2826,7 @@ mod tests {
         ];
 
         // Test Counts
-        minus_two_cards.iter().for_each(|card| test_game.update_count(card));
+        count_cards(&mut test_game, &minus_two_cards);
         let expected_running =  multiplier * minus_two_cards.len() as i32;
         let expected_true = expected_running as f64/deck_count as f64;
         assert_eq!(expected_running, test_game.running_count);
@@ -1028,7 +2844,7 @@ mod tests {
         ];
 
         // Test Counts
-        minus_one_cards.iter().for_each(|card| test_game.update_count(card));
+        count_cards(&mut test_game, &minus_one_cards);
         let expected_running = expected_running + (multiplier * minus_one_cards.len() as i32);
         let expected_true = expected_running as f64/deck_count as f64;
         assert_eq!(expected_running, test_game.running_count);
@@ -1051,7 +2867,7 @@ mod tests {
         ];
 
         // Test Counts
-        neutral_cards.iter().for_each(|card| test_game.update_count(card));
+        count_cards(&mut test_game, &neutral_cards);
         // Running/True count shouldn't change b/c neutral cards
         assert_eq!(expected_running, test_game.running_count);
         assert_eq!(expected_true, test_game.true_count);
@@ -1077,7 +2893,7 @@ mod tests {
         ];
 
         // Test Counts
-        plus_one_cards.iter().for_each(|card| test_game.update_count(card));
+        count_cards(&mut test_game, &plus_one_cards);
         let expected_running = expected_running + (multiplier * plus_one_cards.len() as i32);
         let expected_true = expected_running as f64/deck_count as f64;
         assert_eq!(expected_running, test_game.running_count);
@@ -1104,7 +2920,7 @@ mod tests {
         ];
 
         // Test Counts
-        plus_two_cards.iter().for_each(|card| test_game.update_count(card));
+        count_cards(&mut test_game, &plus_two_cards);
         let expected_running = expected_running + (multiplier * plus_two_cards.len() as i32);
         let expected_true = expected_running as f64/deck_count as f64;
         assert_eq!(expected_running, test_game.running_count);